@@ -0,0 +1,28 @@
+use tauri::State;
+use crate::db::AppDatabase;
+use crate::models::baseline::{Baseline, BaselineVariance};
+use crate::services::baseline_service;
+
+#[tauri::command]
+pub fn list_baselines(db: State<AppDatabase>, sprint_id: Option<i64>) -> Result<Vec<Baseline>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    baseline_service::list_baselines(&conn, sprint_id)
+}
+
+#[tauri::command]
+pub fn save_baseline(db: State<AppDatabase>, name: String, sprint_id: Option<i64>) -> Result<Baseline, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    baseline_service::save_baseline(&conn, &name, sprint_id)
+}
+
+#[tauri::command]
+pub fn delete_baseline(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    baseline_service::delete_baseline(&conn, id)
+}
+
+#[tauri::command]
+pub fn get_baseline_variance(db: State<AppDatabase>, baseline_id: i64) -> Result<BaselineVariance, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    baseline_service::get_variance(&conn, baseline_id)
+}
@@ -1,7 +1,11 @@
 use tauri::State;
 use crate::db::AppDatabase;
-use crate::models::calendar::{CalendarEvent, CalendarResource, DeveloperWorkload};
-use crate::services::{schedule_service, holiday_service};
+use crate::models::calendar::{
+    AvailabilityEstimate, CalendarEvent, CalendarOverride, CalendarResource, CreateCalendarOverrideDto,
+    CreateRegionCalendarDto, DeveloperWorkload, HolidayYearSummary, LevelingPlan, RegionCalendar, ScheduleViolation,
+    UpdateCalendarOverrideDto, UpdateRegionCalendarDto,
+};
+use crate::services::{conflict_service, reschedule_service, schedule_service, holiday_service};
 
 #[tauri::command]
 pub fn get_calendar_events(
@@ -9,15 +13,16 @@ pub fn get_calendar_events(
     start_date: String,
     end_date: String,
     developer_id: Option<i64>,
+    event_types: Option<Vec<String>>,
 ) -> Result<Vec<CalendarEvent>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    schedule_service::get_calendar_events(&conn, &start_date, &end_date, developer_id)
+    schedule_service::get_calendar_events(&conn, &start_date, &end_date, developer_id, event_types.as_deref())
 }
 
 #[tauri::command]
-pub fn get_calendar_resources(db: State<AppDatabase>) -> Result<Vec<CalendarResource>, String> {
+pub fn get_calendar_resources(db: State<AppDatabase>, team_id: Option<i64>) -> Result<Vec<CalendarResource>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    schedule_service::get_calendar_resources(&conn)
+    schedule_service::get_calendar_resources(&conn, team_id)
 }
 
 #[tauri::command]
@@ -33,7 +38,137 @@ pub fn get_developer_workload(
 }
 
 #[tauri::command]
-pub fn sync_holidays(db: State<AppDatabase>, year: i32) -> Result<usize, String> {
+pub fn scan_schedule_violations(
+    db: State<AppDatabase>,
+    start_date: String,
+    end_date: String,
+    team_id: Option<i64>,
+) -> Result<Vec<ScheduleViolation>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    conflict_service::scan_violations(&conn, &start_date, &end_date, team_id)
+}
+
+/// Preview resource leveling; save the returned `updates` with `batch_update_tasks`.
+#[tauri::command]
+pub fn level_resources(
+    db: State<AppDatabase>,
+    start_date: String,
+    end_date: String,
+    team_id: Option<i64>,
+) -> Result<LevelingPlan, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    reschedule_service::level_resources(&conn, &start_date, &end_date, team_id)
+}
+
+fn parse_from_date(from_date: Option<String>) -> Result<chrono::NaiveDate, String> {
+    match from_date {
+        Some(d) => chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|e| e.to_string()),
+        None => Ok(chrono::Local::now().date_naive()),
+    }
+}
+
+#[tauri::command]
+pub fn estimate_availability(
+    db: State<AppDatabase>,
+    developer_id: i64,
+    hours: f64,
+    from_date: Option<String>,
+) -> Result<AvailabilityEstimate, String> {
+    let from = parse_from_date(from_date)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    schedule_service::estimate_earliest_finish(&conn, developer_id, hours, from)
+}
+
+#[tauri::command]
+pub fn find_soonest_available(
+    db: State<AppDatabase>,
+    hours: f64,
+    from_date: Option<String>,
+    team_id: Option<i64>,
+) -> Result<Vec<AvailabilityEstimate>, String> {
+    let from = parse_from_date(from_date)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    schedule_service::find_soonest_available(&conn, hours, from, team_id)
+}
+
+/// `region` defaults to `CN`; other regions sync from Nager.Date.
+#[tauri::command]
+pub fn sync_holidays(
+    db: State<AppDatabase>,
+    year: i32,
+    source: Option<String>,
+    region: Option<String>,
+) -> Result<usize, String> {
+    let region = region.unwrap_or_else(|| holiday_service::DEFAULT_REGION.to_string());
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::sync_holidays_with_source(&conn, &region, year, source.as_deref())
+}
+
+#[tauri::command]
+pub fn import_holidays_from_file(
+    db: State<AppDatabase>,
+    path: String,
+    region: Option<String>,
+) -> Result<Vec<HolidayYearSummary>, String> {
+    let region = region.unwrap_or_else(|| holiday_service::DEFAULT_REGION.to_string());
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::import_holidays_from_file(&conn, &region, &path)
+}
+
+#[tauri::command]
+pub fn list_cached_holiday_years(db: State<AppDatabase>) -> Result<Vec<HolidayYearSummary>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::list_cached_years(&conn)
+}
+
+#[tauri::command]
+pub fn list_region_calendars(db: State<AppDatabase>) -> Result<Vec<RegionCalendar>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::list_calendars(&conn)
+}
+
+#[tauri::command]
+pub fn create_region_calendar(db: State<AppDatabase>, dto: CreateRegionCalendarDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::create_calendar(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn update_region_calendar(db: State<AppDatabase>, dto: UpdateRegionCalendarDto) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::update_calendar(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_region_calendar(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::delete_calendar(&conn, id)
+}
+
+#[tauri::command]
+pub fn list_calendar_overrides(
+    db: State<AppDatabase>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<CalendarOverride>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::list_overrides(&conn, start_date.as_deref(), end_date.as_deref())
+}
+
+#[tauri::command]
+pub fn create_calendar_override(db: State<AppDatabase>, dto: CreateCalendarOverrideDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::create_override(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn update_calendar_override(db: State<AppDatabase>, dto: UpdateCalendarOverrideDto) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::update_override(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_calendar_override(db: State<AppDatabase>, id: i64) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    holiday_service::sync_holidays_for_year(&conn, year)
+    holiday_service::delete_override(&conn, id)
 }
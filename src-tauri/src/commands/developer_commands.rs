@@ -1,7 +1,9 @@
 use tauri::State;
 use crate::db::AppDatabase;
-use crate::models::developer::{Developer, CreateDeveloperDto, UpdateDeveloperDto};
-use crate::services::developer_service;
+use crate::models::developer::{Developer, CreateDeveloperDto, UpdateDeveloperDto, OffboardDeveloperDto, OffboardReport};
+use crate::models::capacity::{CapacityRule, CreateCapacityRuleDto, DailyCapacity};
+use crate::models::calendar::OvertimeConfig;
+use crate::services::{capacity_service, developer_service, holiday_service};
 
 #[tauri::command]
 pub fn list_developers(db: State<AppDatabase>) -> Result<Vec<Developer>, String> {
@@ -32,3 +34,56 @@ pub fn delete_developer(db: State<AppDatabase>, id: i64) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     developer_service::delete_developer(&conn, id)
 }
+
+#[tauri::command]
+pub fn offboard_developer(db: State<AppDatabase>, dto: OffboardDeveloperDto) -> Result<OffboardReport, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    developer_service::offboard_developer(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn list_capacity_rules(db: State<AppDatabase>, developer_id: i64) -> Result<Vec<CapacityRule>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    capacity_service::list_rules(&conn, developer_id)
+}
+
+#[tauri::command]
+pub fn create_capacity_rule(db: State<AppDatabase>, dto: CreateCapacityRuleDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    capacity_service::create_rule(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_capacity_rule(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    capacity_service::delete_rule(&conn, id)
+}
+
+#[tauri::command]
+pub fn get_effective_capacity(
+    db: State<AppDatabase>,
+    developer_id: i64,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<DailyCapacity>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    capacity_service::get_effective_capacity(&conn, developer_id, &start_date, &end_date)
+}
+
+/// The developer's own overtime rules; `None` means they follow the global setting.
+#[tauri::command]
+pub fn get_developer_overtime(db: State<AppDatabase>, developer_id: i64) -> Result<Option<OvertimeConfig>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::get_developer_overtime(&conn, developer_id)
+}
+
+/// Set the developer's own overtime rules; pass `None` to follow the global setting again.
+#[tauri::command]
+pub fn set_developer_overtime(
+    db: State<AppDatabase>,
+    developer_id: i64,
+    config: Option<OvertimeConfig>,
+) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    holiday_service::set_developer_overtime(&conn, developer_id, config.as_ref())
+}
@@ -1,44 +1,58 @@
 use crate::db::AppDatabase;
-use crate::excel::reader::{read_excel_info, read_sheet_as_maps, read_sheet_data, ExcelFileInfo};
+use crate::excel::delimited::read_pasted_data;
+use crate::excel::reader::{read_excel_info, read_sheet_as_maps, read_sheet_data, rows_to_maps, ExcelFileInfo};
 use crate::excel::smart_matcher::{match_columns, score_sheets, ColumnMatch, SheetScore};
-use crate::excel::writer::export_tasks_to_excel;
+use crate::excel::writer::{export_tasks_to_csv, export_tasks_to_excel};
 use crate::models::task::TaskFilter;
+use crate::services::import_export_service;
 use crate::services::import_export_service::{
-    detect_import_conflicts, import_tasks_from_rows, ImportConflict, ImportResult,
+    detect_import_conflicts, dry_run_import, import_tasks_from_rows, ImportConflict, ImportDryRun,
+    ImportResult, ImportRollback, ImportSource,
 };
+use crate::services::settings_service::get_excel_template_config;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Command;
 use tauri::State;
 
+/// The header row set in the Excel template; when unset the header is detected.
+fn configured_header_row(db: &AppDatabase) -> Result<Option<usize>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    Ok(get_excel_template_config(&conn)?
+        .and_then(|c| c.header_row)
+        .and_then(|row| usize::try_from(row).ok()))
+}
+
 #[tauri::command]
-pub fn analyze_excel(file_path: String) -> Result<ExcelFileInfo, String> {
-    read_excel_info(&file_path)
+pub fn analyze_excel(db: State<AppDatabase>, file_path: String) -> Result<ExcelFileInfo, String> {
+    read_excel_info(&file_path, configured_header_row(&db)?)
 }
 
 #[tauri::command]
-pub fn score_excel_sheets(file_path: String) -> Result<Vec<SheetScore>, String> {
-    let info = read_excel_info(&file_path)?;
+pub fn score_excel_sheets(db: State<AppDatabase>, file_path: String) -> Result<Vec<SheetScore>, String> {
+    let info = read_excel_info(&file_path, configured_header_row(&db)?)?;
     Ok(score_sheets(&info.sheets))
 }
 
 #[tauri::command]
 pub fn match_excel_columns(
+    db: State<AppDatabase>,
     file_path: String,
     sheet_name: String,
 ) -> Result<Vec<ColumnMatch>, String> {
-    let (headers, rows) = read_sheet_data(&file_path, &sheet_name)?;
+    let (headers, rows) = read_sheet_data(&file_path, &sheet_name, configured_header_row(&db)?)?;
     let sample: Vec<Vec<String>> = rows.into_iter().take(20).collect();
     Ok(match_columns(&headers, &sample))
 }
 
 #[tauri::command]
 pub fn preview_excel_import(
+    db: State<AppDatabase>,
     file_path: String,
     sheet_name: String,
     limit: Option<usize>,
 ) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
-    let (headers, rows) = read_sheet_data(&file_path, &sheet_name)?;
+    let (headers, rows) = read_sheet_data(&file_path, &sheet_name, configured_header_row(&db)?)?;
     let limited_rows: Vec<Vec<String>> = rows.into_iter().take(limit.unwrap_or(20)).collect();
     Ok((headers, limited_rows))
 }
@@ -51,10 +65,24 @@ pub fn import_excel(
     column_mapping: HashMap<String, String>,
     conflict_mode: Option<String>,
 ) -> Result<ImportResult, String> {
-    let (_, rows) = read_sheet_as_maps(&file_path, &sheet_name)?;
+    let (_, rows) = read_sheet_as_maps(&file_path, &sheet_name, configured_header_row(&db)?)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mode = conflict_mode.unwrap_or_else(|| "create_new".to_string());
+    import_tasks_from_rows(&conn, &rows, &column_mapping, &mode, &ImportSource::file(&file_path, &sheet_name))
+}
+
+#[tauri::command]
+pub fn dry_run_excel_import(
+    db: State<AppDatabase>,
+    file_path: String,
+    sheet_name: String,
+    column_mapping: HashMap<String, String>,
+    conflict_mode: Option<String>,
+) -> Result<ImportDryRun, String> {
+    let (_, rows) = read_sheet_as_maps(&file_path, &sheet_name, configured_header_row(&db)?)?;
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let mode = conflict_mode.unwrap_or_else(|| "create_new".to_string());
-    import_tasks_from_rows(&conn, &rows, &column_mapping, &mode)
+    dry_run_import(&conn, &rows, &column_mapping, &mode)
 }
 
 #[tauri::command]
@@ -64,7 +92,7 @@ pub fn detect_excel_conflicts(
     sheet_name: String,
     column_mapping: HashMap<String, String>,
 ) -> Result<Vec<ImportConflict>, String> {
-    let (_, rows) = read_sheet_as_maps(&file_path, &sheet_name)?;
+    let (_, rows) = read_sheet_as_maps(&file_path, &sheet_name, configured_header_row(&db)?)?;
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     detect_import_conflicts(&conn, &rows, &column_mapping)
 }
@@ -79,6 +107,56 @@ pub fn export_excel(
     export_tasks_to_excel(&conn, &file_path, &filter)
 }
 
+#[tauri::command]
+pub fn export_csv(
+    db: State<AppDatabase>,
+    file_path: String,
+    filter: TaskFilter,
+) -> Result<String, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    export_tasks_to_csv(&conn, &file_path, &filter)
+}
+
+#[tauri::command]
+pub fn preview_pasted_table(text: String) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    read_pasted_data(&text)
+}
+
+#[tauri::command]
+pub fn match_pasted_columns(text: String) -> Result<Vec<ColumnMatch>, String> {
+    let (headers, rows) = read_pasted_data(&text)?;
+    let sample: Vec<Vec<String>> = rows.into_iter().take(20).collect();
+    Ok(match_columns(&headers, &sample))
+}
+
+#[tauri::command]
+pub fn import_pasted_table(
+    db: State<AppDatabase>,
+    text: String,
+    column_mapping: HashMap<String, String>,
+    conflict_mode: Option<String>,
+) -> Result<ImportResult, String> {
+    let (headers, rows) = read_pasted_data(&text)?;
+    let (_, rows) = rows_to_maps(headers, &rows);
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mode = conflict_mode.unwrap_or_else(|| "create_new".to_string());
+    import_tasks_from_rows(&conn, &rows, &column_mapping, &mode, &ImportSource::pasted())
+}
+
+#[tauri::command]
+pub fn dry_run_pasted_import(
+    db: State<AppDatabase>,
+    text: String,
+    column_mapping: HashMap<String, String>,
+    conflict_mode: Option<String>,
+) -> Result<ImportDryRun, String> {
+    let (headers, rows) = read_pasted_data(&text)?;
+    let (_, rows) = rows_to_maps(headers, &rows);
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mode = conflict_mode.unwrap_or_else(|| "create_new".to_string());
+    dry_run_import(&conn, &rows, &column_mapping, &mode)
+}
+
 #[tauri::command]
 pub fn reveal_in_folder(file_path: String) -> Result<(), String> {
     let path = PathBuf::from(&file_path);
@@ -128,7 +206,8 @@ pub fn reveal_in_folder(file_path: String) -> Result<(), String> {
 pub fn get_import_history(db: State<AppDatabase>) -> Result<Vec<serde_json::Value>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let mut stmt = conn.prepare(
-        "SELECT id, file_name, file_path, import_date, sheet_name, column_mapping, rows_imported FROM import_history ORDER BY import_date DESC"
+        "SELECT id, file_name, file_path, import_date, sheet_name, column_mapping, rows_imported, rows_updated, rolled_back_at \
+         FROM import_history ORDER BY import_date DESC, id DESC"
     ).map_err(|e| e.to_string())?;
     let rows = stmt
         .query_map([], |row| {
@@ -140,9 +219,17 @@ pub fn get_import_history(db: State<AppDatabase>) -> Result<Vec<serde_json::Valu
                 "sheet_name": row.get::<_, Option<String>>(4)?,
                 "column_mapping": row.get::<_, Option<String>>(5)?,
                 "rows_imported": row.get::<_, i64>(6)?,
+                "rows_updated": row.get::<_, i64>(7)?,
+                "rolled_back_at": row.get::<_, Option<String>>(8)?,
             }))
         })
         .map_err(|e| e.to_string())?;
     rows.collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub fn rollback_import(db: State<AppDatabase>, import_id: i64) -> Result<ImportRollback, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    import_export_service::rollback_import(&conn, import_id)
+}
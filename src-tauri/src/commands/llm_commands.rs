@@ -5,14 +5,16 @@ use crate::llm::adapter::{
 };
 use crate::llm::openai_adapter::OpenAiCompatibleAdapter;
 use crate::models::task::{TaskFilter, UpdateTaskDto};
-use crate::services::{holiday_service, llm_service, settings_service};
+use crate::services::assignment_service::{
+    self, build_existing_daily_load, ALLOC_EPSILON, MAX_SCHEDULE_DAYS_SCAN,
+};
+use crate::services::date_service::{self, parse_ymd, YearHint};
+use crate::services::{capacity_service, holiday_service, llm_service, settings_service};
 use chrono::{Duration, Local, NaiveDate};
 use std::collections::{HashMap, HashSet};
 use tauri::State;
 
 const DEFAULT_HOURS_PER_DAY: f64 = 8.0;
-const ALLOC_EPSILON: f64 = 1e-6;
-const MAX_SCHEDULE_DAYS_SCAN: i64 = 3650;
 
 #[tauri::command]
 pub fn llm_chat(
@@ -208,108 +210,6 @@ struct ProposedAssignment {
     reasoning: String,
 }
 
-fn parse_ymd(date: &str) -> Option<NaiveDate> {
-    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
-}
-
-fn next_workday(conn: &rusqlite::Connection, mut date: NaiveDate) -> NaiveDate {
-    let mut scanned = 0i64;
-    while scanned < MAX_SCHEDULE_DAYS_SCAN {
-        if holiday_service::is_workday(conn, &date) {
-            return date;
-        }
-        date += Duration::days(1);
-        scanned += 1;
-    }
-    date
-}
-
-fn list_workdays(conn: &rusqlite::Connection, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
-    let mut days = Vec::new();
-    let mut d = start;
-    while d <= end {
-        if holiday_service::is_workday(conn, &d) {
-            days.push(d);
-        }
-        d += Duration::days(1);
-    }
-    days
-}
-
-fn build_existing_daily_load(
-    conn: &rusqlite::Connection,
-    all_tasks: &[crate::models::task::Task],
-    exclude_task_ids: &HashSet<i64>,
-) -> HashMap<(i64, NaiveDate), f64> {
-    let mut load: HashMap<(i64, NaiveDate), f64> = HashMap::new();
-
-    for t in all_tasks {
-        if exclude_task_ids.contains(&t.id) {
-            continue;
-        }
-        if matches!(t.status.as_deref(), Some("已取消")) {
-            continue;
-        }
-
-        let Some(owner_id) = t.owner_id else { continue };
-        let Some(start_str) = t.planned_start.as_deref() else {
-            continue;
-        };
-        let Some(end_str) = t.planned_end.as_deref() else {
-            continue;
-        };
-        let Some(start) = parse_ymd(start_str) else {
-            continue;
-        };
-        let Some(end) = parse_ymd(end_str) else {
-            continue;
-        };
-        if end < start {
-            continue;
-        }
-
-        let hours = t.planned_hours.unwrap_or(0.0);
-        if hours <= ALLOC_EPSILON {
-            continue;
-        }
-
-        let workdays = list_workdays(conn, start, end);
-        if workdays.is_empty() {
-            continue;
-        }
-
-        let daily = hours / (workdays.len() as f64);
-        for d in workdays {
-            *load.entry((owner_id, d)).or_insert(0.0) += daily;
-        }
-    }
-
-    load
-}
-
-fn choose_fallback_developer_id(
-    developers: &[crate::models::developer::Developer],
-    hours_per_day: f64,
-) -> Option<i64> {
-    developers
-        .iter()
-        .filter(|d| d.is_active)
-        .max_by(|a, b| {
-            let ah = if a.max_hours_per_day > 0.0 {
-                a.max_hours_per_day
-            } else {
-                hours_per_day
-            };
-            let bh = if b.max_hours_per_day > 0.0 {
-                b.max_hours_per_day
-            } else {
-                hours_per_day
-            };
-            ah.partial_cmp(&bh).unwrap_or(std::cmp::Ordering::Equal)
-        })
-        .map(|d| d.id)
-}
-
 fn normalize_assignments(
     conn: &rusqlite::Connection,
     selected_tasks: &[crate::models::task::Task],
@@ -325,20 +225,36 @@ fn normalize_assignments(
     let today = Local::now().date_naive();
     holiday_service::ensure_holidays_cached(conn, &today, &(today + Duration::days(730)));
 
+    let calendars = holiday_service::DeveloperCalendars::load(conn).unwrap_or_else(|e| {
+        log::warn!("Failed to load developer calendars: {}", e);
+        Default::default()
+    });
     let selected_ids: HashSet<i64> = selected_tasks.iter().map(|t| t.id).collect();
-    let mut load_map = build_existing_daily_load(conn, all_tasks, &selected_ids);
-
-    let mut dev_capacity: HashMap<i64, f64> = HashMap::new();
-    for d in developers.iter().filter(|d| d.is_active) {
-        let cap = if d.max_hours_per_day > 0.0 {
-            d.max_hours_per_day
-        } else {
-            hours_per_day
-        };
-        dev_capacity.insert(d.id, cap.max(0.1));
-    }
-
-    let fallback_dev_id = choose_fallback_developer_id(developers, hours_per_day);
+    let pinned = assignment_service::load_pinned_hours(conn).unwrap_or_else(|e| {
+        log::warn!("Failed to load pinned daily hours: {}", e);
+        Default::default()
+    });
+    let mut load_map = build_existing_daily_load(&calendars, all_tasks, &selected_ids, &pinned);
+
+    let active_developers: Vec<crate::models::developer::Developer> =
+        developers.iter().filter(|d| d.is_active).cloned().collect();
+    // Every active developer keeps at least a sliver of base capacity, so tasks can
+    // still be laid out for someone configured with 0 hours.
+    let dev_capacity: HashMap<i64, capacity_service::CapacityProfile> =
+        capacity_service::load_profiles(conn, &active_developers, hours_per_day)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load capacity rules: {}", e);
+                active_developers
+                    .iter()
+                    .map(|d| {
+                        let base = capacity_service::base_hours(d, hours_per_day);
+                        (d.id, capacity_service::CapacityProfile::new(base, Vec::new()))
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .map(|(id, profile)| (id, profile.with_min_base(0.1)))
+            .collect();
 
     let mut proposal_map: HashMap<i64, ProposedAssignment> = HashMap::new();
     for p in proposals {
@@ -364,15 +280,34 @@ fn normalize_assignments(
         };
         let proposal = proposal_map.get(&task_id);
 
+        // Keep the proposed owner when they are active and hold every required skill;
+        // otherwise take the best-scoring developer against the load booked so far.
         let proposed_dev = proposal.map(|p| p.developer_id).or(task.owner_id);
-        let developer_id = proposed_dev
-            .filter(|id| dev_capacity.contains_key(id))
-            .or(fallback_dev_id)
-            .unwrap_or(0);
-
-        if developer_id <= 0 {
-            continue;
-        }
+        let proposed_fits = |id: &i64| {
+            dev_capacity.contains_key(id)
+                && active_developers.iter().any(|d| {
+                    d.id == *id && assignment_service::skill_fit(d, &task.required_skills).1.is_empty()
+                })
+        };
+        let mut replaced = false;
+        let developer_id = match proposed_dev.filter(proposed_fits) {
+            Some(id) => id,
+            None => {
+                replaced = proposed_dev.is_some();
+                let ranked = assignment_service::rank_candidates(
+                    &calendars,
+                    task,
+                    &active_developers,
+                    &dev_capacity,
+                    &load_map,
+                    today,
+                );
+                match ranked.first() {
+                    Some(best) => best.developer_id,
+                    None => continue,
+                }
+            }
+        };
 
         let mut cursor = proposal
             .and_then(|p| p.suggested_start)
@@ -382,12 +317,10 @@ fn normalize_assignments(
         if cursor < today {
             cursor = today;
         }
-        cursor = next_workday(conn, cursor);
+        let calendar = calendars.of(developer_id);
+        cursor = calendar.next_workday(cursor);
 
-        let max_h = dev_capacity
-            .get(&developer_id)
-            .copied()
-            .unwrap_or(hours_per_day.max(0.1));
+        let profile = dev_capacity.get(&developer_id);
 
         let mut remaining = task.planned_hours.unwrap_or(0.0).max(0.0);
         let mut first_day: Option<NaiveDate> = None;
@@ -399,12 +332,15 @@ fn normalize_assignments(
             last_day = Some(cursor);
         } else {
             while remaining > ALLOC_EPSILON && scanned < MAX_SCHEDULE_DAYS_SCAN {
-                if !holiday_service::is_workday(conn, &cursor) {
+                if !calendar.is_workday(&cursor) {
                     cursor += Duration::days(1);
                     scanned += 1;
                     continue;
                 }
 
+                let max_h = profile
+                    .map(|p| p.hours_on(&cursor))
+                    .unwrap_or(hours_per_day.max(0.1));
                 let key = (developer_id, cursor);
                 let used = *load_map.get(&key).unwrap_or(&0.0);
                 let capacity = (max_h - used).max(0.0);
@@ -424,12 +360,15 @@ fn normalize_assignments(
             }
         }
 
-        let start = first_day.unwrap_or_else(|| next_workday(conn, today));
+        let start = first_day.unwrap_or_else(|| calendar.next_workday(today));
         let end = last_day.unwrap_or(start);
 
         let mut reasoning = proposal
             .map(|p| p.reasoning.clone())
             .unwrap_or_else(|| "按容量自动分配".to_string());
+        if replaced {
+            reasoning.push_str("（原建议人员不可用或技能不足，已按评分改派）");
+        }
         reasoning.push_str("（已按每日容量归一化）");
 
         result.push(ScheduleSuggestion {
@@ -444,6 +383,16 @@ fn normalize_assignments(
     result
 }
 
+/// Year of a suggested date written without one: the task's sprint, else the current year.
+fn task_year_hint(conn: &rusqlite::Connection, tasks: &[crate::models::task::Task], task_id: i64) -> YearHint {
+    let sprint = tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .and_then(|t| t.sprint_id)
+        .and_then(|id| sprint_repo::get_sprint_by_id(conn, id).ok().flatten());
+    YearHint::for_sprint(sprint.as_ref())
+}
+
 fn normalize_schedule_suggestions(
     conn: &rusqlite::Connection,
     selected_tasks: &[crate::models::task::Task],
@@ -457,7 +406,7 @@ fn normalize_schedule_suggestions(
         .map(|s| ProposedAssignment {
             task_id: s.task_id,
             developer_id: s.developer_id,
-            suggested_start: parse_ymd(&s.planned_start),
+            suggested_start: date_service::parse_date(&s.planned_start, &task_year_hint(conn, all_tasks, s.task_id)),
             reasoning: s.reasoning.clone(),
         })
         .collect();
@@ -485,7 +434,10 @@ fn normalize_auto_fill_updates(
         .map(|u| ProposedAssignment {
             task_id: u.id,
             developer_id: u.owner_id.unwrap_or(0),
-            suggested_start: u.planned_start.as_deref().and_then(parse_ymd),
+            suggested_start: u
+                .planned_start
+                .as_deref()
+                .and_then(|d| date_service::parse_date(d, &task_year_hint(conn, all_tasks, u.id))),
             reasoning: "自动分配".to_string(),
         })
         .collect();
@@ -512,12 +464,15 @@ fn normalize_auto_fill_updates(
             priority: None,
             planned_start: Some(s.planned_start),
             planned_end: Some(s.planned_end),
+            planned_start_time: None,
+            planned_end_time: None,
             planned_hours: None,
             parent_task_id: None,
             parent_number: None,
             parent_name: None,
             status: None,
             co_owner_ids: None,
+            required_skills: None,
         })
         .collect()
 }
@@ -556,9 +511,11 @@ mod tests {
             name: format!("Dev-{}", id),
             roles: vec![],
             skills: vec![],
+            skill_levels: vec![],
             max_hours_per_day: max_h,
             avatar_color: "#1890ff".to_string(),
             is_active: true,
+            calendar_id: None,
         }
     }
 
@@ -582,30 +539,31 @@ mod tests {
             priority: Some("P1".to_string()),
             planned_start,
             planned_end,
+            planned_start_time: None,
+            planned_end_time: None,
             planned_hours: Some(planned_hours),
             parent_task_id: None,
             parent_number: None,
             parent_name: None,
             status: Some("待开始".to_string()),
             co_owners: None,
+            required_skills: vec![],
         }
     }
 
-    fn nth_workday_from(conn: &Connection, mut start: NaiveDate, mut offset: usize) -> NaiveDate {
-        start = next_workday(conn, start);
-        while offset > 0 {
-            start += Duration::days(1);
-            start = next_workday(conn, start);
-            offset -= 1;
-        }
-        start
+    fn first_workday_from(conn: &Connection, date: NaiveDate) -> NaiveDate {
+        holiday_service::default_work_calendar(conn).next_workday(date)
+    }
+
+    fn nth_workday_from(conn: &Connection, start: NaiveDate, offset: usize) -> NaiveDate {
+        holiday_service::default_work_calendar(conn).nth_workday(start, offset)
     }
 
     #[test]
     fn normalize_schedule_should_expand_duration_by_daily_capacity() {
         let conn = setup_conn();
         let today = Local::now().date_naive();
-        let first_workday = next_workday(&conn, today);
+        let first_workday = first_workday_from(&conn, today);
 
         let developers = vec![make_dev(1, 8.0)];
         let selected = vec![make_task(101, Some(1), 24.0, None, None)];
@@ -626,7 +584,7 @@ mod tests {
         let s = &normalized[0];
         let start = parse_ymd(&s.planned_start).expect("valid start");
         let end = parse_ymd(&s.planned_end).expect("valid end");
-        let days = list_workdays(&conn, start, end);
+        let days = holiday_service::default_work_calendar(&conn).workdays(start, end);
 
         assert_eq!(days.len(), 3, "24h at 8h/day must span 3 workdays");
     }
@@ -635,7 +593,7 @@ mod tests {
     fn normalize_schedule_should_skip_fully_loaded_day() {
         let conn = setup_conn();
         let today = Local::now().date_naive();
-        let day1 = next_workday(&conn, today);
+        let day1 = first_workday_from(&conn, today);
         let day2 = nth_workday_from(&conn, day1, 1);
         let day3 = nth_workday_from(&conn, day1, 2);
 
@@ -670,4 +628,65 @@ mod tests {
         assert_eq!(s.planned_start, day2.format("%Y-%m-%d").to_string());
         assert_eq!(s.planned_end, day3.format("%Y-%m-%d").to_string());
     }
+
+    #[test]
+    fn normalize_schedule_should_reassign_when_proposed_dev_lacks_required_skill() {
+        use crate::models::developer::SkillLevel;
+
+        let conn = setup_conn();
+        let today = Local::now().date_naive();
+        let first_workday = first_workday_from(&conn, today);
+
+        let mut expert = make_dev(2, 8.0);
+        expert.skill_levels = vec![SkillLevel { name: "Rust".to_string(), level: 4 }];
+        let developers = vec![make_dev(1, 8.0), expert];
+
+        let mut task = make_task(301, None, 8.0, None, None);
+        task.required_skills = vec![SkillLevel { name: "Rust".to_string(), level: 3 }];
+        let selected = vec![task];
+
+        let raw = vec![ScheduleSuggestion {
+            task_id: 301,
+            developer_id: 1,
+            planned_start: first_workday.format("%Y-%m-%d").to_string(),
+            planned_end: first_workday.format("%Y-%m-%d").to_string(),
+            reasoning: "llm".to_string(),
+        }];
+
+        let normalized =
+            normalize_schedule_suggestions(&conn, &selected, &selected, &developers, &raw, 8.0);
+        assert_eq!(normalized.len(), 1);
+        assert_eq!(normalized[0].developer_id, 2);
+    }
+
+    #[test]
+    fn chat_action_dates_are_normalized_within_the_sprint() {
+        let conn = setup_conn();
+        conn.execute(
+            "INSERT INTO sprints (name, start_date, end_date) VALUES ('S12', '2026-12-21', '2027-01-08')",
+            [],
+        )
+        .unwrap();
+        let sprints = sprint_repo::get_all_sprints(&conn).unwrap();
+        let action = |payload: serde_json::Value| ChatAction {
+            action_type: "batch_create".to_string(),
+            description: String::new(),
+            payload,
+            requires_confirmation: true,
+        };
+
+        let created = action(serde_json::json!({"tasks": [
+            {"name": "联调", "sprint_name": "S12", "planned_start": "12月30日", "planned_end": "1/4"}
+        ]}));
+        llm_service::execute_chat_action(&conn, &created, &[], &sprints).unwrap();
+        let task = task_repo::get_by_id(&conn, 1).unwrap().unwrap();
+        assert_eq!(
+            (task.planned_start.as_deref(), task.planned_end.as_deref()),
+            (Some("2026-12-30"), Some("2027-01-04"))
+        );
+
+        let invalid = action(serde_json::json!({"tasks": [{"name": "验收", "planned_start": "下周"}]}));
+        let err = llm_service::execute_chat_action(&conn, &invalid, &[], &sprints).unwrap_err();
+        assert!(err.contains("下周"));
+    }
 }
@@ -7,3 +7,6 @@ pub mod settings_commands;
 pub mod batch_commands;
 pub mod llm_commands;
 pub mod standup_commands;
+pub mod team_commands;
+pub mod baseline_commands;
+pub mod scenario_commands;
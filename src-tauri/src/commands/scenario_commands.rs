@@ -0,0 +1,97 @@
+use tauri::State;
+use crate::db::AppDatabase;
+use crate::models::scenario::{
+    CreateScenarioDeveloperDto, CreateScenarioDto, Scenario, ScenarioComparison, ScenarioDeveloper, ScenarioTask,
+    ScenarioTaskEdit,
+};
+use crate::models::task::TaskMove;
+use crate::services::reschedule_service::MoveMode;
+use crate::services::scenario_service;
+
+#[tauri::command]
+pub fn list_scenarios(db: State<AppDatabase>) -> Result<Vec<Scenario>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::list_scenarios(&conn)
+}
+
+#[tauri::command]
+pub fn create_scenario(db: State<AppDatabase>, dto: CreateScenarioDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::create_scenario(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_scenario(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::delete_scenario(&conn, id)
+}
+
+#[tauri::command]
+pub fn list_scenario_tasks(db: State<AppDatabase>, scenario_id: i64) -> Result<Vec<ScenarioTask>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::list_tasks(&conn, scenario_id)
+}
+
+#[tauri::command]
+pub fn update_scenario_task(db: State<AppDatabase>, edit: ScenarioTaskEdit) -> Result<ScenarioTask, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::update_task(&conn, &edit)
+}
+
+#[tauri::command]
+pub fn reset_scenario_task(db: State<AppDatabase>, scenario_id: i64, task_id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::reset_task(&conn, scenario_id, task_id)
+}
+
+/// Move a task inside a scenario and keep the rippled changes there.
+#[tauri::command]
+pub fn move_scenario_task(
+    db: State<AppDatabase>,
+    scenario_id: i64,
+    task_id: i64,
+    new_start: String,
+    mode: Option<String>,
+    ripple: Option<bool>,
+) -> Result<Vec<TaskMove>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mode = MoveMode::parse(mode.as_deref())?;
+    scenario_service::move_task(&conn, scenario_id, task_id, &new_start, mode, ripple.unwrap_or(true))
+}
+
+#[tauri::command]
+pub fn list_scenario_developers(db: State<AppDatabase>, scenario_id: i64) -> Result<Vec<ScenarioDeveloper>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::list_developers(&conn, scenario_id)
+}
+
+#[tauri::command]
+pub fn add_scenario_developer(db: State<AppDatabase>, dto: CreateScenarioDeveloperDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::add_developer(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn remove_scenario_developer(db: State<AppDatabase>, scenario_id: i64, developer_id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::remove_developer(&conn, scenario_id, developer_id)
+}
+
+/// Compare two plans; an omitted id means the live plan.
+#[tauri::command]
+pub fn compare_scenarios(
+    db: State<AppDatabase>,
+    left_id: Option<i64>,
+    right_id: Option<i64>,
+    start_date: String,
+    end_date: String,
+) -> Result<ScenarioComparison, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::compare(&conn, left_id, right_id, &start_date, &end_date)
+}
+
+#[tauri::command]
+pub fn apply_scenario(db: State<AppDatabase>, id: i64) -> Result<usize, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    scenario_service::apply_scenario(&conn, id)
+}
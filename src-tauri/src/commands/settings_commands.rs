@@ -1,7 +1,8 @@
 use tauri::State;
 use crate::db::AppDatabase;
 use crate::models::settings::{LlmConfig, ExcelTemplateConfig};
-use crate::services::settings_service;
+use crate::services::{settings_service, value_mapping_service};
+use std::collections::HashMap;
 
 #[tauri::command]
 pub fn get_llm_config(db: State<AppDatabase>) -> Result<Option<LlmConfig>, String> {
@@ -38,3 +39,21 @@ pub fn save_setting(db: State<AppDatabase>, key: String, value: String, category
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     settings_service::set_setting(&conn, &key, &value, &category)
 }
+
+#[tauri::command]
+pub fn get_value_mappings(db: State<AppDatabase>) -> Result<HashMap<String, HashMap<String, String>>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    value_mapping_service::get_value_mappings(&conn)
+}
+
+#[tauri::command]
+pub fn save_value_mappings(db: State<AppDatabase>, field: String, mappings: HashMap<String, String>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    value_mapping_service::save_value_mappings(&conn, &field, &mappings)
+}
+
+#[tauri::command]
+pub fn remember_value_mappings(db: State<AppDatabase>, field: String, mappings: HashMap<String, String>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    value_mapping_service::remember_value_mappings(&conn, &field, &mappings)
+}
@@ -1,7 +1,11 @@
 use tauri::State;
 use crate::db::AppDatabase;
-use crate::models::sprint::{Sprint, Project, CreateSprintDto, CreateProjectDto, UpdateSprintDto, DeleteSprintResult};
+use crate::models::sprint::{
+    Sprint, Project, CreateSprintDto, CreateProjectDto, UpdateSprintDto, DeleteSprintResult, SprintCapacitySummary,
+    Milestone, CreateMilestoneDto, UpdateMilestoneDto,
+};
 use crate::db::sprint_repo;
+use crate::services::{milestone_service, team_service};
 
 #[tauri::command]
 pub fn list_sprints(db: State<AppDatabase>) -> Result<Vec<Sprint>, String> {
@@ -68,3 +72,42 @@ pub fn create_project(db: State<AppDatabase>, dto: CreateProjectDto) -> Result<i
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     sprint_repo::create_project(&conn, &dto).map_err(|e| e.to_string())
 }
+
+/// Sprint capacity with each member's workdays on their own calendar.
+#[tauri::command]
+pub fn get_sprint_capacity(
+    db: State<AppDatabase>,
+    sprint_id: i64,
+    team_id: Option<i64>,
+) -> Result<SprintCapacitySummary, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::get_sprint_capacity(&conn, sprint_id, team_id)
+}
+
+#[tauri::command]
+pub fn list_milestones(
+    db: State<AppDatabase>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<Milestone>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    milestone_service::list_milestones(&conn, start_date.as_deref(), end_date.as_deref())
+}
+
+#[tauri::command]
+pub fn create_milestone(db: State<AppDatabase>, dto: CreateMilestoneDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    milestone_service::create_milestone(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn update_milestone(db: State<AppDatabase>, dto: UpdateMilestoneDto) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    milestone_service::update_milestone(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_milestone(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    milestone_service::delete_milestone(&conn, id)
+}
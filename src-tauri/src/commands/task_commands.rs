@@ -1,7 +1,9 @@
 use tauri::State;
 use crate::db::AppDatabase;
-use crate::models::task::{Task, CreateTaskDto, UpdateTaskDto, TaskFilter};
-use crate::services::task_service;
+use crate::models::task::{Task, CreateTaskDto, UpdateTaskDto, TaskFilter, TaskDependency, TaskMove, DailyAllocation};
+use crate::models::assignment::AssignmentScore;
+use crate::services::{assignment_service, reschedule_service, task_service};
+use crate::services::reschedule_service::MoveMode;
 
 #[tauri::command]
 pub fn list_tasks(db: State<AppDatabase>, filter: TaskFilter) -> Result<Vec<Task>, String> {
@@ -38,3 +40,59 @@ pub fn count_tasks(db: State<AppDatabase>) -> Result<i64, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     task_service::count_tasks(&conn)
 }
+
+#[tauri::command]
+pub fn suggest_task_owner(db: State<AppDatabase>, task_id: i64, limit: Option<usize>) -> Result<Vec<AssignmentScore>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    assignment_service::suggest_owner(&conn, task_id, limit)
+}
+
+/// Move a task to a new start date; returns the changed tasks and saves them only when `apply`.
+#[tauri::command]
+pub fn move_task(
+    db: State<AppDatabase>,
+    task_id: i64,
+    new_start: String,
+    mode: Option<String>,
+    ripple: Option<bool>,
+    apply: Option<bool>,
+) -> Result<Vec<TaskMove>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mode = MoveMode::parse(mode.as_deref())?;
+    reschedule_service::move_task(&conn, task_id, &new_start, mode, ripple.unwrap_or(false), apply.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn list_task_dependencies(db: State<AppDatabase>, task_id: i64) -> Result<Vec<TaskDependency>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    task_service::list_dependencies(&conn, task_id)
+}
+
+#[tauri::command]
+pub fn add_task_dependency(db: State<AppDatabase>, predecessor_id: i64, successor_id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    task_service::add_dependency(&conn, predecessor_id, successor_id)
+}
+
+#[tauri::command]
+pub fn remove_task_dependency(db: State<AppDatabase>, predecessor_id: i64, successor_id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    task_service::remove_dependency(&conn, predecessor_id, successor_id)
+}
+
+#[tauri::command]
+pub fn list_task_daily_allocations(db: State<AppDatabase>, task_id: i64) -> Result<Vec<DailyAllocation>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    task_service::list_daily_allocations(&conn, task_id)
+}
+
+/// Replace a task's pinned daily hours; an empty list returns it to automatic distribution.
+#[tauri::command]
+pub fn set_task_daily_allocations(
+    db: State<AppDatabase>,
+    task_id: i64,
+    allocations: Vec<DailyAllocation>,
+) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    task_service::set_daily_allocations(&conn, task_id, &allocations)
+}
@@ -0,0 +1,52 @@
+use tauri::State;
+use crate::db::AppDatabase;
+use crate::models::calendar::DeveloperWorkload;
+use crate::models::team::{Team, CreateTeamDto, UpdateTeamDto, TeamCapacitySummary};
+use crate::services::team_service;
+
+#[tauri::command]
+pub fn list_teams(db: State<AppDatabase>) -> Result<Vec<Team>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::list_teams(&conn)
+}
+
+#[tauri::command]
+pub fn create_team(db: State<AppDatabase>, dto: CreateTeamDto) -> Result<i64, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::create_team(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn update_team(db: State<AppDatabase>, dto: UpdateTeamDto) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::update_team(&conn, &dto)
+}
+
+#[tauri::command]
+pub fn delete_team(db: State<AppDatabase>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::delete_team(&conn, id)
+}
+
+#[tauri::command]
+pub fn get_team_workload(
+    db: State<AppDatabase>,
+    team_id: i64,
+    start_date: String,
+    end_date: String,
+    include_overtime: Option<bool>,
+) -> Result<Vec<DeveloperWorkload>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::get_team_workload(&conn, team_id, &start_date, &end_date, include_overtime.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn get_team_capacity(
+    db: State<AppDatabase>,
+    team_id: i64,
+    start_date: String,
+    end_date: String,
+) -> Result<TeamCapacitySummary, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    team_service::get_team_capacity(&conn, team_id, &start_date, &end_date)
+}
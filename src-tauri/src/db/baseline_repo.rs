@@ -0,0 +1,85 @@
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::baseline::{Baseline, BaselineTask};
+
+const BASELINE_SELECT: &str = "SELECT b.id, b.name, b.sprint_id, s.name, b.created_at, \
+     (SELECT COUNT(*) FROM schedule_baseline_tasks bt WHERE bt.baseline_id = b.id) \
+     FROM schedule_baselines b LEFT JOIN sprints s ON s.id = b.sprint_id";
+
+fn map_baseline(row: &Row) -> Result<Baseline> {
+    Ok(Baseline {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        sprint_id: row.get(2)?,
+        sprint_name: row.get(3)?,
+        created_at: row.get(4)?,
+        task_count: row.get(5)?,
+    })
+}
+
+/// Newest first, optionally only one sprint's baselines.
+pub fn get_all(conn: &Connection, sprint_id: Option<i64>) -> Result<Vec<Baseline>> {
+    let sql = format!(
+        "{} WHERE (?1 IS NULL OR b.sprint_id = ?1) ORDER BY b.created_at DESC, b.id DESC",
+        BASELINE_SELECT
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![sprint_id], map_baseline)?;
+    rows.collect()
+}
+
+pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Baseline>> {
+    let sql = format!("{} WHERE b.id = ?1", BASELINE_SELECT);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![id], map_baseline)?;
+    rows.next().transpose()
+}
+
+pub fn create(conn: &Connection, name: &str, sprint_id: Option<i64>) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO schedule_baselines (name, sprint_id) VALUES (?1, ?2)",
+        params![name, sprint_id],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn insert_task(conn: &Connection, baseline_id: i64, task: &BaselineTask) -> Result<()> {
+    conn.execute(
+        "INSERT INTO schedule_baseline_tasks \
+         (baseline_id, task_id, task_name, owner_id, planned_start, planned_end, planned_hours) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            baseline_id,
+            task.task_id,
+            task.task_name,
+            task.owner_id,
+            task.planned_start,
+            task.planned_end,
+            task.planned_hours
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_tasks(conn: &Connection, baseline_id: i64) -> Result<Vec<BaselineTask>> {
+    let mut stmt = conn.prepare(
+        "SELECT task_id, task_name, owner_id, planned_start, planned_end, planned_hours \
+         FROM schedule_baseline_tasks WHERE baseline_id = ?1 ORDER BY task_id",
+    )?;
+    let rows = stmt.query_map(params![baseline_id], |row| {
+        Ok(BaselineTask {
+            task_id: row.get(0)?,
+            task_name: row.get(1)?,
+            owner_id: row.get(2)?,
+            planned_start: row.get(3)?,
+            planned_end: row.get(4)?,
+            planned_hours: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn delete(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM schedule_baseline_tasks WHERE baseline_id = ?1", params![id])?;
+    conn.execute("DELETE FROM schedule_baselines WHERE id = ?1", params![id])?;
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::calendar::{
+    CalendarOverride, CreateCalendarOverrideDto, CreateRegionCalendarDto, OvertimeConfig, RegionCalendar,
+    UpdateCalendarOverrideDto, UpdateRegionCalendarDto,
+};
+
+const CALENDAR_COLUMNS: &str = "id, name, region, weekend_days, is_default";
+
+fn map_calendar(row: &Row) -> Result<RegionCalendar> {
+    let weekend_json: String = row.get(3)?;
+    Ok(RegionCalendar {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        region: row.get(2)?,
+        weekend_days: serde_json::from_str(&weekend_json).unwrap_or_else(|_| vec![5, 6]),
+        is_default: row.get::<_, i32>(4)? != 0,
+    })
+}
+
+fn weekend_json(days: &[u32]) -> String {
+    let mut days = days.to_vec();
+    days.sort();
+    days.dedup();
+    serde_json::to_string(&days).unwrap_or_else(|_| "[]".to_string())
+}
+
+pub fn get_all(conn: &Connection) -> Result<Vec<RegionCalendar>> {
+    let sql = format!("SELECT {} FROM region_calendars ORDER BY is_default DESC, name", CALENDAR_COLUMNS);
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], map_calendar)?;
+    rows.collect()
+}
+
+pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<RegionCalendar>> {
+    let sql = format!("SELECT {} FROM region_calendars WHERE id = ?1", CALENDAR_COLUMNS);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![id], map_calendar)?;
+    rows.next().transpose()
+}
+
+pub fn get_default(conn: &Connection) -> Result<Option<RegionCalendar>> {
+    let sql = format!(
+        "SELECT {} FROM region_calendars WHERE is_default = 1 ORDER BY id LIMIT 1",
+        CALENDAR_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map([], map_calendar)?;
+    rows.next().transpose()
+}
+
+/// Distinct holiday regions across all calendars.
+pub fn get_regions(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT DISTINCT region FROM region_calendars ORDER BY region")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// (developer id, calendar) for every developer with an explicit calendar.
+pub fn get_developer_calendars(conn: &Connection) -> Result<Vec<(i64, RegionCalendar)>> {
+    let mut stmt = conn.prepare(
+        "SELECT d.id, c.id, c.name, c.region, c.weekend_days, c.is_default \
+         FROM developers d JOIN region_calendars c ON c.id = d.calendar_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let weekend_json: String = row.get(4)?;
+        Ok((
+            row.get(0)?,
+            RegionCalendar {
+                id: row.get(1)?,
+                name: row.get(2)?,
+                region: row.get(3)?,
+                weekend_days: serde_json::from_str(&weekend_json).unwrap_or_else(|_| vec![5, 6]),
+                is_default: row.get::<_, i32>(5)? != 0,
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+fn clear_default(conn: &Connection) -> Result<()> {
+    conn.execute("UPDATE region_calendars SET is_default = 0 WHERE is_default = 1", [])?;
+    Ok(())
+}
+
+pub fn create(conn: &Connection, dto: &CreateRegionCalendarDto) -> Result<i64> {
+    let is_default = dto.is_default.unwrap_or(false);
+    if is_default {
+        clear_default(conn)?;
+    }
+    conn.execute(
+        "INSERT INTO region_calendars (name, region, weekend_days, is_default) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            dto.name,
+            dto.region,
+            weekend_json(dto.weekend_days.as_deref().unwrap_or(&[5, 6])),
+            is_default as i32
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update(conn: &Connection, dto: &UpdateRegionCalendarDto) -> Result<()> {
+    let current = get_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let name = dto.name.as_ref().unwrap_or(&current.name);
+    let region = dto.region.as_ref().unwrap_or(&current.region);
+    let weekend = dto.weekend_days.as_ref().unwrap_or(&current.weekend_days);
+    let is_default = dto.is_default.unwrap_or(current.is_default);
+    if is_default && !current.is_default {
+        clear_default(conn)?;
+    }
+    conn.execute(
+        "UPDATE region_calendars SET name = ?1, region = ?2, weekend_days = ?3, is_default = ?4 WHERE id = ?5",
+        params![name, region, weekend_json(weekend), is_default as i32, dto.id],
+    )?;
+    Ok(())
+}
+
+/// Delete a calendar and its overrides; developers using it fall back to the default calendar.
+pub fn delete(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("UPDATE developers SET calendar_id = NULL WHERE calendar_id = ?1", params![id])?;
+    conn.execute("DELETE FROM calendar_overrides WHERE calendar_id = ?1", params![id])?;
+    conn.execute("DELETE FROM region_calendars WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+const OVERRIDE_COLUMNS: &str = "id, calendar_id, date, is_workday, name";
+
+fn map_override(row: &Row) -> Result<CalendarOverride> {
+    Ok(CalendarOverride {
+        id: row.get(0)?,
+        calendar_id: row.get(1)?,
+        date: row.get(2)?,
+        is_workday: row.get::<_, i32>(3)? != 0,
+        name: row.get(4)?,
+    })
+}
+
+/// Overrides whose date falls in `[start_date, end_date]`; either bound may be open.
+pub fn get_overrides(conn: &Connection, start_date: Option<&str>, end_date: Option<&str>) -> Result<Vec<CalendarOverride>> {
+    let sql = format!(
+        "SELECT {} FROM calendar_overrides \
+         WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2) \
+         ORDER BY date, calendar_id",
+        OVERRIDE_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![start_date, end_date], map_override)?;
+    rows.collect()
+}
+
+/// Overrides that apply to `calendar_id`: its own plus the global ones, global first.
+pub fn get_overrides_for_calendar(conn: &Connection, calendar_id: i64) -> Result<Vec<CalendarOverride>> {
+    let sql = format!(
+        "SELECT {} FROM calendar_overrides WHERE calendar_id IS NULL OR calendar_id = ?1 \
+         ORDER BY calendar_id IS NOT NULL, date",
+        OVERRIDE_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![calendar_id], map_override)?;
+    rows.collect()
+}
+
+pub fn get_override_by_id(conn: &Connection, id: i64) -> Result<Option<CalendarOverride>> {
+    let sql = format!("SELECT {} FROM calendar_overrides WHERE id = ?1", OVERRIDE_COLUMNS);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![id], map_override)?;
+    rows.next().transpose()
+}
+
+/// Id of an existing override for the same scope and date, other than `exclude_id`.
+pub fn find_override(conn: &Connection, calendar_id: Option<i64>, date: &str, exclude_id: Option<i64>) -> Result<Option<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM calendar_overrides \
+         WHERE calendar_id IS ?1 AND date = ?2 AND (?3 IS NULL OR id <> ?3) LIMIT 1",
+    )?;
+    let mut rows = stmt.query_map(params![calendar_id, date, exclude_id], |row| row.get(0))?;
+    rows.next().transpose()
+}
+
+pub fn create_override(conn: &Connection, dto: &CreateCalendarOverrideDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO calendar_overrides (calendar_id, date, is_workday, name) VALUES (?1, ?2, ?3, ?4)",
+        params![dto.calendar_id, dto.date, dto.is_workday as i32, dto.name],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_override(conn: &Connection, dto: &UpdateCalendarOverrideDto) -> Result<()> {
+    let current = get_override_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let date = dto.date.as_ref().unwrap_or(&current.date);
+    let is_workday = dto.is_workday.unwrap_or(current.is_workday);
+    let name = dto.name.as_ref().or(current.name.as_ref());
+    conn.execute(
+        "UPDATE calendar_overrides SET date = ?1, is_workday = ?2, name = ?3 WHERE id = ?4",
+        params![date, is_workday as i32, name, dto.id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_override(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM calendar_overrides WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+fn map_overtime(row: &Row) -> Result<(i64, OvertimeConfig)> {
+    let dates_json: String = row.get(2)?;
+    Ok((
+        row.get(0)?,
+        OvertimeConfig {
+            weekend: row.get(1)?,
+            custom_dates: serde_json::from_str(&dates_json).unwrap_or_default(),
+        },
+    ))
+}
+
+pub fn get_developer_overtime(conn: &Connection, developer_id: i64) -> Result<Option<OvertimeConfig>> {
+    let mut stmt = conn.prepare(
+        "SELECT developer_id, weekend, custom_dates FROM developer_overtime WHERE developer_id = ?1",
+    )?;
+    let mut rows = stmt.query_map(params![developer_id], map_overtime)?;
+    rows.next().transpose().map(|r| r.map(|(_, cfg)| cfg))
+}
+
+/// (developer id, overtime rules) for every developer with their own rules.
+pub fn get_all_developer_overtime(conn: &Connection) -> Result<Vec<(i64, OvertimeConfig)>> {
+    let mut stmt = conn.prepare("SELECT developer_id, weekend, custom_dates FROM developer_overtime")?;
+    let rows = stmt.query_map([], map_overtime)?;
+    rows.collect()
+}
+
+pub fn set_developer_overtime(conn: &Connection, developer_id: i64, config: &OvertimeConfig) -> Result<()> {
+    let dates_json = serde_json::to_string(&config.custom_dates).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT OR REPLACE INTO developer_overtime (developer_id, weekend, custom_dates) VALUES (?1, ?2, ?3)",
+        params![developer_id, config.weekend, dates_json],
+    )?;
+    Ok(())
+}
+
+pub fn clear_developer_overtime(conn: &Connection, developer_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM developer_overtime WHERE developer_id = ?1", params![developer_id])?;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::capacity::{CapacityRule, CreateCapacityRuleDto};
+
+fn map_rule(row: &Row) -> Result<CapacityRule> {
+    Ok(CapacityRule {
+        id: row.get(0)?,
+        developer_id: row.get(1)?,
+        mode: row.get(2)?,
+        value: row.get(3)?,
+        start_date: row.get(4)?,
+        end_date: row.get(5)?,
+        weekday: row.get(6)?,
+        note: row.get(7)?,
+    })
+}
+
+pub fn get_by_developer(conn: &Connection, developer_id: i64) -> Result<Vec<CapacityRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, developer_id, mode, value, start_date, end_date, weekday, note \
+         FROM developer_capacity_rules WHERE developer_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![developer_id], map_rule)?;
+    rows.collect()
+}
+
+pub fn get_all(conn: &Connection) -> Result<Vec<CapacityRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, developer_id, mode, value, start_date, end_date, weekday, note \
+         FROM developer_capacity_rules ORDER BY developer_id, id",
+    )?;
+    let rows = stmt.query_map([], map_rule)?;
+    rows.collect()
+}
+
+pub fn create(conn: &Connection, dto: &CreateCapacityRuleDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO developer_capacity_rules (developer_id, mode, value, start_date, end_date, weekday, note) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            dto.developer_id, dto.mode, dto.value,
+            dto.start_date, dto.end_date, dto.weekday, dto.note
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn delete(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM developer_capacity_rules WHERE id = ?1", params![id])?;
+    Ok(())
+}
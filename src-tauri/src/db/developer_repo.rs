@@ -1,43 +1,52 @@
-use rusqlite::{params, Connection, Result};
-use crate::models::developer::{Developer, CreateDeveloperDto, UpdateDeveloperDto};
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::developer::{Developer, CreateDeveloperDto, UpdateDeveloperDto, SkillLevel};
+
+const DEVELOPER_COLUMNS: &str =
+    "id, name, roles, skills, max_hours_per_day, avatar_color, is_active, skill_levels, calendar_id";
+
+fn map_developer(row: &Row) -> Result<Developer> {
+    let roles_str: String = row.get(2)?;
+    let skills_str: String = row.get(3)?;
+    let levels_str: String = row.get(7)?;
+    Ok(Developer {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        roles: serde_json::from_str(&roles_str).unwrap_or_default(),
+        skills: serde_json::from_str(&skills_str).unwrap_or_default(),
+        skill_levels: serde_json::from_str(&levels_str).unwrap_or_default(),
+        max_hours_per_day: row.get(4)?,
+        avatar_color: row.get(5)?,
+        is_active: row.get::<_, i32>(6)? != 0,
+        calendar_id: row.get(8)?,
+    })
+}
+
+/// Make sure every leveled skill also shows up in the plain skill tag list.
+fn merge_skill_names(skills: &[String], levels: &[SkillLevel]) -> Vec<String> {
+    let mut merged = skills.to_vec();
+    for level in levels {
+        if !merged.iter().any(|s| s.trim().eq_ignore_ascii_case(level.name.trim())) {
+            merged.push(level.name.clone());
+        }
+    }
+    merged
+}
 
 pub fn get_all(conn: &Connection) -> Result<Vec<Developer>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, roles, skills, max_hours_per_day, avatar_color, is_active FROM developers ORDER BY name"
-    )?;
-    let rows = stmt.query_map([], |row| {
-        let roles_str: String = row.get(2)?;
-        let skills_str: String = row.get(3)?;
-        Ok(Developer {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            roles: serde_json::from_str(&roles_str).unwrap_or_default(),
-            skills: serde_json::from_str(&skills_str).unwrap_or_default(),
-            max_hours_per_day: row.get(4)?,
-            avatar_color: row.get(5)?,
-            is_active: row.get::<_, i32>(6)? != 0,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM developers ORDER BY name",
+        DEVELOPER_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], map_developer)?;
     rows.collect()
 }
 
 pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Developer>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, roles, skills, max_hours_per_day, avatar_color, is_active FROM developers WHERE id = ?1"
-    )?;
-    let mut rows = stmt.query_map(params![id], |row| {
-        let roles_str: String = row.get(2)?;
-        let skills_str: String = row.get(3)?;
-        Ok(Developer {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            roles: serde_json::from_str(&roles_str).unwrap_or_default(),
-            skills: serde_json::from_str(&skills_str).unwrap_or_default(),
-            max_hours_per_day: row.get(4)?,
-            avatar_color: row.get(5)?,
-            is_active: row.get::<_, i32>(6)? != 0,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM developers WHERE id = ?1",
+        DEVELOPER_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map(params![id], map_developer)?;
     match rows.next() {
         Some(row) => Ok(Some(row?)),
         None => Ok(None),
@@ -45,22 +54,11 @@ pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Developer>> {
 }
 
 pub fn find_by_name(conn: &Connection, name: &str) -> Result<Option<Developer>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, roles, skills, max_hours_per_day, avatar_color, is_active FROM developers WHERE name = ?1"
-    )?;
-    let mut rows = stmt.query_map(params![name], |row| {
-        let roles_str: String = row.get(2)?;
-        let skills_str: String = row.get(3)?;
-        Ok(Developer {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            roles: serde_json::from_str(&roles_str).unwrap_or_default(),
-            skills: serde_json::from_str(&skills_str).unwrap_or_default(),
-            max_hours_per_day: row.get(4)?,
-            avatar_color: row.get(5)?,
-            is_active: row.get::<_, i32>(6)? != 0,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM developers WHERE name = ?1",
+        DEVELOPER_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map(params![name], map_developer)?;
     match rows.next() {
         Some(row) => Ok(Some(row?)),
         None => Ok(None),
@@ -68,14 +66,17 @@ pub fn find_by_name(conn: &Connection, name: &str) -> Result<Option<Developer>>
 }
 
 pub fn create(conn: &Connection, dto: &CreateDeveloperDto) -> Result<i64> {
+    let levels = dto.skill_levels.clone().unwrap_or_default();
+    let skills = merge_skill_names(&dto.skills.clone().unwrap_or_default(), &levels);
     let roles_json = serde_json::to_string(&dto.roles.clone().unwrap_or_default()).unwrap();
-    let skills_json = serde_json::to_string(&dto.skills.clone().unwrap_or_default()).unwrap();
+    let skills_json = serde_json::to_string(&skills).unwrap();
+    let levels_json = serde_json::to_string(&levels).unwrap();
     let color = dto.avatar_color.clone().unwrap_or_else(|| "#1890ff".to_string());
     let max_hours = dto.max_hours_per_day.unwrap_or(8.0);
 
     conn.execute(
-        "INSERT INTO developers (name, roles, skills, max_hours_per_day, avatar_color) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![dto.name, roles_json, skills_json, max_hours, color],
+        "INSERT INTO developers (name, roles, skills, max_hours_per_day, avatar_color, skill_levels, calendar_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![dto.name, roles_json, skills_json, max_hours, color, levels_json, dto.calendar_id],
     )?;
     Ok(conn.last_insert_rowid())
 }
@@ -90,8 +91,10 @@ pub fn find_or_create_by_name(conn: &Connection, name: &str, color_index: usize)
         name: name.to_string(),
         roles: None,
         skills: None,
+        skill_levels: None,
         max_hours_per_day: None,
         avatar_color: Some(color.to_string()),
+        calendar_id: None,
     };
     create(conn, &dto)
 }
@@ -100,17 +103,20 @@ pub fn update(conn: &Connection, dto: &UpdateDeveloperDto) -> Result<()> {
     let current = get_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
     let name = dto.name.as_ref().unwrap_or(&current.name);
     let roles = dto.roles.as_ref().unwrap_or(&current.roles);
-    let skills = dto.skills.as_ref().unwrap_or(&current.skills);
+    let levels = dto.skill_levels.as_ref().unwrap_or(&current.skill_levels);
+    let skills = merge_skill_names(dto.skills.as_ref().unwrap_or(&current.skills), levels);
     let max_hours = dto.max_hours_per_day.unwrap_or(current.max_hours_per_day);
     let color = dto.avatar_color.as_ref().unwrap_or(&current.avatar_color);
     let active = dto.is_active.unwrap_or(current.is_active);
+    let calendar_id = if dto.clear_calendar { None } else { dto.calendar_id.or(current.calendar_id) };
 
     let roles_json = serde_json::to_string(roles).unwrap();
-    let skills_json = serde_json::to_string(skills).unwrap();
+    let skills_json = serde_json::to_string(&skills).unwrap();
+    let levels_json = serde_json::to_string(levels).unwrap();
 
     conn.execute(
-        "UPDATE developers SET name=?1, roles=?2, skills=?3, max_hours_per_day=?4, avatar_color=?5, is_active=?6 WHERE id=?7",
-        params![name, roles_json, skills_json, max_hours, color, active as i32, dto.id],
+        "UPDATE developers SET name=?1, roles=?2, skills=?3, max_hours_per_day=?4, avatar_color=?5, is_active=?6, skill_levels=?7, calendar_id=?8 WHERE id=?9",
+        params![name, roles_json, skills_json, max_hours, color, active as i32, levels_json, calendar_id, dto.id],
     )?;
     Ok(())
 }
@@ -119,3 +125,22 @@ pub fn delete(conn: &Connection, id: i64) -> Result<()> {
     conn.execute("DELETE FROM developers WHERE id = ?1", params![id])?;
     Ok(())
 }
+
+pub fn set_active(conn: &Connection, id: i64, active: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE developers SET is_active = ?1 WHERE id = ?2",
+        params![active as i32, id],
+    )?;
+    Ok(())
+}
+
+/// Number of tasks, co-ownerships and standup entries that point at this developer.
+pub fn count_references(conn: &Connection, id: i64) -> Result<i64> {
+    conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM tasks WHERE owner_id = ?1) \
+              + (SELECT COUNT(*) FROM task_co_owners WHERE developer_id = ?1) \
+              + (SELECT COUNT(*) FROM standup_entries WHERE developer_id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+}
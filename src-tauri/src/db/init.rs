@@ -68,12 +68,29 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             rows_imported INTEGER NOT NULL DEFAULT 0
         );
 
+        -- What each import did, replayed in reverse to roll it back.
+        -- entity_type: 'task' | 'developer' | 'sprint'; action: 'create' | 'update'.
+        -- prior_values holds the task as JSON before an update, after_values as the import left it.
+        CREATE TABLE IF NOT EXISTS import_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            import_id INTEGER NOT NULL REFERENCES import_history(id) ON DELETE CASCADE,
+            entity_type TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            prior_values TEXT,
+            after_values TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_import_changes_import ON import_changes(import_id);
+
         CREATE TABLE IF NOT EXISTS holiday_cache (
-            date TEXT PRIMARY KEY,
+            region TEXT NOT NULL DEFAULT 'CN',
+            date TEXT NOT NULL,
             is_holiday INTEGER NOT NULL,
             is_workday INTEGER NOT NULL,
             name TEXT,
-            year INTEGER NOT NULL
+            year INTEGER NOT NULL,
+            source TEXT,
+            PRIMARY KEY (region, date)
         );
 
         CREATE INDEX IF NOT EXISTS idx_holiday_year ON holiday_cache(year);
@@ -119,6 +136,135 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 
         CREATE INDEX IF NOT EXISTS idx_standup_date ON standup_meetings(meeting_date);
         CREATE INDEX IF NOT EXISTS idx_standup_entry_meeting ON standup_entries(meeting_id);
+
+        CREATE TABLE IF NOT EXISTS developer_capacity_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            developer_id INTEGER NOT NULL,
+            mode TEXT NOT NULL DEFAULT 'set',
+            value REAL NOT NULL,
+            start_date TEXT,
+            end_date TEXT,
+            weekday INTEGER,
+            note TEXT,
+            FOREIGN KEY (developer_id) REFERENCES developers(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_capacity_rules_developer ON developer_capacity_rules(developer_id);
+
+        CREATE TABLE IF NOT EXISTS teams (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            color TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS team_members (
+            team_id INTEGER NOT NULL,
+            developer_id INTEGER NOT NULL,
+            PRIMARY KEY (team_id, developer_id),
+            FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE,
+            FOREIGN KEY (developer_id) REFERENCES developers(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_team_members_developer ON team_members(developer_id);
+
+        CREATE TABLE IF NOT EXISTS region_calendars (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            region TEXT NOT NULL,
+            weekend_days TEXT NOT NULL DEFAULT '[5,6]',
+            is_default INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS calendar_overrides (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            calendar_id INTEGER REFERENCES region_calendars(id) ON DELETE CASCADE,
+            date TEXT NOT NULL,
+            is_workday INTEGER NOT NULL,
+            name TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_calendar_overrides_date ON calendar_overrides(date);
+
+        CREATE TABLE IF NOT EXISTS developer_overtime (
+            developer_id INTEGER PRIMARY KEY REFERENCES developers(id) ON DELETE CASCADE,
+            weekend TEXT NOT NULL DEFAULT 'none',
+            custom_dates TEXT NOT NULL DEFAULT '[]'
+        );
+
+        CREATE TABLE IF NOT EXISTS milestones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            date TEXT NOT NULL,
+            sprint_id INTEGER REFERENCES sprints(id) ON DELETE SET NULL,
+            note TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_milestones_date ON milestones(date);
+
+        CREATE TABLE IF NOT EXISTS task_dependencies (
+            predecessor_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            successor_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            PRIMARY KEY (predecessor_id, successor_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_dependencies_successor ON task_dependencies(successor_id);
+
+        CREATE TABLE IF NOT EXISTS task_daily_allocations (
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            date TEXT NOT NULL,
+            hours REAL NOT NULL,
+            PRIMARY KEY (task_id, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS schedule_baselines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            sprint_id INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+        );
+
+        CREATE TABLE IF NOT EXISTS schedule_baseline_tasks (
+            baseline_id INTEGER NOT NULL REFERENCES schedule_baselines(id) ON DELETE CASCADE,
+            task_id INTEGER NOT NULL,
+            task_name TEXT NOT NULL,
+            owner_id INTEGER,
+            planned_start TEXT,
+            planned_end TEXT,
+            planned_hours REAL,
+            PRIMARY KEY (baseline_id, task_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS scenarios (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+            applied_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS scenario_developers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scenario_id INTEGER NOT NULL REFERENCES scenarios(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            max_hours_per_day REAL NOT NULL DEFAULT 8.0
+        );
+
+        CREATE TABLE IF NOT EXISTS scenario_tasks (
+            scenario_id INTEGER NOT NULL REFERENCES scenarios(id) ON DELETE CASCADE,
+            task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            owner_id INTEGER REFERENCES developers(id) ON DELETE SET NULL,
+            scenario_developer_id INTEGER REFERENCES scenario_developers(id) ON DELETE SET NULL,
+            planned_start TEXT,
+            planned_end TEXT,
+            planned_hours REAL,
+            base_owner_id INTEGER,
+            base_start TEXT,
+            base_end TEXT,
+            base_hours REAL,
+            PRIMARY KEY (scenario_id, task_id)
+        );
         ",
     )?;
     Ok(())
@@ -139,9 +285,85 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
 
     migrate_standup_schema(conn)?;
 
+    if !column_exists(conn, "developers", "skill_levels") {
+        conn.execute_batch("ALTER TABLE developers ADD COLUMN skill_levels TEXT NOT NULL DEFAULT '[]';")?;
+    }
+    if !column_exists(conn, "tasks", "required_skills") {
+        conn.execute_batch("ALTER TABLE tasks ADD COLUMN required_skills TEXT;")?;
+    }
+    if !column_exists(conn, "holiday_cache", "source") {
+        conn.execute_batch("ALTER TABLE holiday_cache ADD COLUMN source TEXT;")?;
+    }
+    if !column_exists(conn, "holiday_cache", "region") {
+        // Key the cache by (region, date) so several countries can coexist.
+        conn.execute_batch(
+            "ALTER TABLE holiday_cache RENAME TO holiday_cache_legacy;
+             CREATE TABLE holiday_cache (
+                 region TEXT NOT NULL DEFAULT 'CN',
+                 date TEXT NOT NULL,
+                 is_holiday INTEGER NOT NULL,
+                 is_workday INTEGER NOT NULL,
+                 name TEXT,
+                 year INTEGER NOT NULL,
+                 source TEXT,
+                 PRIMARY KEY (region, date)
+             );
+             INSERT INTO holiday_cache (region, date, is_holiday, is_workday, name, year, source)
+                 SELECT 'CN', date, is_holiday, is_workday, name, year, source FROM holiday_cache_legacy;
+             DROP TABLE holiday_cache_legacy;
+             CREATE INDEX IF NOT EXISTS idx_holiday_year ON holiday_cache(year);",
+        )?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_holiday_region_year ON holiday_cache(region, year);")?;
+
+    if !column_exists(conn, "tasks", "planned_start_time") {
+        conn.execute_batch(
+            "ALTER TABLE tasks ADD COLUMN planned_start_time TEXT;
+             ALTER TABLE tasks ADD COLUMN planned_end_time TEXT;",
+        )?;
+    }
+    if !column_exists(conn, "import_history", "rows_updated") {
+        conn.execute_batch(
+            "ALTER TABLE import_history ADD COLUMN rows_updated INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE import_history ADD COLUMN rolled_back_at TEXT;",
+        )?;
+    }
+    // The settings page used to save header row 0 by default; kept, it would now turn
+    // header detection off. Dropped once, so a 0 chosen later is respected.
+    let header_row_migrated: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM app_settings WHERE key = 'migration.auto_header_row'",
+        [],
+        |r| r.get(0),
+    )?;
+    if header_row_migrated == 0 {
+        conn.execute_batch(
+            "DELETE FROM app_settings WHERE key = 'excel_template.header_row' AND value = '0';
+             INSERT INTO app_settings (key, value, category) VALUES ('migration.auto_header_row', '1', 'migration');",
+        )?;
+    }
+    if !column_exists(conn, "developers", "calendar_id") {
+        conn.execute_batch("ALTER TABLE developers ADD COLUMN calendar_id INTEGER REFERENCES region_calendars(id);")?;
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO region_calendars (name, region, weekend_days, is_default) \
+         SELECT '中国大陆', 'CN', '[5,6]', 1 \
+         WHERE NOT EXISTS (SELECT 1 FROM region_calendars WHERE is_default = 1)",
+        [],
+    )?;
+
     Ok(())
 }
 
+fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.prepare(&format!(
+        "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+        table
+    ))
+    .and_then(|mut s| s.query_row(params![column], |r| r.get::<_, i64>(0)))
+    .map(|c| c > 0)
+    .unwrap_or(false)
+}
+
 fn migrate_standup_schema(conn: &Connection) -> Result<()> {
     let has_content_col: bool = conn
         .prepare("SELECT COUNT(*) FROM pragma_table_info('standup_meetings') WHERE name='content'")
@@ -322,6 +544,24 @@ mod tests {
         conn
     }
 
+    #[test]
+    fn legacy_header_row_zero_is_dropped_once() {
+        let conn = setup_conn();
+        let header_row = |conn: &Connection| -> Option<String> {
+            conn.query_row("SELECT value FROM app_settings WHERE key = 'excel_template.header_row'", [], |r| r.get(0))
+                .ok()
+        };
+        conn.execute("INSERT INTO app_settings (key, value) VALUES ('excel_template.header_row', '0')", params![])
+            .unwrap();
+        run_migrations(&conn).unwrap();
+        assert_eq!(header_row(&conn), None);
+
+        conn.execute("INSERT INTO app_settings (key, value) VALUES ('excel_template.header_row', '0')", params![])
+            .unwrap();
+        run_migrations(&conn).unwrap();
+        assert_eq!(header_row(&conn).as_deref(), Some("0"));
+    }
+
     #[test]
     fn standup_migration_backfills_legacy_entries_to_markdown() {
         let conn = setup_conn();
@@ -4,6 +4,11 @@ pub mod developer_repo;
 pub mod sprint_repo;
 pub mod settings_repo;
 pub mod standup_repo;
+pub mod capacity_repo;
+pub mod team_repo;
+pub mod calendar_repo;
+pub mod baseline_repo;
+pub mod scenario_repo;
 
 use rusqlite::Connection;
 use std::sync::Mutex;
@@ -0,0 +1,142 @@
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::scenario::{CreateScenarioDeveloperDto, CreateScenarioDto, Scenario, ScenarioDeveloper, ScenarioTask};
+
+const SCENARIO_SELECT: &str = "SELECT sc.id, sc.name, sc.description, sc.created_at, sc.applied_at, \
+     (SELECT COUNT(*) FROM scenario_tasks st WHERE st.scenario_id = sc.id) \
+     FROM scenarios sc";
+
+fn map_scenario(row: &Row) -> Result<Scenario> {
+    Ok(Scenario {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        created_at: row.get(3)?,
+        applied_at: row.get(4)?,
+        task_count: row.get(5)?,
+    })
+}
+
+pub fn get_all(conn: &Connection) -> Result<Vec<Scenario>> {
+    let sql = format!("{} ORDER BY sc.created_at DESC, sc.id DESC", SCENARIO_SELECT);
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], map_scenario)?;
+    rows.collect()
+}
+
+pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Scenario>> {
+    let sql = format!("{} WHERE sc.id = ?1", SCENARIO_SELECT);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![id], map_scenario)?;
+    rows.next().transpose()
+}
+
+pub fn create(conn: &Connection, dto: &CreateScenarioDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO scenarios (name, description) VALUES (?1, ?2)",
+        params![dto.name.trim(), dto.description],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn mark_applied(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE scenarios SET applied_at = datetime('now', 'localtime') WHERE id = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
+pub fn delete(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM scenario_tasks WHERE scenario_id = ?1", params![id])?;
+    conn.execute("DELETE FROM scenario_developers WHERE scenario_id = ?1", params![id])?;
+    conn.execute("DELETE FROM scenarios WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn get_developers(conn: &Connection, scenario_id: i64) -> Result<Vec<ScenarioDeveloper>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, scenario_id, name, max_hours_per_day FROM scenario_developers \
+         WHERE scenario_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![scenario_id], |row| {
+        Ok(ScenarioDeveloper {
+            id: row.get(0)?,
+            scenario_id: row.get(1)?,
+            name: row.get(2)?,
+            max_hours_per_day: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn create_developer(conn: &Connection, dto: &CreateScenarioDeveloperDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO scenario_developers (scenario_id, name, max_hours_per_day) VALUES (?1, ?2, ?3)",
+        params![dto.scenario_id, dto.name.trim(), dto.max_hours_per_day.unwrap_or(8.0)],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn delete_developer(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM scenario_developers WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn get_tasks(conn: &Connection, scenario_id: i64) -> Result<Vec<ScenarioTask>> {
+    let mut stmt = conn.prepare(
+        "SELECT st.scenario_id, st.task_id, t.name, st.owner_id, st.scenario_developer_id, \
+                st.planned_start, st.planned_end, st.planned_hours, \
+                st.base_owner_id, st.base_start, st.base_end, st.base_hours \
+         FROM scenario_tasks st JOIN tasks t ON t.id = st.task_id \
+         WHERE st.scenario_id = ?1 ORDER BY st.task_id",
+    )?;
+    let rows = stmt.query_map(params![scenario_id], |row| {
+        Ok(ScenarioTask {
+            scenario_id: row.get(0)?,
+            task_id: row.get(1)?,
+            task_name: row.get(2)?,
+            owner_id: row.get(3)?,
+            scenario_developer_id: row.get(4)?,
+            planned_start: row.get(5)?,
+            planned_end: row.get(6)?,
+            planned_hours: row.get(7)?,
+            base_owner_id: row.get(8)?,
+            base_start: row.get(9)?,
+            base_end: row.get(10)?,
+            base_hours: row.get(11)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn upsert_task(conn: &Connection, task: &ScenarioTask) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO scenario_tasks \
+         (scenario_id, task_id, owner_id, scenario_developer_id, planned_start, planned_end, planned_hours, \
+          base_owner_id, base_start, base_end, base_hours) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            task.scenario_id,
+            task.task_id,
+            task.owner_id,
+            task.scenario_developer_id,
+            task.planned_start,
+            task.planned_end,
+            task.planned_hours,
+            task.base_owner_id,
+            task.base_start,
+            task.base_end,
+            task.base_hours
+        ],
+    )?;
+    Ok(())
+}
+
+/// Drop a task's edits so it reads through to the live plan again.
+pub fn delete_task(conn: &Connection, scenario_id: i64, task_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM scenario_tasks WHERE scenario_id = ?1 AND task_id = ?2",
+        params![scenario_id, task_id],
+    )?;
+    Ok(())
+}
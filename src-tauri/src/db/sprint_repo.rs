@@ -1,5 +1,8 @@
-use rusqlite::{params, Connection, Result};
-use crate::models::sprint::{Sprint, Project, CreateSprintDto, CreateProjectDto, UpdateSprintDto, DeleteSprintResult};
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::sprint::{
+    Sprint, Project, CreateSprintDto, CreateProjectDto, UpdateSprintDto, DeleteSprintResult, Milestone,
+    CreateMilestoneDto, UpdateMilestoneDto,
+};
 
 /// 迭代列表（含关联任务数）by AI.Coding
 pub fn get_all_sprints(conn: &Connection) -> Result<Vec<Sprint>> {
@@ -97,6 +100,7 @@ pub fn create_sprint(conn: &Connection, dto: &CreateSprintDto) -> Result<i64> {
 }
 
 pub fn delete_sprint(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("UPDATE milestones SET sprint_id = NULL WHERE sprint_id = ?1", params![id])?;
     conn.execute("DELETE FROM sprints WHERE id = ?1", params![id])?;
     Ok(())
 }
@@ -219,3 +223,62 @@ pub fn create_project(conn: &Connection, dto: &CreateProjectDto) -> Result<i64>
     )?;
     Ok(conn.last_insert_rowid())
 }
+
+// Milestone operations
+const MILESTONE_SELECT: &str = "SELECT m.id, m.name, m.date, m.sprint_id, s.name, m.note \
+     FROM milestones m LEFT JOIN sprints s ON s.id = m.sprint_id";
+
+fn map_milestone(row: &Row) -> Result<Milestone> {
+    Ok(Milestone {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        date: row.get(2)?,
+        sprint_id: row.get(3)?,
+        sprint_name: row.get(4)?,
+        note: row.get(5)?,
+    })
+}
+
+/// Milestones dated within `[start_date, end_date]`; either bound may be open.
+pub fn get_milestones(conn: &Connection, start_date: Option<&str>, end_date: Option<&str>) -> Result<Vec<Milestone>> {
+    let sql = format!(
+        "{} WHERE (?1 IS NULL OR m.date >= ?1) AND (?2 IS NULL OR m.date <= ?2) ORDER BY m.date, m.id",
+        MILESTONE_SELECT
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![start_date, end_date], map_milestone)?;
+    rows.collect()
+}
+
+pub fn get_milestone_by_id(conn: &Connection, id: i64) -> Result<Option<Milestone>> {
+    let sql = format!("{} WHERE m.id = ?1", MILESTONE_SELECT);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![id], map_milestone)?;
+    rows.next().transpose()
+}
+
+pub fn create_milestone(conn: &Connection, dto: &CreateMilestoneDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO milestones (name, date, sprint_id, note) VALUES (?1, ?2, ?3, ?4)",
+        params![dto.name, dto.date, dto.sprint_id, dto.note],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_milestone(conn: &Connection, dto: &UpdateMilestoneDto) -> Result<()> {
+    let current = get_milestone_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let name = dto.name.as_ref().unwrap_or(&current.name);
+    let date = dto.date.as_ref().unwrap_or(&current.date);
+    let sprint_id = dto.sprint_id.or(current.sprint_id);
+    let note = dto.note.as_ref().or(current.note.as_ref());
+    conn.execute(
+        "UPDATE milestones SET name = ?1, date = ?2, sprint_id = ?3, note = ?4 WHERE id = ?5",
+        params![name, date, sprint_id, note, dto.id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_milestone(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM milestones WHERE id = ?1", params![id])?;
+    Ok(())
+}
@@ -1,16 +1,47 @@
-use crate::models::task::{CoOwner, CreateTaskDto, Task, TaskFilter, UpdateTaskDto};
-use rusqlite::{params, Connection, Result};
+use crate::models::developer::SkillLevel;
+use crate::models::task::{CoOwner, CreateTaskDto, DailyAllocation, Task, TaskDependency, TaskFilter, UpdateTaskDto};
+use rusqlite::{params, Connection, Result, Row};
+
+/// Shared SELECT/FROM clause for task queries; callers append WHERE/ORDER BY.
+const TASK_SELECT: &str = "SELECT t.id, t.external_id, t.task_type, t.name, t.description, t.owner_id, d.name as owner_name, \
+     t.sprint_id, s.name as sprint_name, t.priority, t.planned_start, t.planned_end, \
+     t.planned_hours, t.parent_task_id, t.parent_number, t.parent_name, t.status, t.required_skills, \
+     t.planned_start_time, t.planned_end_time \
+     FROM tasks t \
+     LEFT JOIN developers d ON t.owner_id = d.id \
+     LEFT JOIN sprints s ON t.sprint_id = s.id";
+
+fn map_task_row(row: &Row) -> Result<Task> {
+    let required_skills: Option<String> = row.get(17)?;
+    Ok(Task {
+        id: row.get(0)?,
+        external_id: row.get(1)?,
+        task_type: row.get(2)?,
+        name: row.get(3)?,
+        description: row.get(4)?,
+        owner_id: row.get(5)?,
+        owner_name: row.get(6)?,
+        sprint_id: row.get(7)?,
+        sprint_name: row.get(8)?,
+        priority: row.get(9)?,
+        planned_start: row.get(10)?,
+        planned_end: row.get(11)?,
+        planned_start_time: row.get(18)?,
+        planned_end_time: row.get(19)?,
+        planned_hours: row.get(12)?,
+        parent_task_id: row.get(13)?,
+        parent_number: row.get(14)?,
+        parent_name: row.get(15)?,
+        status: row.get(16)?,
+        co_owners: None,
+        required_skills: required_skills
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+    })
+}
 
 pub fn get_all(conn: &Connection, filter: &TaskFilter) -> Result<Vec<Task>> {
-    let mut sql = String::from(
-        "SELECT t.id, t.external_id, t.task_type, t.name, t.description, t.owner_id, d.name as owner_name, \
-         t.sprint_id, s.name as sprint_name, t.priority, t.planned_start, t.planned_end, \
-         t.planned_hours, t.parent_task_id, t.parent_number, t.parent_name, t.status \
-         FROM tasks t \
-         LEFT JOIN developers d ON t.owner_id = d.id \
-         LEFT JOIN sprints s ON t.sprint_id = s.id \
-         WHERE 1=1"
-    );
+    let mut sql = format!("{} WHERE 1=1", TASK_SELECT);
     let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
     let mut param_idx = 1;
 
@@ -96,6 +127,17 @@ pub fn get_all(conn: &Connection, filter: &TaskFilter) -> Result<Vec<Task>> {
         }
     }
 
+    if let Some(team_id) = filter.team_id {
+        sql.push_str(&format!(
+            " AND (t.owner_id IN (SELECT developer_id FROM team_members WHERE team_id = ?{0}) \
+             OR EXISTS (SELECT 1 FROM task_co_owners tc JOIN team_members tm ON tm.developer_id = tc.developer_id \
+             WHERE tc.task_id = t.id AND tm.team_id = ?{0}))",
+            param_idx
+        ));
+        param_values.push(Box::new(team_id));
+        param_idx += 1;
+    }
+
     // Default ordering: planned_start ascending (nulls last), then id descending
     // Keep this consistent for both task list and Excel export.
     sql.push_str(" ORDER BY (t.planned_start IS NULL) ASC, t.planned_start ASC, t.id DESC");
@@ -103,28 +145,7 @@ pub fn get_all(conn: &Connection, filter: &TaskFilter) -> Result<Vec<Task>> {
     let mut stmt = conn.prepare(&sql)?;
     let param_refs: Vec<&dyn rusqlite::types::ToSql> =
         param_values.iter().map(|p| p.as_ref()).collect();
-    let rows = stmt.query_map(param_refs.as_slice(), |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            external_id: row.get(1)?,
-            task_type: row.get(2)?,
-            name: row.get(3)?,
-            description: row.get(4)?,
-            owner_id: row.get(5)?,
-            owner_name: row.get(6)?,
-            sprint_id: row.get(7)?,
-            sprint_name: row.get(8)?,
-            priority: row.get(9)?,
-            planned_start: row.get(10)?,
-            planned_end: row.get(11)?,
-            planned_hours: row.get(12)?,
-            parent_task_id: row.get(13)?,
-            parent_number: row.get(14)?,
-            parent_name: row.get(15)?,
-            status: row.get(16)?,
-            co_owners: None,
-        })
-    })?;
+    let rows = stmt.query_map(param_refs.as_slice(), map_task_row)?;
 
     let mut tasks: Vec<Task> = rows.collect::<Result<Vec<_>>>()?;
 
@@ -137,37 +158,8 @@ pub fn get_all(conn: &Connection, filter: &TaskFilter) -> Result<Vec<Task>> {
 }
 
 pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Task>> {
-    let mut stmt = conn.prepare(
-        "SELECT t.id, t.external_id, t.task_type, t.name, t.description, t.owner_id, d.name as owner_name, \
-         t.sprint_id, s.name as sprint_name, t.priority, t.planned_start, t.planned_end, \
-         t.planned_hours, t.parent_task_id, t.parent_number, t.parent_name, t.status \
-         FROM tasks t \
-         LEFT JOIN developers d ON t.owner_id = d.id \
-         LEFT JOIN sprints s ON t.sprint_id = s.id \
-         WHERE t.id = ?1"
-    )?;
-    let mut rows = stmt.query_map(params![id], |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            external_id: row.get(1)?,
-            task_type: row.get(2)?,
-            name: row.get(3)?,
-            description: row.get(4)?,
-            owner_id: row.get(5)?,
-            owner_name: row.get(6)?,
-            sprint_id: row.get(7)?,
-            sprint_name: row.get(8)?,
-            priority: row.get(9)?,
-            planned_start: row.get(10)?,
-            planned_end: row.get(11)?,
-            planned_hours: row.get(12)?,
-            parent_task_id: row.get(13)?,
-            parent_number: row.get(14)?,
-            parent_name: row.get(15)?,
-            status: row.get(16)?,
-            co_owners: None,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!("{} WHERE t.id = ?1", TASK_SELECT))?;
+    let mut rows = stmt.query_map(params![id], map_task_row)?;
 
     match rows.next() {
         Some(row) => {
@@ -182,14 +174,17 @@ pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Task>> {
 pub fn create(conn: &Connection, dto: &CreateTaskDto) -> Result<i64> {
     conn.execute(
         "INSERT INTO tasks (external_id, task_type, name, description, owner_id, sprint_id, priority, \
-         planned_start, planned_end, planned_hours, parent_task_id, parent_number, parent_name, status) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+         planned_start, planned_end, planned_hours, parent_task_id, parent_number, parent_name, status, \
+         required_skills, planned_start_time, planned_end_time) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
         params![
             dto.external_id, dto.task_type, dto.name, dto.description,
             dto.owner_id, dto.sprint_id, dto.priority,
             dto.planned_start, dto.planned_end, dto.planned_hours,
             dto.parent_task_id, dto.parent_number, dto.parent_name,
-            dto.status.as_deref().unwrap_or("待开始")
+            dto.status.as_deref().unwrap_or("待开始"),
+            skills_to_json(dto.required_skills.as_deref()),
+            dto.planned_start_time, dto.planned_end_time
         ],
     )?;
     let task_id = conn.last_insert_rowid();
@@ -206,6 +201,13 @@ pub fn create(conn: &Connection, dto: &CreateTaskDto) -> Result<i64> {
     Ok(task_id)
 }
 
+/// Serialize required skills; an empty list is stored as NULL.
+fn skills_to_json(skills: Option<&[SkillLevel]>) -> Option<String> {
+    skills
+        .filter(|s| !s.is_empty())
+        .map(|s| serde_json::to_string(s).unwrap_or_default())
+}
+
 pub fn update(conn: &Connection, dto: &UpdateTaskDto) -> Result<()> {
     let current = get_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
 
@@ -221,10 +223,14 @@ pub fn update(conn: &Connection, dto: &UpdateTaskDto) -> Result<()> {
         }
     };
 
+    let required_skills = dto.required_skills.as_ref().unwrap_or(&current.required_skills);
+
     conn.execute(
         "UPDATE tasks SET external_id=?1, task_type=?2, name=?3, description=?4, owner_id=?5, \
          sprint_id=?6, priority=?7, planned_start=?8, planned_end=?9, planned_hours=?10, \
-         parent_task_id=?11, parent_number=?12, parent_name=?13, status=?14 WHERE id=?15",
+         parent_task_id=?11, parent_number=?12, parent_name=?13, status=?14, required_skills=?15, \
+         planned_start_time=?16, planned_end_time=?17 \
+         WHERE id=?18",
         params![
             resolve_str(&dto.external_id, &current.external_id),
             resolve_str(&dto.task_type, &current.task_type),
@@ -240,6 +246,9 @@ pub fn update(conn: &Connection, dto: &UpdateTaskDto) -> Result<()> {
             resolve_str(&dto.parent_number, &current.parent_number),
             resolve_str(&dto.parent_name, &current.parent_name),
             resolve_str(&dto.status, &current.status),
+            skills_to_json(Some(required_skills)),
+            resolve_str(&dto.planned_start_time, &current.planned_start_time),
+            resolve_str(&dto.planned_end_time, &current.planned_end_time),
             dto.id
         ],
     )?;
@@ -260,12 +269,90 @@ pub fn update(conn: &Connection, dto: &UpdateTaskDto) -> Result<()> {
     Ok(())
 }
 
+/// Write back the fields an import can change, from a snapshot taken before it.
+pub fn restore_imported_fields(conn: &Connection, task: &Task) -> Result<()> {
+    conn.execute(
+        "UPDATE tasks SET external_id=?1, task_type=?2, name=?3, description=?4, owner_id=?5, \
+         sprint_id=?6, priority=?7, planned_start=?8, planned_end=?9, planned_hours=?10, \
+         parent_number=?11, parent_name=?12, status=?13 \
+         WHERE id=?14",
+        params![
+            task.external_id,
+            task.task_type,
+            task.name,
+            task.description,
+            task.owner_id,
+            task.sprint_id,
+            task.priority,
+            task.planned_start,
+            task.planned_end,
+            task.planned_hours,
+            task.parent_number,
+            task.parent_name,
+            task.status,
+            task.id
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn delete(conn: &Connection, id: i64) -> Result<()> {
     conn.execute("DELETE FROM task_co_owners WHERE task_id = ?1", params![id])?;
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE predecessor_id = ?1 OR successor_id = ?1",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM task_daily_allocations WHERE task_id = ?1", params![id])?;
+    conn.execute("DELETE FROM scenario_tasks WHERE task_id = ?1", params![id])?;
     conn.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
     Ok(())
 }
 
+pub fn set_owner(conn: &Connection, task_id: i64, owner_id: Option<i64>) -> Result<()> {
+    conn.execute("UPDATE tasks SET owner_id = ?1 WHERE id = ?2", params![owner_id, task_id])?;
+    Ok(())
+}
+
+pub fn set_dates(conn: &Connection, task_id: i64, planned_start: &str, planned_end: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE tasks SET planned_start = ?1, planned_end = ?2 WHERE id = ?3",
+        params![planned_start, planned_end, task_id],
+    )?;
+    Ok(())
+}
+
+/// Overwrite the planning fields of a task (owner, dates, hours).
+pub fn set_plan(
+    conn: &Connection,
+    task_id: i64,
+    owner_id: Option<i64>,
+    planned_start: Option<&str>,
+    planned_end: Option<&str>,
+    planned_hours: Option<f64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE tasks SET owner_id = ?1, planned_start = ?2, planned_end = ?3, planned_hours = ?4 WHERE id = ?5",
+        params![owner_id, planned_start, planned_end, planned_hours, task_id],
+    )?;
+    Ok(())
+}
+
+pub fn add_co_owner(conn: &Connection, task_id: i64, developer_id: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO task_co_owners (task_id, developer_id) VALUES (?1, ?2)",
+        params![task_id, developer_id],
+    )?;
+    Ok(())
+}
+
+pub fn remove_co_owner(conn: &Connection, task_id: i64, developer_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM task_co_owners WHERE task_id = ?1 AND developer_id = ?2",
+        params![task_id, developer_id],
+    )?;
+    Ok(())
+}
+
 pub fn get_co_owners(conn: &Connection, task_id: i64) -> Result<Vec<CoOwner>> {
     let mut stmt = conn.prepare(
         "SELECT tc.developer_id, d.name FROM task_co_owners tc \
@@ -286,41 +373,15 @@ pub fn get_tasks_for_developer_in_range(
     start_date: &str,
     end_date: &str,
 ) -> Result<Vec<Task>> {
-    let mut stmt = conn.prepare(
-        "SELECT t.id, t.external_id, t.task_type, t.name, t.description, t.owner_id, d.name as owner_name, \
-         t.sprint_id, s.name as sprint_name, t.priority, t.planned_start, t.planned_end, \
-         t.planned_hours, t.parent_task_id, t.parent_number, t.parent_name, t.status \
-         FROM tasks t \
-         LEFT JOIN developers d ON t.owner_id = d.id \
-         LEFT JOIN sprints s ON t.sprint_id = s.id \
-         WHERE t.owner_id = ?1 \
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE t.owner_id = ?1 \
          AND t.planned_start IS NOT NULL AND t.planned_end IS NOT NULL \
          AND t.planned_start <= ?3 AND t.planned_end >= ?2 \
          AND t.status NOT IN ('已取消') \
-         ORDER BY t.planned_start"
-    )?;
-    let rows = stmt.query_map(params![developer_id, start_date, end_date], |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            external_id: row.get(1)?,
-            task_type: row.get(2)?,
-            name: row.get(3)?,
-            description: row.get(4)?,
-            owner_id: row.get(5)?,
-            owner_name: row.get(6)?,
-            sprint_id: row.get(7)?,
-            sprint_name: row.get(8)?,
-            priority: row.get(9)?,
-            planned_start: row.get(10)?,
-            planned_end: row.get(11)?,
-            planned_hours: row.get(12)?,
-            parent_task_id: row.get(13)?,
-            parent_number: row.get(14)?,
-            parent_name: row.get(15)?,
-            status: row.get(16)?,
-            co_owners: None,
-        })
-    })?;
+         ORDER BY t.planned_start",
+        TASK_SELECT
+    ))?;
+    let rows = stmt.query_map(params![developer_id, start_date, end_date], map_task_row)?;
     rows.collect()
 }
 
@@ -333,39 +394,86 @@ pub fn get_tasks_in_date_range(
     start_date: &str,
     end_date: &str,
 ) -> Result<Vec<Task>> {
-    let mut stmt = conn.prepare(
-        "SELECT t.id, t.external_id, t.task_type, t.name, t.description, t.owner_id, d.name as owner_name, \
-         t.sprint_id, s.name as sprint_name, t.priority, t.planned_start, t.planned_end, \
-         t.planned_hours, t.parent_task_id, t.parent_number, t.parent_name, t.status \
-         FROM tasks t \
-         LEFT JOIN developers d ON t.owner_id = d.id \
-         LEFT JOIN sprints s ON t.sprint_id = s.id \
-         WHERE t.planned_start IS NOT NULL AND t.planned_end IS NOT NULL \
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE t.planned_start IS NOT NULL AND t.planned_end IS NOT NULL \
          AND t.planned_start <= ?2 AND t.planned_end >= ?1 \
          AND t.status NOT IN ('已取消') \
-         ORDER BY t.planned_start"
+         ORDER BY t.planned_start",
+        TASK_SELECT
+    ))?;
+    let rows = stmt.query_map(params![start_date, end_date], map_task_row)?;
+    rows.collect()
+}
+
+fn map_dependency(row: &Row) -> Result<TaskDependency> {
+    Ok(TaskDependency {
+        predecessor_id: row.get(0)?,
+        successor_id: row.get(1)?,
+    })
+}
+
+pub fn get_all_dependencies(conn: &Connection) -> Result<Vec<TaskDependency>> {
+    let mut stmt = conn.prepare("SELECT predecessor_id, successor_id FROM task_dependencies ORDER BY predecessor_id, successor_id")?;
+    let rows = stmt.query_map([], map_dependency)?;
+    rows.collect()
+}
+
+/// Dependencies where `task_id` is either the predecessor or the successor.
+pub fn get_dependencies_for_task(conn: &Connection, task_id: i64) -> Result<Vec<TaskDependency>> {
+    let mut stmt = conn.prepare(
+        "SELECT predecessor_id, successor_id FROM task_dependencies \
+         WHERE predecessor_id = ?1 OR successor_id = ?1 ORDER BY predecessor_id, successor_id",
     )?;
-    let rows = stmt.query_map(params![start_date, end_date], |row| {
-        Ok(Task {
-            id: row.get(0)?,
-            external_id: row.get(1)?,
-            task_type: row.get(2)?,
-            name: row.get(3)?,
-            description: row.get(4)?,
-            owner_id: row.get(5)?,
-            owner_name: row.get(6)?,
-            sprint_id: row.get(7)?,
-            sprint_name: row.get(8)?,
-            priority: row.get(9)?,
-            planned_start: row.get(10)?,
-            planned_end: row.get(11)?,
-            planned_hours: row.get(12)?,
-            parent_task_id: row.get(13)?,
-            parent_number: row.get(14)?,
-            parent_name: row.get(15)?,
-            status: row.get(16)?,
-            co_owners: None,
-        })
-    })?;
+    let rows = stmt.query_map(params![task_id], map_dependency)?;
     rows.collect()
 }
+
+pub fn add_dependency(conn: &Connection, predecessor_id: i64, successor_id: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO task_dependencies (predecessor_id, successor_id) VALUES (?1, ?2)",
+        params![predecessor_id, successor_id],
+    )?;
+    Ok(())
+}
+
+pub fn remove_dependency(conn: &Connection, predecessor_id: i64, successor_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE predecessor_id = ?1 AND successor_id = ?2",
+        params![predecessor_id, successor_id],
+    )?;
+    Ok(())
+}
+
+fn map_allocation(row: &Row) -> Result<DailyAllocation> {
+    Ok(DailyAllocation {
+        task_id: row.get(0)?,
+        date: row.get(1)?,
+        hours: row.get(2)?,
+    })
+}
+
+pub fn get_daily_allocations(conn: &Connection, task_id: i64) -> Result<Vec<DailyAllocation>> {
+    let mut stmt = conn.prepare(
+        "SELECT task_id, date, hours FROM task_daily_allocations WHERE task_id = ?1 ORDER BY date",
+    )?;
+    let rows = stmt.query_map(params![task_id], map_allocation)?;
+    rows.collect()
+}
+
+pub fn get_all_daily_allocations(conn: &Connection) -> Result<Vec<DailyAllocation>> {
+    let mut stmt = conn.prepare("SELECT task_id, date, hours FROM task_daily_allocations ORDER BY task_id, date")?;
+    let rows = stmt.query_map([], map_allocation)?;
+    rows.collect()
+}
+
+/// Replace all pinned days of `task_id` with `allocations`.
+pub fn set_daily_allocations(conn: &Connection, task_id: i64, allocations: &[DailyAllocation]) -> Result<()> {
+    conn.execute("DELETE FROM task_daily_allocations WHERE task_id = ?1", params![task_id])?;
+    for a in allocations {
+        conn.execute(
+            "INSERT OR REPLACE INTO task_daily_allocations (task_id, date, hours) VALUES (?1, ?2, ?3)",
+            params![task_id, a.date, a.hours],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use rusqlite::{params, Connection, Result, Row};
+use crate::models::team::{Team, CreateTeamDto, UpdateTeamDto};
+
+fn map_team(row: &Row) -> Result<Team> {
+    Ok(Team {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        color: row.get(3)?,
+        member_ids: Vec::new(),
+    })
+}
+
+pub fn get_member_ids(conn: &Connection, team_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT tm.developer_id FROM team_members tm \
+         JOIN developers d ON d.id = tm.developer_id \
+         WHERE tm.team_id = ?1 ORDER BY d.name",
+    )?;
+    let rows = stmt.query_map(params![team_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+pub fn get_all(conn: &Connection) -> Result<Vec<Team>> {
+    let mut stmt = conn.prepare("SELECT id, name, description, color FROM teams ORDER BY name")?;
+    let mut teams: Vec<Team> = stmt.query_map([], map_team)?.collect::<Result<Vec<_>>>()?;
+    for team in &mut teams {
+        team.member_ids = get_member_ids(conn, team.id)?;
+    }
+    Ok(teams)
+}
+
+pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Team>> {
+    let mut stmt = conn.prepare("SELECT id, name, description, color FROM teams WHERE id = ?1")?;
+    let mut rows = stmt.query_map(params![id], map_team)?;
+    match rows.next() {
+        Some(row) => {
+            let mut team = row?;
+            team.member_ids = get_member_ids(conn, team.id)?;
+            Ok(Some(team))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn set_members(conn: &Connection, team_id: i64, member_ids: &[i64]) -> Result<()> {
+    conn.execute("DELETE FROM team_members WHERE team_id = ?1", params![team_id])?;
+    for dev_id in member_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO team_members (team_id, developer_id) VALUES (?1, ?2)",
+            params![team_id, dev_id],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn create(conn: &Connection, dto: &CreateTeamDto) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO teams (name, description, color) VALUES (?1, ?2, ?3)",
+        params![dto.name, dto.description, dto.color],
+    )?;
+    let id = conn.last_insert_rowid();
+    if let Some(ref member_ids) = dto.member_ids {
+        set_members(conn, id, member_ids)?;
+    }
+    Ok(id)
+}
+
+pub fn update(conn: &Connection, dto: &UpdateTeamDto) -> Result<()> {
+    let current = get_by_id(conn, dto.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    let name = dto.name.as_ref().unwrap_or(&current.name);
+    let description = dto.description.as_ref().or(current.description.as_ref());
+    let color = dto.color.as_ref().or(current.color.as_ref());
+    conn.execute(
+        "UPDATE teams SET name = ?1, description = ?2, color = ?3 WHERE id = ?4",
+        params![name, description, color, dto.id],
+    )?;
+    if let Some(ref member_ids) = dto.member_ids {
+        set_members(conn, dto.id, member_ids)?;
+    }
+    Ok(())
+}
+
+/// Membership rows go with the team through `ON DELETE CASCADE`.
+pub fn delete(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM teams WHERE id = ?1", params![id])?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::excel::layout::{split_table, HeaderLayout};
+use crate::excel::reader::{ExcelFileInfo, SheetInfo};
+use encoding_rs::{GB18030, UTF_8};
+use std::path::Path;
+
+/// File extensions read as delimited text instead of a workbook.
+pub const DELIMITED_EXTENSIONS: [&str; 3] = ["csv", "tsv", "txt"];
+
+pub fn is_delimited_file(file_path: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| DELIMITED_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Decode file bytes as UTF-8 (with or without BOM), falling back to GBK/GB18030,
+/// the default encoding of CSVs saved by Excel on Chinese Windows.
+pub fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    if let Some(text) = UTF_8.decode_without_bom_handling_and_without_replacement(bytes) {
+        return text.into_owned();
+    }
+    let (text, _, _) = GB18030.decode(bytes);
+    text.into_owned()
+}
+
+/// Pick the delimiter: tab for `.tsv`, otherwise whichever of tab, comma and
+/// semicolon occurs most in the first line (pasted spreadsheet cells are tab-separated).
+pub fn detect_delimiter(text: &str, extension: Option<&str>) -> u8 {
+    if extension == Some("tsv") {
+        return b'\t';
+    }
+    let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    [b'\t', b',', b';']
+        .into_iter()
+        .max_by_key(|d| (first_line.bytes().filter(|b| b == d).count(), *d == b','))
+        .filter(|d| first_line.as_bytes().contains(d))
+        .unwrap_or(b',')
+}
+
+/// Parse delimited text into rows of trimmed cells. Quoted fields may contain the
+/// delimiter and line breaks; short rows are padded to the widest row.
+pub fn parse_rows(text: &str, delimiter: u8) -> Result<Vec<Vec<String>>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("解析分隔文本失败: {}", e))?;
+        let row: Vec<String> = record.iter().map(|c| c.trim().to_string()).collect();
+        if row.iter().all(|c| c.is_empty()) {
+            continue;
+        }
+        rows.push(row);
+    }
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, String::new());
+    }
+    Ok(rows)
+}
+
+/// Split parsed rows into the header row and data rows.
+pub fn split_header(mut rows: Vec<Vec<String>>) -> (Vec<String>, Vec<Vec<String>>) {
+    if rows.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let headers = rows.remove(0);
+    (headers, rows)
+}
+
+/// Header and data rows of a CSV/TSV file, below any title lines above the header.
+fn read_delimited_table(
+    file_path: &str,
+    header_row: Option<usize>,
+) -> Result<(HeaderLayout, Vec<Vec<String>>), String> {
+    let path = Path::new(file_path);
+    let bytes = std::fs::read(path).map_err(|e| format!("无法读取文件: {}", e))?;
+    let text = decode_text(&bytes);
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    let rows = parse_rows(&text, detect_delimiter(&text, extension.as_deref()))?;
+    // Text files never record merged cells, so blank group cells are filled down.
+    Ok(split_table(&rows, header_row, true))
+}
+
+/// Headers and data rows of a CSV/TSV file.
+pub fn read_delimited_data(
+    file_path: &str,
+    header_row: Option<usize>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let (layout, rows) = read_delimited_table(file_path, header_row)?;
+    Ok((layout.headers, rows))
+}
+
+/// Headers and data rows of a table pasted from a spreadsheet or text editor.
+pub fn read_pasted_data(text: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let rows = parse_rows(text, detect_delimiter(text, None))?;
+    Ok(split_header(rows))
+}
+
+/// A delimited file presented as a workbook with a single sheet named after the file,
+/// so it goes through the same sheet scoring and column matching as Excel.
+pub fn read_delimited_info(file_path: &str, header_row: Option<usize>) -> Result<ExcelFileInfo, String> {
+    let path = Path::new(file_path);
+    let file_name = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (layout, rows) = read_delimited_table(file_path, header_row)?;
+    let sheet = SheetInfo {
+        name: sheet_name(file_path),
+        row_count: rows.len(),
+        col_count: layout.headers.len(),
+        headers: layout.headers,
+        sample_rows: rows.into_iter().take(20).collect(),
+        is_hidden: false,
+        header_row: layout.start,
+        header_height: layout.height,
+    };
+    Ok(ExcelFileInfo { file_name, sheets: vec![sheet] })
+}
+
+/// The single sheet name of a delimited file: its file stem.
+pub fn sheet_name(file_path: &str) -> String {
+    Path::new(file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Sheet1".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gbk_csv_is_decoded_and_quoted_fields_survive() {
+        let source = "编号,名称,负责人\nT-1,\"登录, 注册\",张三\n\nT-2,导出,李四\n";
+        let (gbk, _, _) = encoding_rs::GBK.encode(source);
+        let text = decode_text(&gbk);
+        assert_eq!(text, source);
+
+        let rows = parse_rows(&text, detect_delimiter(&text, Some("csv"))).unwrap();
+        let (headers, rows) = split_header(rows);
+        assert_eq!(headers, vec!["编号", "名称", "负责人"]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][1], "登录, 注册");
+    }
+
+    #[test]
+    fn pasted_cells_are_tab_separated() {
+        let text = "\u{feff}名称\t计划工时\n接口联调\t2\n  验收\n";
+        let text = decode_text(text.as_bytes());
+        let (headers, rows) = read_pasted_data(&text).unwrap();
+        assert_eq!(headers, vec!["名称", "计划工时"]);
+        assert_eq!(rows, vec![vec!["接口联调", "2"], vec!["验收", ""]]);
+        assert_eq!(detect_delimiter("a;b;c,d", None), b';');
+    }
+}
@@ -0,0 +1,254 @@
+use crate::excel::column_definitions::{get_task_column_definitions, ColumnDefinition};
+use crate::excel::smart_matcher::header_has_keyword;
+use std::collections::HashSet;
+
+/// Rows scanned for the header when no header row is configured.
+pub const HEADER_SCAN_ROWS: usize = 15;
+
+/// Cells longer than this are text, not column names, and never count as header matches.
+const MAX_HEADER_CHARS: usize = 20;
+
+/// Fields usually written once per group in a merged cell spanning the group's rows.
+const GROUPING_FIELDS: [&str; 3] = ["parent_number", "parent_name", "sprint"];
+
+/// A merged cell region in grid coordinates, inclusive: (first row, first col, last row, last col).
+pub type MergedRegion = (usize, usize, usize, usize);
+
+/// Where the header of a sheet sits and the column names read from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderLayout {
+    /// Index of the first header row.
+    pub start: usize,
+    /// Number of header rows: 1, or 2 for a group row over a row of sub-headers.
+    pub height: usize,
+    pub headers: Vec<String>,
+}
+
+/// Give every cell of each merged region the value of its top-left cell, which is
+/// the only one a workbook stores.
+pub fn fill_merged_regions(grid: &mut [Vec<String>], regions: &[MergedRegion]) {
+    for &(first_row, first_col, last_row, last_col) in regions {
+        let value = match grid.get(first_row).and_then(|row| row.get(first_col)) {
+            Some(v) if !v.is_empty() => v.clone(),
+            _ => continue,
+        };
+        for row in grid.iter_mut().take(last_row + 1).skip(first_row) {
+            for cell in row.iter_mut().take(last_col + 1).skip(first_col) {
+                if cell.is_empty() {
+                    *cell = value.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Number of column definitions with a keyword in one of the headers.
+fn keyword_score(headers: &[String], definitions: &[ColumnDefinition]) -> usize {
+    definitions
+        .iter()
+        .filter(|def| {
+            headers
+                .iter()
+                .any(|h| h.chars().count() <= MAX_HEADER_CHARS && header_has_keyword(h, def))
+        })
+        .count()
+}
+
+/// Numbers and dates mark a data row; header cells are words.
+fn looks_like_value(cell: &str) -> bool {
+    !cell.is_empty() && cell.chars().all(|c| c.is_ascii_digit() || "-./: ".contains(c))
+}
+
+fn cell(row: &[String], col: usize) -> String {
+    row.get(col).map(|c| c.trim().to_string()).unwrap_or_default()
+}
+
+/// End of the group starting at `col`: a label in `upper` spanning the sub-headers of
+/// `lower` to its right, repeated by a merged cell or followed by blanks. Equals `col`
+/// when no group starts there.
+fn group_end(upper: &[String], lower: &[String], col: usize, width: usize) -> usize {
+    let (top, bottom) = (cell(upper, col), cell(lower, col));
+    let mut end = col;
+    if top.is_empty() || bottom.is_empty() || top == bottom {
+        return end;
+    }
+    while end + 1 < width {
+        let (next_top, next_bottom) = (cell(upper, end + 1), cell(lower, end + 1));
+        if next_bottom.is_empty() || !(next_top.is_empty() || next_top == top) {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// Whether `upper` is the group row of a two-row header over `lower`. A banner (one
+/// label across the sheet) is not, nor is a row above numbers or dates.
+fn is_group_row(upper: &[String], lower: &[String]) -> bool {
+    let labels: HashSet<String> = upper.iter().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+    if labels.len() < 2 || lower.iter().any(|c| looks_like_value(c.trim())) {
+        return false;
+    }
+    let width = upper.len().max(lower.len());
+    (0..width).any(|c| group_end(upper, lower, c, width) > c)
+}
+
+/// Column names of a two-row header: under a group label each sub-header is joined to
+/// it, "计划" over "开始" giving "计划开始"; other columns take whichever row is filled,
+/// the upper one for a header merged down both rows.
+pub fn combine_header_rows(upper: &[String], lower: &[String]) -> Vec<String> {
+    let width = upper.len().max(lower.len());
+    let mut names = Vec::with_capacity(width);
+    let mut col = 0;
+    while col < width {
+        let end = group_end(upper, lower, col, width);
+        let top = cell(upper, col);
+        if end > col {
+            names.extend((col..=end).map(|c| format!("{}{}", top, cell(lower, c))));
+        } else if top.is_empty() {
+            names.push(cell(lower, col));
+        } else {
+            names.push(top);
+        }
+        col = end + 1;
+    }
+    names
+}
+
+/// Find the header: the row, or pair of rows, among the first rows of the sheet whose
+/// names match the most task column definitions. Earlier and single-row headers win
+/// ties. A configured row is taken as the start and only checked for a second row.
+/// With no match at all, the first non-blank row is the header.
+pub fn detect_header(grid: &[Vec<String>], configured: Option<usize>) -> HeaderLayout {
+    let definitions = get_task_column_definitions();
+    let starts: Vec<usize> = match configured {
+        Some(row) => vec![row],
+        None => (0..grid.len().min(HEADER_SCAN_ROWS)).collect(),
+    };
+
+    let mut best: Option<(usize, HeaderLayout)> = None;
+    for start in starts {
+        let Some(row) = grid.get(start) else { continue };
+        let mut candidates = vec![(1, row.iter().map(|c| c.trim().to_string()).collect::<Vec<_>>())];
+        if let Some(next) = grid.get(start + 1) {
+            if is_group_row(row, next) {
+                candidates.push((2, combine_header_rows(row, next)));
+            }
+        }
+        for (height, headers) in candidates {
+            let score = keyword_score(&headers, &definitions);
+            if !matches!(best, Some((best_score, _)) if best_score >= score) {
+                best = Some((score, HeaderLayout { start, height, headers }));
+            }
+        }
+    }
+
+    match best {
+        Some((score, layout)) if score > 0 || configured.is_some() => layout,
+        _ => {
+            let start = grid.iter().position(|r| r.iter().any(|c| !c.trim().is_empty())).unwrap_or(0);
+            let headers = grid.get(start).map(|r| r.iter().map(|c| c.trim().to_string()).collect()).unwrap_or_default();
+            HeaderLayout { start, height: 1, headers }
+        }
+    }
+}
+
+/// Split a sheet grid into its header and data rows, skipping anything above the
+/// header and blank rows. With `fill_down`, for formats that do not record merged
+/// cells, a blank parent or sprint cell takes the value above it, as the merged group
+/// cell it was part of would have.
+pub fn split_table(
+    grid: &[Vec<String>],
+    configured: Option<usize>,
+    fill_down: bool,
+) -> (HeaderLayout, Vec<Vec<String>>) {
+    let layout = detect_header(grid, configured);
+    let mut rows: Vec<Vec<String>> = grid
+        .iter()
+        .skip(layout.start + layout.height)
+        .filter(|r| r.iter().any(|c| !c.trim().is_empty()))
+        .cloned()
+        .collect();
+
+    if fill_down {
+        let definitions = get_task_column_definitions();
+        let grouping: Vec<usize> = layout
+            .headers
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| {
+                definitions
+                    .iter()
+                    .any(|def| GROUPING_FIELDS.contains(&def.field.as_str()) && header_has_keyword(h, def))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        for col in grouping {
+            let mut above = String::new();
+            for row in rows.iter_mut() {
+                match row.get_mut(col) {
+                    Some(cell) if cell.trim().is_empty() => *cell = above.clone(),
+                    Some(cell) => above = cell.clone(),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    (layout, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn banner_and_two_row_header_are_found_below_the_title() {
+        // As read from .xls: merged cells keep their value in the top-left cell only.
+        let sheet = grid(&[
+            &["2026 Q1 迭代计划", "", "", "", ""],
+            &["", "", "", "", ""],
+            &["所属模块", "任务名称", "负责人", "计划", ""],
+            &["", "", "", "开始", "结束"],
+            &["账号", "登录", "张三", "2026-03-02", "2026-03-03"],
+            &["", "注册", "李四", "2026-03-04", "2026-03-05"],
+            &["", "", "", "", ""],
+            &["报表", "导出", "张三", "2026-03-06", "2026-03-06"],
+        ]);
+        let expected = vec!["所属模块", "任务名称", "负责人", "计划开始", "计划结束"];
+        let (layout, rows) = split_table(&sheet, None, true);
+        assert_eq!((layout.start, layout.height), (2, 2));
+        assert_eq!(layout.headers, expected);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][..2], ["账号", "注册"]);
+        assert_eq!(rows[2][0], "报表");
+
+        // As read from .xlsx, with the merged regions filled instead of filling down.
+        let mut merged = sheet.clone();
+        fill_merged_regions(&mut merged, &[
+            (0, 0, 0, 4), (2, 0, 3, 0), (2, 1, 3, 1), (2, 2, 3, 2), (2, 3, 2, 4), (4, 0, 5, 0),
+        ]);
+        assert_eq!(merged[3][..4], ["所属模块", "任务名称", "负责人", "开始"]);
+        let (layout, rows) = split_table(&merged, None, false);
+        assert_eq!(layout.headers, expected);
+        assert_eq!(rows[1][0], "账号");
+    }
+
+    #[test]
+    fn single_row_header_is_not_merged_with_the_first_data_row() {
+        let sheet = grid(&[
+            &["编号", "名称", "负责人", "备注", ""],
+            &["T-1", "版本发布", "张三", "上线", "x"],
+        ]);
+        let layout = detect_header(&sheet, None);
+        assert_eq!((layout.start, layout.height), (0, 1));
+
+        let unknown = grid(&[&["", ""], &["foo", "bar"], &["1", "2"]]);
+        assert_eq!(detect_header(&unknown, None).start, 1);
+        assert_eq!(detect_header(&unknown, Some(2)).headers, vec!["1", "2"]);
+    }
+}
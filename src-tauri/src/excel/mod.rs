@@ -1,4 +1,6 @@
 pub mod reader;
+pub mod layout;
+pub mod delimited;
 pub mod writer;
 pub mod smart_matcher;
 pub mod column_definitions;
@@ -1,6 +1,10 @@
-use calamine::{Reader, open_workbook, Xlsx, Data, Range};
+use crate::excel::delimited;
+use crate::excel::layout::{fill_merged_regions, split_table, MergedRegion};
+use calamine::{open_workbook_auto, Data, Range, Reader, SheetVisible, Sheets};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +15,10 @@ pub struct SheetInfo {
     pub headers: Vec<String>,
     pub sample_rows: Vec<Vec<String>>,
     pub is_hidden: bool,
+    /// Index of the first header row, found by detection unless configured.
+    pub header_row: usize,
+    /// Number of header rows: 2 when a group row sits over the column names.
+    pub header_height: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,94 +27,122 @@ pub struct ExcelFileInfo {
     pub sheets: Vec<SheetInfo>,
 }
 
-pub fn read_excel_info(file_path: &str) -> Result<ExcelFileInfo, String> {
+/// Open an .xlsx/.xlsm/.xls/.xlsb/.ods workbook, picking the format from the extension
+/// or, for unknown extensions, from the file content.
+fn open_workbook(path: &Path) -> Result<Sheets<BufReader<File>>, String> {
+    open_workbook_auto(path).map_err(|e| format!("Failed to open Excel file: {}", e))
+}
+
+/// Sheets of a workbook with their headers and sample rows. `header_row` is the
+/// configured header row; when `None` it is detected on each sheet.
+pub fn read_excel_info(file_path: &str, header_row: Option<usize>) -> Result<ExcelFileInfo, String> {
+    if delimited::is_delimited_file(file_path) {
+        return delimited::read_delimited_info(file_path, header_row);
+    }
     let path = Path::new(file_path);
     let file_name = path.file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
 
-    let mut workbook: Xlsx<_> = open_workbook(path)
-        .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+    let mut workbook = open_workbook(path)?;
 
-    let sheet_names = workbook.sheet_names().to_vec();
+    // Chart and macro sheets have no cell range and are skipped.
+    let metadata = workbook.sheets_metadata().to_vec();
     let mut sheets = Vec::new();
 
-    for name in &sheet_names {
-        if let Ok(range) = workbook.worksheet_range(name) {
-            let sheet_info = parse_sheet_info(name, &range);
-            sheets.push(sheet_info);
+    for sheet in &metadata {
+        if let Ok((grid, merges_known)) = read_grid(&mut workbook, &sheet.name) {
+            let hidden = sheet.visible != SheetVisible::Visible;
+            let (layout, rows) = split_table(&grid, header_row, !merges_known);
+            sheets.push(SheetInfo {
+                name: sheet.name.clone(),
+                row_count: rows.len(),
+                col_count: layout.headers.len(),
+                headers: layout.headers,
+                sample_rows: rows.into_iter().take(20).collect(),
+                is_hidden: hidden,
+                header_row: layout.start,
+                header_height: layout.height,
+            });
         }
     }
 
     Ok(ExcelFileInfo { file_name, sheets })
 }
 
-fn parse_sheet_info(name: &str, range: &Range<Data>) -> SheetInfo {
-    let (row_count, col_count) = range.get_size();
-
-    let headers: Vec<String> = if row_count > 0 {
-        (0..col_count)
-            .map(|c| cell_to_string(range.get((0, c))))
-            .collect()
-    } else {
-        Vec::new()
+/// Cells of a sheet as text. Merged regions, which only .xlsx records, are filled
+/// with their top-left value; the flag tells whether merges were known.
+fn read_grid(
+    workbook: &mut Sheets<BufReader<File>>,
+    sheet_name: &str,
+) -> Result<(Vec<Vec<String>>, bool), String> {
+    let range = workbook.worksheet_range(sheet_name)
+        .map_err(|e| format!("Failed to read sheet '{}': {}", sheet_name, e))?;
+    let mut grid = range_to_grid(&range);
+
+    let regions: Option<Vec<MergedRegion>> = match workbook {
+        Sheets::Xlsx(xlsx) => xlsx.load_merged_regions().ok().map(|_| {
+            // Regions are in sheet coordinates, the grid starts at the range's first cell.
+            let (top, left) = range.start().unwrap_or((0, 0));
+            xlsx.merged_regions_by_sheet(sheet_name)
+                .into_iter()
+                .filter_map(|(_, _, d)| {
+                    Some((
+                        d.start.0.checked_sub(top)? as usize,
+                        d.start.1.checked_sub(left)? as usize,
+                        d.end.0.checked_sub(top)? as usize,
+                        d.end.1.checked_sub(left)? as usize,
+                    ))
+                })
+                .collect()
+        }),
+        _ => None,
     };
+    if let Some(ref regions) = regions {
+        fill_merged_regions(&mut grid, regions);
+    }
+    Ok((grid, regions.is_some()))
+}
 
-    let sample_rows: Vec<Vec<String>> = (1..std::cmp::min(21, row_count))
+fn range_to_grid(range: &Range<Data>) -> Vec<Vec<String>> {
+    let (row_count, col_count) = range.get_size();
+    (0..row_count)
         .map(|r| {
             (0..col_count)
                 .map(|c| cell_to_string(range.get((r, c))))
                 .collect()
         })
-        .collect();
-
-    SheetInfo {
-        name: name.to_string(),
-        row_count: if row_count > 0 { row_count - 1 } else { 0 },
-        col_count,
-        headers,
-        sample_rows,
-        is_hidden: false,
-    }
+        .collect()
 }
 
+/// Headers and data rows of a sheet. The header is taken from `header_row` when
+/// configured, otherwise detected; a two-row header gives combined column names.
 pub fn read_sheet_data(
     file_path: &str,
     sheet_name: &str,
+    header_row: Option<usize>,
 ) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
-    let path = Path::new(file_path);
-    let mut workbook: Xlsx<_> = open_workbook(path)
-        .map_err(|e| format!("Failed to open Excel file: {}", e))?;
-
-    let range = workbook.worksheet_range(sheet_name)
-        .map_err(|e| format!("Failed to read sheet '{}': {}", sheet_name, e))?;
-
-    let (row_count, col_count) = range.get_size();
-    if row_count == 0 {
-        return Ok((Vec::new(), Vec::new()));
+    // A CSV/TSV file has a single sheet, whatever name is asked for.
+    if delimited::is_delimited_file(file_path) {
+        return delimited::read_delimited_data(file_path, header_row);
     }
-
-    let headers: Vec<String> = (0..col_count)
-        .map(|c| cell_to_string(range.get((0, c))))
-        .collect();
-
-    let rows: Vec<Vec<String>> = (1..row_count)
-        .map(|r| {
-            (0..col_count)
-                .map(|c| cell_to_string(range.get((r, c))))
-                .collect()
-        })
-        .collect();
-
-    Ok((headers, rows))
+    let mut workbook = open_workbook(Path::new(file_path))?;
+    let (grid, merges_known) = read_grid(&mut workbook, sheet_name)?;
+    let (layout, rows) = split_table(&grid, header_row, !merges_known);
+    Ok((layout.headers, rows))
 }
 
 pub fn read_sheet_as_maps(
     file_path: &str,
     sheet_name: &str,
+    header_row: Option<usize>,
 ) -> Result<(Vec<String>, Vec<HashMap<String, String>>), String> {
-    let (headers, rows) = read_sheet_data(file_path, sheet_name)?;
+    let (headers, rows) = read_sheet_data(file_path, sheet_name, header_row)?;
+    Ok(rows_to_maps(headers, &rows))
+}
 
+/// Key each row's cells by the header of their column.
+pub fn rows_to_maps(headers: Vec<String>, rows: &[Vec<String>]) -> (Vec<String>, Vec<HashMap<String, String>>) {
     let maps: Vec<HashMap<String, String>> = rows.iter().map(|row| {
         let mut map = HashMap::new();
         for (i, header) in headers.iter().enumerate() {
@@ -117,7 +153,7 @@ pub fn read_sheet_as_maps(
         map
     }).collect();
 
-    Ok((headers, maps))
+    (headers, maps)
 }
 
 fn cell_to_string(cell: Option<&Data>) -> String {
@@ -156,3 +192,52 @@ fn excel_date_to_string(serial: f64) -> Option<String> {
     let date = base + chrono::Duration::days(adjusted);
     Some(date.format("%Y-%m-%d").to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(ext: &str) -> String {
+        format!("{}/tests/fixtures/tasks.{}", env!("CARGO_MANIFEST_DIR"), ext)
+    }
+
+    #[test]
+    fn every_workbook_format_reads_the_same_sheets() {
+        for ext in ["xlsx", "xls", "xlsb", "ods"] {
+            let info = read_excel_info(&fixture(ext), None).unwrap_or_else(|e| panic!("{}: {}", ext, e));
+            let sheets: Vec<(&str, bool, usize)> = info
+                .sheets
+                .iter()
+                .map(|s| (s.name.as_str(), s.is_hidden, s.row_count))
+                .collect();
+            assert_eq!(sheets, vec![("任务", false, 2), ("说明", true, 0)], "{}", ext);
+
+            let (headers, rows) = read_sheet_data(&fixture(ext), "任务", None).unwrap();
+            assert_eq!(headers, vec!["编号", "名称", "负责人", "计划工时"], "{}", ext);
+            assert_eq!(rows[0], vec!["T-1", "登录", "张三", "2"], "{}", ext);
+            assert_eq!(rows[1][3], "1.5", "{}", ext);
+        }
+    }
+
+    #[test]
+    fn plan_layout_header_and_merged_cells_are_read() {
+        let path = format!("{}/tests/fixtures/plan.xlsx", env!("CARGO_MANIFEST_DIR"));
+        let info = read_excel_info(&path, None).unwrap();
+        let sheet = &info.sheets[0];
+        assert_eq!((sheet.header_row, sheet.header_height, sheet.row_count), (2, 2, 3));
+
+        let (headers, rows) = read_sheet_data(&path, "计划", None).unwrap();
+        assert_eq!(headers, vec!["所属模块", "任务名称", "负责人", "计划开始", "计划结束"]);
+        assert_eq!(rows[1][..3], ["账号", "注册", "李四"]);
+        assert_eq!(rows[2][0], "报表");
+    }
+
+    #[test]
+    fn format_is_detected_from_content_when_the_extension_is_unknown() {
+        let copy = std::env::temp_dir().join(format!("tasks-{}.dat", uuid::Uuid::new_v4()));
+        std::fs::copy(fixture("xls"), &copy).unwrap();
+        let info = read_excel_info(&copy.to_string_lossy(), None);
+        std::fs::remove_file(&copy).ok();
+        assert_eq!(info.unwrap().sheets[0].name, "任务");
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use strsim::jaro_winkler;
 
-use super::column_definitions::{get_task_column_definitions, ColumnDataType};
+use super::column_definitions::{get_task_column_definitions, ColumnDataType, ColumnDefinition};
 use super::reader::SheetInfo;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +21,13 @@ pub struct ColumnMatch {
     pub match_method: String,
 }
 
+/// Whether a header contains one of the column definition's Chinese or English keywords.
+pub fn header_has_keyword(header: &str, def: &ColumnDefinition) -> bool {
+    let h_lower = header.to_lowercase().trim().to_string();
+    def.keywords_cn.iter().any(|k| h_lower.contains(&k.to_lowercase()))
+        || def.keywords_en.iter().any(|k| h_lower.contains(&k.to_lowercase()))
+}
+
 /// Score sheets to find the most likely task sheet
 pub fn score_sheets(sheets: &[SheetInfo]) -> Vec<SheetScore> {
     let mut scores: Vec<SheetScore> = sheets.iter().map(|sheet| {
@@ -42,14 +49,8 @@ pub fn score_sheets(sheets: &[SheetInfo]) -> Vec<SheetScore> {
         let definitions = get_task_column_definitions();
         let mut matched_headers = 0;
         for def in &definitions {
-            for header in &sheet.headers {
-                let h_lower = header.to_lowercase().trim().to_string();
-                let is_match = def.keywords_cn.iter().any(|k| h_lower.contains(&k.to_lowercase()))
-                    || def.keywords_en.iter().any(|k| h_lower.contains(&k.to_lowercase()));
-                if is_match {
-                    matched_headers += 1;
-                    break;
-                }
+            if sheet.headers.iter().any(|h| header_has_keyword(h, def)) {
+                matched_headers += 1;
             }
         }
         let header_ratio = if !definitions.is_empty() {
@@ -61,6 +62,11 @@ pub fn score_sheets(sheets: &[SheetInfo]) -> Vec<SheetScore> {
         if matched_headers > 0 {
             reasons.push(format!("匹配到 {}/{} 个任务列头", matched_headers, definitions.len()));
         }
+        if sheet.header_height > 1 {
+            reasons.push(format!("表头为第 {}-{} 行（两行表头）", sheet.header_row + 1, sheet.header_row + sheet.header_height));
+        } else if sheet.header_row > 0 {
+            reasons.push(format!("表头位于第 {} 行", sheet.header_row + 1));
+        }
 
         // Signal 3: Data shape analysis (+0.15)
         if sheet.row_count >= 5 && sheet.col_count >= 5 {
@@ -1,5 +1,5 @@
 use crate::db::task_repo;
-use crate::models::task::TaskFilter;
+use crate::models::task::{Task, TaskFilter};
 use crate::services::settings_service;
 use rusqlite::Connection;
 use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook};
@@ -62,8 +62,59 @@ fn export_width(key: &str) -> f64 {
     }
 }
 
-fn is_date_column(key: &str) -> bool {
-    matches!(key, "planned_start" | "planned_end")
+/// How planned hours are shown: in hours, or in days of `hours_per_day`.
+struct HoursDisplay {
+    in_hours: bool,
+    hours_per_day: f64,
+}
+
+fn load_hours_display(conn: &Connection) -> HoursDisplay {
+    let display_unit = settings_service::get_setting(conn, "work_hours.display_unit")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "day".to_string());
+    HoursDisplay {
+        in_hours: display_unit == "hour",
+        hours_per_day: settings_service::default_hours_per_day(conn),
+    }
+}
+
+/// One export cell, shared by the Excel and CSV writers.
+enum ExportValue {
+    Text(String),
+    Date(String),
+    Number(f64),
+    Empty,
+}
+
+fn export_value(task: &Task, key: &str, hours: &HoursDisplay) -> ExportValue {
+    let text = |v: &Option<String>| ExportValue::Text(v.clone().unwrap_or_default());
+    match key {
+        "task_type" => text(&task.task_type),
+        "external_id" => text(&task.external_id),
+        "name" => ExportValue::Text(task.name.clone()),
+        "description" => text(&task.description),
+        "owner_name" => text(&task.owner_name),
+        "co_owners" => ExportValue::Text(
+            task.co_owners
+                .as_ref()
+                .map(|list| list.iter().map(|c| c.developer_name.clone()).collect::<Vec<_>>().join("、"))
+                .unwrap_or_default(),
+        ),
+        "sprint_name" => text(&task.sprint_name),
+        "priority" => text(&task.priority),
+        "planned_start" => ExportValue::Date(task.planned_start.clone().unwrap_or_default()),
+        "planned_end" => ExportValue::Date(task.planned_end.clone().unwrap_or_default()),
+        "planned_hours" => match task.planned_hours {
+            Some(h) if hours.in_hours => ExportValue::Number(h),
+            Some(h) => ExportValue::Number(h / hours.hours_per_day),
+            None => ExportValue::Empty,
+        },
+        "parent_number" => text(&task.parent_number),
+        "parent_name" => text(&task.parent_name),
+        "status" => text(&task.status),
+        _ => ExportValue::Text(String::new()),
+    }
 }
 
 fn resolve_export_columns(conn: &Connection) -> Vec<String> {
@@ -106,16 +157,7 @@ pub fn export_tasks_to_excel(
 ) -> Result<String, String> {
     let tasks = task_repo::get_all(conn, filter).map_err(|e| e.to_string())?;
 
-    // Read work hours display settings
-    let display_unit = settings_service::get_setting(conn, "work_hours.display_unit")
-        .ok()
-        .flatten()
-        .unwrap_or_else(|| "day".to_string());
-    let hours_per_day = settings_service::get_setting(conn, "work_hours.hours_per_day")
-        .ok()
-        .flatten()
-        .and_then(|v| v.parse::<f64>().ok())
-        .unwrap_or(8.0);
+    let hours = load_hours_display(conn);
 
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
@@ -153,93 +195,59 @@ pub fn export_tasks_to_excel(
     for (row_idx, task) in tasks.iter().enumerate() {
         let row = (row_idx + 1) as u32;
 
-        let write_cell =
-            |ws: &mut rust_xlsxwriter::Worksheet, col: u16, val: &str| -> Result<(), String> {
-                ws.write_string_with_format(row, col, val, &cell_format)
-                    .map_err(|e| e.to_string())?;
-                Ok(())
-            };
-
         for (col_idx, key) in export_columns.iter().enumerate() {
             let col = col_idx as u16;
-            match key.as_str() {
-                "task_type" => write_cell(worksheet, col, task.task_type.as_deref().unwrap_or(""))?,
-                "external_id" => {
-                    write_cell(worksheet, col, task.external_id.as_deref().unwrap_or(""))?
-                }
-                "name" => write_cell(worksheet, col, &task.name)?,
-                "description" => {
-                    write_cell(worksheet, col, task.description.as_deref().unwrap_or(""))?
-                }
-                "owner_name" => {
-                    write_cell(worksheet, col, task.owner_name.as_deref().unwrap_or(""))?
-                }
-                "co_owners" => {
-                    let co = task
-                        .co_owners
-                        .as_ref()
-                        .map(|list| {
-                            list.iter()
-                                .map(|c| c.developer_name.clone())
-                                .collect::<Vec<_>>()
-                                .join("、")
-                        })
-                        .unwrap_or_default();
-                    write_cell(worksheet, col, &co)?;
-                }
-                "sprint_name" => {
-                    write_cell(worksheet, col, task.sprint_name.as_deref().unwrap_or(""))?
-                }
-                "priority" => write_cell(worksheet, col, task.priority.as_deref().unwrap_or(""))?,
-                "planned_start" => {
-                    worksheet
-                        .write_string_with_format(
-                            row,
-                            col,
-                            task.planned_start.as_deref().unwrap_or(""),
-                            &date_format,
-                        )
-                        .map_err(|e| e.to_string())?;
-                }
-                "planned_end" => {
-                    worksheet
-                        .write_string_with_format(
-                            row,
-                            col,
-                            task.planned_end.as_deref().unwrap_or(""),
-                            &date_format,
-                        )
-                        .map_err(|e| e.to_string())?;
-                }
-                "planned_hours" => {
-                    if let Some(hours) = task.planned_hours {
-                        let val = if display_unit == "hour" {
-                            hours
-                        } else {
-                            hours / hours_per_day
-                        };
-                        worksheet
-                            .write_number_with_format(row, col, val, &cell_format)
-                            .map_err(|e| e.to_string())?;
-                    }
-                }
-                "parent_number" => {
-                    write_cell(worksheet, col, task.parent_number.as_deref().unwrap_or(""))?
-                }
-                "parent_name" => {
-                    write_cell(worksheet, col, task.parent_name.as_deref().unwrap_or(""))?
-                }
-                "status" => write_cell(worksheet, col, task.status.as_deref().unwrap_or(""))?,
-                _ => {
-                    if is_date_column(key) {
-                        worksheet
-                            .write_string_with_format(row, col, "", &date_format)
-                            .map_err(|e| e.to_string())?;
-                    } else {
-                        write_cell(worksheet, col, "")?;
-                    }
-                }
+            match export_value(task, key, &hours) {
+                ExportValue::Text(val) => worksheet.write_string_with_format(row, col, val, &cell_format),
+                ExportValue::Date(val) => worksheet.write_string_with_format(row, col, val, &date_format),
+                ExportValue::Number(val) => worksheet.write_number_with_format(row, col, val, &cell_format),
+                ExportValue::Empty => continue,
+            }
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Pinned daily hours of the exported tasks, on their own sheet so the Task sheet
+    // keeps the import layout.
+    let exported: HashSet<i64> = tasks.iter().map(|t| t.id).collect();
+    let allocations: Vec<_> = task_repo::get_all_daily_allocations(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|a| exported.contains(&a.task_id))
+        .collect();
+    if !allocations.is_empty() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("每日工时").map_err(|e| e.to_string())?;
+        for (col, (label, width)) in [("编号", 15.0), ("名称", 30.0), ("负责人", 10.0), ("日期", 14.0), ("固定工时(小时)", 14.0)]
+            .iter()
+            .enumerate()
+        {
+            sheet
+                .write_string_with_format(0, col as u16, *label, &header_format)
+                .map_err(|e| e.to_string())?;
+            sheet.set_column_width(col as u16, *width).map_err(|e| e.to_string())?;
+        }
+        for (row_idx, a) in allocations.iter().enumerate() {
+            let row = (row_idx + 1) as u32;
+            let Some(task) = tasks.iter().find(|t| t.id == a.task_id) else { continue };
+            for (col, val) in [
+                task.external_id.as_deref().unwrap_or(""),
+                task.name.as_str(),
+                task.owner_name.as_deref().unwrap_or(""),
+            ]
+            .iter()
+            .enumerate()
+            {
+                sheet
+                    .write_string_with_format(row, col as u16, *val, &cell_format)
+                    .map_err(|e| e.to_string())?;
             }
+            sheet
+                .write_string_with_format(row, 3, &a.date, &date_format)
+                .map_err(|e| e.to_string())?;
+            sheet
+                .write_number_with_format(row, 4, a.hours, &cell_format)
+                .map_err(|e| e.to_string())?;
         }
     }
 
@@ -247,3 +255,73 @@ pub fn export_tasks_to_excel(
 
     Ok(file_path.to_string())
 }
+
+/// Text of one export column for a task, as written to CSV.
+fn csv_cell(task: &Task, key: &str, hours: &HoursDisplay) -> String {
+    match export_value(task, key, hours) {
+        ExportValue::Text(text) | ExportValue::Date(text) => text,
+        ExportValue::Number(value) => format!("{}", (value * 100.0).round() / 100.0),
+        ExportValue::Empty => String::new(),
+    }
+}
+
+/// Export tasks as CSV (or TSV for a `.tsv` path) with the configured export columns.
+///
+/// The file is UTF-8 with a BOM so Excel opens Chinese text correctly.
+pub fn export_tasks_to_csv(
+    conn: &Connection,
+    file_path: &str,
+    filter: &TaskFilter,
+) -> Result<String, String> {
+    let tasks = task_repo::get_all(conn, filter).map_err(|e| e.to_string())?;
+    let hours = load_hours_display(conn);
+    let export_columns = resolve_export_columns(conn);
+
+    let delimiter = if file_path.to_lowercase().ends_with(".tsv") { b'\t' } else { b',' };
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+    writer
+        .write_record(export_columns.iter().map(|k| export_label(k)))
+        .map_err(|e| e.to_string())?;
+    for task in &tasks {
+        writer
+            .write_record(export_columns.iter().map(|k| csv_cell(task, k, &hours)))
+            .map_err(|e| e.to_string())?;
+    }
+    let body = writer.into_inner().map_err(|e| e.to_string())?;
+
+    let mut bytes = b"\xEF\xBB\xBF".to_vec();
+    bytes.extend(body);
+    std::fs::write(file_path, bytes).map_err(|e| e.to_string())?;
+    Ok(file_path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::excel::delimited;
+
+    #[test]
+    fn csv_export_reads_back_through_the_importer() {
+        let conn = Connection::open_in_memory().unwrap();
+        init::create_tables(&conn).unwrap();
+        init::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO tasks (external_id, name, planned_start, planned_end, planned_hours, status) \
+             VALUES ('T-1', '登录, 注册', '2026-11-02', '2026-11-03', 12, '进行中');",
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join(format!("export-{}.csv", uuid::Uuid::new_v4()));
+        let path = path.to_string_lossy().to_string();
+        export_tasks_to_csv(&conn, &path, &TaskFilter::default()).unwrap();
+        let (headers, rows) = delimited::read_delimited_data(&path, None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let column = |label: &str| headers.iter().position(|h| h == label).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][column("名称")], "登录, 注册");
+        assert_eq!(rows[0][column("计划工作量")], "1.5");
+        assert_eq!(rows[0][column("进度")], "进行中");
+    }
+}
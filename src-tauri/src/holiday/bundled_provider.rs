@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use super::provider::{HolidayProvider, HolidayRecord};
+
+/// Official arrangements shipped with the app, used when the online API is unreachable.
+/// Update `data/cn_holidays.json` when the State Council publishes a new year.
+const BUNDLED_CN_HOLIDAYS: &str = include_str!("data/cn_holidays.json");
+
+#[derive(Debug, Deserialize)]
+struct BundledPeriod {
+    year: i32,
+    name: String,
+    /// Inclusive `[first, last]` day off.
+    off: [String; 2],
+    /// Makeup workdays (调休补班).
+    work: Vec<String>,
+}
+
+pub struct BundledHolidayProvider;
+
+impl HolidayProvider for BundledHolidayProvider {
+    fn source(&self) -> String {
+        "bundled".to_string()
+    }
+
+    fn fetch_year(&self, year: i32) -> Result<Vec<HolidayRecord>, String> {
+        let periods: Vec<BundledPeriod> =
+            serde_json::from_str(BUNDLED_CN_HOLIDAYS).map_err(|e| format!("内置节假日数据损坏: {}", e))?;
+
+        let mut records = Vec::new();
+        for period in periods.into_iter().filter(|p| p.year == year) {
+            let first = parse(&period.off[0])?;
+            let last = parse(&period.off[1])?;
+            let mut day = first;
+            while day <= last {
+                records.push(HolidayRecord {
+                    date: day.format("%Y-%m-%d").to_string(),
+                    is_holiday: true,
+                    name: period.name.clone(),
+                });
+                day += chrono::Duration::days(1);
+            }
+            for work in &period.work {
+                records.push(HolidayRecord {
+                    date: parse(work)?.format("%Y-%m-%d").to_string(),
+                    is_holiday: false,
+                    name: format!("{}补班", period.name),
+                });
+            }
+        }
+        Ok(records)
+    }
+}
+
+fn parse(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("内置节假日日期无效: {}", date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_2026_includes_spring_festival_and_makeup_days() {
+        let records = BundledHolidayProvider.fetch_year(2026).unwrap();
+        let find = |d: &str| records.iter().find(|r| r.date == d).cloned();
+
+        assert!(find("2026-02-17").unwrap().is_holiday);
+        let makeup = find("2026-02-28").unwrap();
+        assert!(!makeup.is_holiday);
+        assert_eq!(makeup.name, "春节补班");
+        assert!(find("2026-03-02").is_none());
+        assert!(BundledHolidayProvider.fetch_year(1999).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{Datelike, NaiveDate};
+use serde_json::Value;
+use std::path::Path;
+use super::provider::{HolidayProvider, HolidayRecord};
+
+/// Holidays imported from a local file (JSON / CSV / ICS), e.g. a company calendar
+/// or a dataset downloaded ahead of time for an offline machine.
+///
+/// Supported layouts:
+/// - JSON: `[{"date": "2026-01-01", "holiday": true, "name": "元旦"}]` (`is_holiday` also
+///   accepted), or a saved timor.tech response `{"holiday": {"01-01": {...}}}`
+/// - CSV/TSV: `date,type,name` with an optional header; type is `休`/`holiday`/`1` for a
+///   day off and `班`/`workday`/`0` for a makeup workday
+/// - ICS: all-day `VEVENT`s; events whose `SUMMARY` contains `班` are makeup workdays,
+///   every other event marks its days (DTEND exclusive) as holidays
+pub struct FileHolidayProvider {
+    file_name: String,
+    records: Vec<HolidayRecord>,
+}
+
+impl FileHolidayProvider {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?;
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let records = match ext.as_str() {
+            "json" => parse_json(&content)?,
+            "csv" | "tsv" | "txt" => parse_csv(&content)?,
+            "ics" | "ical" => parse_ics(&content)?,
+            other => return Err(format!("不支持的节假日文件格式: .{}", other)),
+        };
+        if records.is_empty() {
+            return Err("文件中没有识别到节假日数据".to_string());
+        }
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self { file_name, records })
+    }
+
+    /// Years present in the file, ascending.
+    pub fn years(&self) -> Vec<i32> {
+        let mut years: Vec<i32> = self
+            .records
+            .iter()
+            .filter_map(|r| parse_date(&r.date).map(|d| d.year()))
+            .collect();
+        years.sort();
+        years.dedup();
+        years
+    }
+}
+
+impl HolidayProvider for FileHolidayProvider {
+    fn source(&self) -> String {
+        format!("file:{}", self.file_name)
+    }
+
+    fn fetch_year(&self, year: i32) -> Result<Vec<HolidayRecord>, String> {
+        Ok(self
+            .records
+            .iter()
+            .filter(|r| parse_date(&r.date).is_some_and(|d| d.year() == year))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Accepts `2026-01-01`, `2026/1/1` and `20260101`.
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    let s = raw.trim();
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y/%m/%d"))
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y%m%d"))
+        .ok()
+}
+
+fn record(date: NaiveDate, is_holiday: bool, name: &str) -> HolidayRecord {
+    HolidayRecord {
+        date: date.format("%Y-%m-%d").to_string(),
+        is_holiday,
+        name: name.to_string(),
+    }
+}
+
+fn parse_json(content: &str) -> Result<Vec<HolidayRecord>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let entries: Vec<&Value> = match &value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(obj) => match obj.get("holiday") {
+            Some(Value::Object(map)) => map.values().collect(),
+            _ => return Err("无法识别的 JSON 节假日格式".to_string()),
+        },
+        _ => return Err("无法识别的 JSON 节假日格式".to_string()),
+    };
+
+    let mut records = Vec::new();
+    for entry in entries {
+        let Some(date) = entry.get("date").and_then(|v| v.as_str()).and_then(parse_date) else {
+            continue;
+        };
+        let is_holiday = entry
+            .get("holiday")
+            .or_else(|| entry.get("is_holiday"))
+            .and_then(|v| v.as_bool().or_else(|| v.as_i64().map(|n| n != 0)))
+            .unwrap_or(true);
+        let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        records.push(record(date, is_holiday, name));
+    }
+    Ok(records)
+}
+
+fn parse_day_type(raw: &str) -> Option<bool> {
+    match raw.trim().to_lowercase().as_str() {
+        "休" | "假" | "holiday" | "off" | "1" | "true" => Some(true),
+        "班" | "补班" | "workday" | "work" | "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_csv(content: &str) -> Result<Vec<HolidayRecord>, String> {
+    let mut records = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        let cells: Vec<&str> = if line.contains('\t') {
+            line.split('\t').collect()
+        } else {
+            line.split(',').collect()
+        };
+        let Some(date) = parse_date(cells[0]) else {
+            if idx == 0 {
+                continue; // header row
+            }
+            return Err(format!("第 {} 行日期无效: {}", idx + 1, cells[0]));
+        };
+        let is_holiday = match cells.get(1) {
+            Some(t) => parse_day_type(t).ok_or_else(|| format!("第 {} 行类型无法识别: {}", idx + 1, t))?,
+            None => true,
+        };
+        let name = cells.get(2).map(|s| s.trim()).unwrap_or("");
+        records.push(record(date, is_holiday, name));
+    }
+    Ok(records)
+}
+
+fn parse_ics(content: &str) -> Result<Vec<HolidayRecord>, String> {
+    // Unfold continuation lines (RFC 5545 §3.1).
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            lines.last_mut().unwrap().push_str(&raw[1..]);
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+
+    let mut records = Vec::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    let mut summary = String::new();
+    let mut in_event = false;
+
+    for line in &lines {
+        if line == "BEGIN:VEVENT" {
+            in_event = true;
+            start = None;
+            end = None;
+            summary.clear();
+            continue;
+        }
+        if !in_event {
+            continue;
+        }
+        if line == "END:VEVENT" {
+            in_event = false;
+            let Some(first) = start else { continue };
+            let is_holiday = !summary.contains('班');
+            // DTEND is exclusive for all-day events; makeup days are single days.
+            let last = match end {
+                Some(e) if is_holiday && e > first => e - chrono::Duration::days(1),
+                _ => first,
+            };
+            let mut day = first;
+            while day <= last {
+                records.push(record(day, is_holiday, summary.trim()));
+                day += chrono::Duration::days(1);
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let prop = key.split(';').next().unwrap_or("");
+        match prop {
+            "DTSTART" => start = value.get(..8).and_then(parse_date),
+            "DTEND" => end = value.get(..8).and_then(parse_date),
+            "SUMMARY" => summary = value.replace("\\,", ",").replace("\\n", " "),
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_with_header_and_chinese_day_types() {
+        let records = parse_csv("日期,类型,名称\n2026-10-01,休,国庆节\n2026/10/10,班,国庆节补班\n").unwrap();
+        assert_eq!(records, vec![
+            record(NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(), true, "国庆节"),
+            record(NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(), false, "国庆节补班"),
+        ]);
+    }
+
+    #[test]
+    fn ics_expands_all_day_ranges_and_detects_makeup_days() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20260501\r\nDTEND;VALUE=DATE:20260506\r\nSUMMARY:劳动节\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20260509\r\nDTEND;VALUE=DATE:20260510\r\nSUMMARY:劳动节补\r\n 班\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let records = parse_ics(ics).unwrap();
+        assert_eq!(records.iter().filter(|r| r.is_holiday).count(), 5);
+        let makeup: Vec<_> = records.iter().filter(|r| !r.is_holiday).collect();
+        assert_eq!(makeup.len(), 1);
+        assert_eq!(makeup[0].date, "2026-05-09");
+        assert_eq!(makeup[0].name, "劳动节补班");
+    }
+}
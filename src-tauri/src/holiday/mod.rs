@@ -0,0 +1,6 @@
+pub mod provider;
+pub mod timor_provider;
+pub mod nager_provider;
+pub mod bundled_provider;
+pub mod file_provider;
+pub mod work_calendar;
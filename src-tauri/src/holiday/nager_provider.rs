@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use super::provider::{HolidayProvider, HolidayRecord};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicHoliday {
+    date: String,
+    local_name: Option<String>,
+    name: String,
+    global: bool,
+}
+
+/// Online provider for non-Chinese regions backed by the Nager.Date public holiday API.
+///
+/// Only nationwide holidays are kept; state/county-only days (`global = false`) would
+/// otherwise mark the whole country as off. These regions have no makeup workdays.
+pub struct NagerHolidayProvider {
+    pub country_code: String,
+}
+
+impl HolidayProvider for NagerHolidayProvider {
+    fn source(&self) -> String {
+        "nager".to_string()
+    }
+
+    fn fetch_year(&self, year: i32) -> Result<Vec<HolidayRecord>, String> {
+        let url = format!(
+            "https://date.nager.at/api/v3/PublicHolidays/{}/{}",
+            year,
+            self.country_code.to_uppercase()
+        );
+
+        let resp: Vec<PublicHoliday> = ureq::get(&url)
+            .call()
+            .map_err(|e| format!("HTTP request failed: {}", e))?
+            .into_json()
+            .map_err(|e| format!("JSON parse failed: {}", e))?;
+
+        Ok(resp
+            .into_iter()
+            .filter(|h| h.global)
+            .map(|h| HolidayRecord {
+                date: h.date,
+                is_holiday: true,
+                name: h.local_name.unwrap_or(h.name),
+            })
+            .collect())
+    }
+}
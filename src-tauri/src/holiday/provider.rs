@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// One special day: either a day off (`is_holiday = true`) or a makeup workday
+/// (调休补班, `is_holiday = false`). Ordinary weekdays/weekends are not listed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HolidayRecord {
+    pub date: String,
+    pub is_holiday: bool,
+    pub name: String,
+}
+
+/// A source of official holiday arrangements.
+pub trait HolidayProvider {
+    /// Identifier stored in `holiday_cache.source`.
+    fn source(&self) -> String;
+
+    /// All special days for `year`. An empty list means the provider has no data for that year.
+    fn fetch_year(&self, year: i32) -> Result<Vec<HolidayRecord>, String>;
+}
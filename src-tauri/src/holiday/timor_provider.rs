@@ -0,0 +1,50 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use super::provider::{HolidayProvider, HolidayRecord};
+
+#[derive(Debug, Deserialize)]
+struct HolidayApiResponse {
+    code: i32,
+    holiday: Option<HashMap<String, HolidayEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HolidayEntry {
+    holiday: bool,   // true = day off, false = makeup workday
+    name: String,
+    date: String,
+}
+
+/// Online provider backed by the timor.tech holiday API.
+pub struct TimorHolidayProvider;
+
+impl HolidayProvider for TimorHolidayProvider {
+    fn source(&self) -> String {
+        "timor".to_string()
+    }
+
+    fn fetch_year(&self, year: i32) -> Result<Vec<HolidayRecord>, String> {
+        let url = format!("https://timor.tech/api/holiday/year/{}", year);
+
+        let resp: HolidayApiResponse = ureq::get(&url)
+            .call()
+            .map_err(|e| format!("HTTP request failed: {}", e))?
+            .into_json()
+            .map_err(|e| format!("JSON parse failed: {}", e))?;
+
+        if resp.code != 0 {
+            return Err(format!("API returned error code: {}", resp.code));
+        }
+
+        Ok(resp
+            .holiday
+            .unwrap_or_default()
+            .into_values()
+            .map(|entry| HolidayRecord {
+                date: entry.date,
+                is_holiday: entry.holiday,
+                name: entry.name,
+            })
+            .collect())
+    }
+}
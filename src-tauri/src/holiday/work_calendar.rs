@@ -0,0 +1,161 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound for forward/backward scans, so a calendar with no workdays can't loop forever.
+const MAX_SCAN_DAYS: i64 = 3650;
+
+/// Working-day rules for one calendar, held in memory so hot loops don't hit SQLite.
+///
+/// Resolution order for a date:
+/// 1. special days (synced holidays and makeup workdays)
+/// 2. overtime: weekend overtime or custom overtime dates make the day a workday
+/// 3. the work week: any day outside `weekend` is a workday
+#[derive(Debug, Clone)]
+pub struct WorkCalendar {
+    /// Indexed by weekday, 0 = Monday .. 6 = Sunday.
+    weekend: [bool; 7],
+    overtime_weekdays: [bool; 7],
+    overtime_dates: HashSet<NaiveDate>,
+    /// `true` = workday, `false` = day off.
+    special_days: HashMap<NaiveDate, bool>,
+}
+
+impl WorkCalendar {
+    /// A plain work week with the given weekend days (0 = Monday .. 6 = Sunday).
+    pub fn new(weekend_days: &[u32]) -> Self {
+        let mut weekend = [false; 7];
+        for d in weekend_days {
+            if let Some(slot) = weekend.get_mut(*d as usize) {
+                *slot = true;
+            }
+        }
+        Self {
+            weekend,
+            overtime_weekdays: [false; 7],
+            overtime_dates: HashSet::new(),
+            special_days: HashMap::new(),
+        }
+    }
+
+    /// Mark `date` as a day off or workday regardless of weekday or overtime.
+    pub fn set_special_day(&mut self, date: NaiveDate, is_workday: bool) {
+        self.special_days.insert(date, is_workday);
+    }
+
+    /// Work every `weekday` (0 = Monday .. 6 = Sunday) unless it is a special day.
+    pub fn add_overtime_weekday(&mut self, weekday: u32) {
+        if let Some(slot) = self.overtime_weekdays.get_mut(weekday as usize) {
+            *slot = true;
+        }
+    }
+
+    pub fn add_overtime_date(&mut self, date: NaiveDate) {
+        self.overtime_dates.insert(date);
+    }
+
+    pub fn is_workday(&self, date: &NaiveDate) -> bool {
+        if let Some(is_workday) = self.special_days.get(date) {
+            return *is_workday;
+        }
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        if self.overtime_weekdays[weekday] || self.overtime_dates.contains(date) {
+            return true;
+        }
+        !self.weekend[weekday]
+    }
+
+    /// The first workday on or after `date`.
+    pub fn next_workday(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date;
+        for _ in 0..MAX_SCAN_DAYS {
+            if self.is_workday(&day) {
+                return day;
+            }
+            day += Duration::days(1);
+        }
+        day
+    }
+
+    /// Move `n` workdays away from `date` (backwards when negative); `date` itself is not counted.
+    pub fn add_workdays(&self, date: NaiveDate, n: i64) -> NaiveDate {
+        let step = Duration::days(if n < 0 { -1 } else { 1 });
+        let mut remaining = n.abs();
+        let mut day = date;
+        let mut scanned = 0;
+        while remaining > 0 && scanned < MAX_SCAN_DAYS {
+            day += step;
+            scanned += 1;
+            if self.is_workday(&day) {
+                remaining -= 1;
+            }
+        }
+        day
+    }
+
+    /// The `n`-th workday (0-based) on or after `start`.
+    pub fn nth_workday(&self, start: NaiveDate, n: usize) -> NaiveDate {
+        self.add_workdays(self.next_workday(start), n as i64)
+    }
+
+    /// Number of workdays in `[start, end]`; 0 when `end < start`.
+    pub fn workdays_between(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+        self.workdays(start, end).len() as i64
+    }
+
+    /// Workdays in `[start, end]`, ascending.
+    pub fn workdays(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let mut days = Vec::new();
+        let mut day = start;
+        while day <= end {
+            if self.is_workday(&day) {
+                days.push(day);
+            }
+            day += Duration::days(1);
+        }
+        days
+    }
+}
+
+impl Default for WorkCalendar {
+    /// Monday to Friday with no holidays.
+    fn default() -> Self {
+        Self::new(&[5, 6])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn special_days_override_weekend_and_overtime() {
+        let mut cal = WorkCalendar::default();
+        cal.set_special_day(ymd("2026-10-01"), false); // Thursday holiday
+        cal.set_special_day(ymd("2026-10-10"), true); // Saturday makeup workday
+        cal.add_overtime_weekday(6);
+        cal.set_special_day(ymd("2026-10-11"), false); // Sunday off despite overtime
+
+        assert!(!cal.is_workday(&ymd("2026-10-01")));
+        assert!(cal.is_workday(&ymd("2026-10-10")));
+        assert!(!cal.is_workday(&ymd("2026-10-11")));
+        assert!(cal.is_workday(&ymd("2026-10-04"))); // ordinary Sunday overtime
+    }
+
+    #[test]
+    fn workday_arithmetic_skips_days_off() {
+        let mut cal = WorkCalendar::default();
+        cal.set_special_day(ymd("2026-10-07"), false);
+
+        // Fri 10-02 -> Mon 10-05, Tue 10-06, (Wed off), Thu 10-08
+        assert_eq!(cal.add_workdays(ymd("2026-10-02"), 3), ymd("2026-10-08"));
+        assert_eq!(cal.add_workdays(ymd("2026-10-08"), -3), ymd("2026-10-02"));
+        assert_eq!(cal.next_workday(ymd("2026-10-03")), ymd("2026-10-05"));
+        assert_eq!(cal.nth_workday(ymd("2026-10-03"), 2), ymd("2026-10-08"));
+        assert_eq!(cal.workdays_between(ymd("2026-10-01"), ymd("2026-10-11")), 6);
+        assert_eq!(cal.workdays_between(ymd("2026-10-11"), ymd("2026-10-01")), 0);
+    }
+}
@@ -4,6 +4,7 @@ mod services;
 mod commands;
 mod excel;
 mod llm;
+mod holiday;
 
 use db::AppDatabase;
 use tauri::Manager;
@@ -38,12 +39,33 @@ pub fn run() {
             commands::task_commands::update_task,
             commands::task_commands::delete_task,
             commands::task_commands::count_tasks,
+            commands::task_commands::suggest_task_owner,
+            commands::task_commands::move_task,
+            commands::task_commands::list_task_dependencies,
+            commands::task_commands::add_task_dependency,
+            commands::task_commands::remove_task_dependency,
+            commands::task_commands::list_task_daily_allocations,
+            commands::task_commands::set_task_daily_allocations,
             // Developer commands
             commands::developer_commands::list_developers,
             commands::developer_commands::get_developer,
             commands::developer_commands::create_developer,
             commands::developer_commands::update_developer,
             commands::developer_commands::delete_developer,
+            commands::developer_commands::offboard_developer,
+            commands::developer_commands::list_capacity_rules,
+            commands::developer_commands::create_capacity_rule,
+            commands::developer_commands::delete_capacity_rule,
+            commands::developer_commands::get_effective_capacity,
+            commands::developer_commands::get_developer_overtime,
+            commands::developer_commands::set_developer_overtime,
+            // Team commands
+            commands::team_commands::list_teams,
+            commands::team_commands::create_team,
+            commands::team_commands::update_team,
+            commands::team_commands::delete_team,
+            commands::team_commands::get_team_workload,
+            commands::team_commands::get_team_capacity,
             // Sprint commands
             commands::sprint_commands::list_sprints,
             commands::sprint_commands::create_sprint,
@@ -51,20 +73,64 @@ pub fn run() {
             commands::sprint_commands::delete_sprint,
             commands::sprint_commands::list_projects,
             commands::sprint_commands::create_project,
+            commands::sprint_commands::get_sprint_capacity,
+            commands::sprint_commands::list_milestones,
+            commands::sprint_commands::create_milestone,
+            commands::sprint_commands::update_milestone,
+            commands::sprint_commands::delete_milestone,
+            // Baseline commands
+            commands::baseline_commands::list_baselines,
+            commands::baseline_commands::save_baseline,
+            commands::baseline_commands::delete_baseline,
+            commands::baseline_commands::get_baseline_variance,
+            // Scenario commands
+            commands::scenario_commands::list_scenarios,
+            commands::scenario_commands::create_scenario,
+            commands::scenario_commands::delete_scenario,
+            commands::scenario_commands::list_scenario_tasks,
+            commands::scenario_commands::update_scenario_task,
+            commands::scenario_commands::reset_scenario_task,
+            commands::scenario_commands::move_scenario_task,
+            commands::scenario_commands::list_scenario_developers,
+            commands::scenario_commands::add_scenario_developer,
+            commands::scenario_commands::remove_scenario_developer,
+            commands::scenario_commands::compare_scenarios,
+            commands::scenario_commands::apply_scenario,
             // Calendar commands
             commands::calendar_commands::get_calendar_events,
             commands::calendar_commands::get_calendar_resources,
             commands::calendar_commands::get_developer_workload,
+            commands::calendar_commands::scan_schedule_violations,
+            commands::calendar_commands::level_resources,
+            commands::calendar_commands::estimate_availability,
+            commands::calendar_commands::find_soonest_available,
             commands::calendar_commands::sync_holidays,
+            commands::calendar_commands::import_holidays_from_file,
+            commands::calendar_commands::list_cached_holiday_years,
+            commands::calendar_commands::list_region_calendars,
+            commands::calendar_commands::create_region_calendar,
+            commands::calendar_commands::update_region_calendar,
+            commands::calendar_commands::delete_region_calendar,
+            commands::calendar_commands::list_calendar_overrides,
+            commands::calendar_commands::create_calendar_override,
+            commands::calendar_commands::update_calendar_override,
+            commands::calendar_commands::delete_calendar_override,
             // Excel commands
             commands::excel_commands::analyze_excel,
             commands::excel_commands::score_excel_sheets,
             commands::excel_commands::match_excel_columns,
             commands::excel_commands::preview_excel_import,
             commands::excel_commands::import_excel,
+            commands::excel_commands::dry_run_excel_import,
             commands::excel_commands::export_excel,
+            commands::excel_commands::export_csv,
+            commands::excel_commands::preview_pasted_table,
+            commands::excel_commands::match_pasted_columns,
+            commands::excel_commands::import_pasted_table,
+            commands::excel_commands::dry_run_pasted_import,
             commands::excel_commands::reveal_in_folder,
             commands::excel_commands::get_import_history,
+            commands::excel_commands::rollback_import,
             commands::excel_commands::detect_excel_conflicts,
             // Settings commands
             commands::settings_commands::get_llm_config,
@@ -73,6 +139,9 @@ pub fn run() {
             commands::settings_commands::save_excel_template_config,
             commands::settings_commands::get_setting,
             commands::settings_commands::save_setting,
+            commands::settings_commands::get_value_mappings,
+            commands::settings_commands::save_value_mappings,
+            commands::settings_commands::remember_value_mappings,
             // Batch commands
             commands::batch_commands::batch_update_tasks,
             commands::batch_commands::batch_delete_tasks,
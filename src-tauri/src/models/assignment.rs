@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// How well a developer fits a task, as produced by the assignment scorer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentScore {
+    pub developer_id: i64,
+    pub developer_name: String,
+    /// Weighted total in `[0, 1]`, higher is better.
+    pub score: f64,
+    /// Average coverage of the task's required skills in `[0, 1]`.
+    pub skill_fit: f64,
+    /// Booked hours / available hours over the look-ahead window.
+    pub load_ratio: f64,
+    pub missing_skills: Vec<String>,
+    pub reasons: Vec<String>,
+}
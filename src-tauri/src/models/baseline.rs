@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// A saved snapshot of the plan, for the whole backlog or one sprint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub id: i64,
+    pub name: String,
+    pub sprint_id: Option<i64>,
+    pub sprint_name: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub task_count: i64,
+}
+
+/// Planned values of one task at the time a baseline was saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineTask {
+    pub task_id: i64,
+    pub task_name: String,
+    pub owner_id: Option<i64>,
+    pub planned_start: Option<String>,
+    pub planned_end: Option<String>,
+    pub planned_hours: Option<f64>,
+}
+
+/// One task compared between a baseline and the current plan.
+///
+/// `change` is one of "added" (not in the baseline), "removed" (deleted or moved out of
+/// the baseline's sprint), "changed" or "unchanged". Slips are in calendar days,
+/// positive when the current date is later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskVariance {
+    pub task_id: i64,
+    pub task_name: String,
+    pub owner_name: Option<String>,
+    pub change: String,
+    pub baseline_start: Option<String>,
+    pub baseline_end: Option<String>,
+    pub current_start: Option<String>,
+    pub current_end: Option<String>,
+    pub start_slip_days: Option<i64>,
+    pub end_slip_days: Option<i64>,
+    pub baseline_hours: Option<f64>,
+    pub current_hours: Option<f64>,
+    pub hours_delta: f64,
+}
+
+/// Drift of the current plan since a baseline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineVariance {
+    pub baseline: Baseline,
+    pub tasks: Vec<TaskVariance>,
+    pub added_count: i64,
+    pub removed_count: i64,
+    pub changed_count: i64,
+    /// Tasks whose end date moved later.
+    pub slipped_count: i64,
+    /// Largest end-date slip in calendar days (0 when nothing slipped).
+    pub max_slip_days: i64,
+    pub baseline_hours: f64,
+    pub current_hours: f64,
+    pub hours_growth: f64,
+}
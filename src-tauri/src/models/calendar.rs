@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::task::{TaskMove, UpdateTaskDto};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarEvent {
@@ -8,12 +9,22 @@ pub struct CalendarEvent {
     pub end: Option<String>,
     pub resource_id: Option<String>,
     pub color: Option<String>,
+    /// FullCalendar display mode; `background` for day markers such as calendar overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// `false` for context events (sprints, milestones, holidays, absences) so they can't be dragged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editable: Option<bool>,
     pub ext_props: Option<CalendarEventExtProps>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarEventExtProps {
-    pub task_id: i64,
+    /// `task`, `override`, `sprint`, `milestone`, `holiday`, `makeup` or `absence`.
+    #[serde(default = "default_event_type")]
+    pub event_type: String,
+    /// Set for task events only.
+    pub task_id: Option<i64>,
     pub task_type: Option<String>,
     pub priority: Option<String>,
     pub status: Option<String>,
@@ -24,6 +35,28 @@ pub struct CalendarEventExtProps {
     pub sprint_name: Option<String>,
 }
 
+fn default_event_type() -> String {
+    "task".to_string()
+}
+
+impl CalendarEventExtProps {
+    /// Props for a non-task event: only the type is set.
+    pub fn of_type(event_type: &str) -> Self {
+        Self {
+            event_type: event_type.to_string(),
+            task_id: None,
+            task_type: None,
+            priority: None,
+            status: None,
+            owner_id: None,
+            owner_name: None,
+            planned_hours: None,
+            sprint_id: None,
+            sprint_name: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarResource {
     pub id: String,
@@ -44,9 +77,158 @@ pub struct DeveloperWorkload {
     pub is_overtime: bool,
 }
 
+/// One problem found by the schedule scan, with a suggested fix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleViolation {
+    /// `overload`, `non_workday`, `insufficient_capacity`, `invalid_range`, `outside_sprint` or `dependency`.
+    pub kind: String,
+    /// `error` or `warning`.
+    pub severity: String,
+    pub task_id: Option<i64>,
+    pub task_name: Option<String>,
+    pub developer_id: Option<i64>,
+    pub developer_name: Option<String>,
+    /// The overloaded day, or the task's start date.
+    pub date: Option<String>,
+    pub message: String,
+    pub suggestion: String,
+}
+
+/// Preview of a resource-leveling run. `updates` can be saved as is with
+/// `batch_update_tasks`; `remaining` lists the overloads leveling could not remove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelingPlan {
+    pub changes: Vec<TaskMove>,
+    pub updates: Vec<UpdateTaskDto>,
+    pub remaining: Vec<ScheduleViolation>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkloadTask {
     pub task_id: i64,
     pub task_name: String,
     pub daily_hours: f64,
+    /// Hours come from `task_daily_allocations` rather than the automatic distribution.
+    #[serde(default)]
+    pub pinned: bool,
+    /// "HH:MM" slot of the day's hours inside the working-hours window.
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub end_time: Option<String>,
+}
+
+/// Result of simulating a new N-hour task on top of a developer's existing allocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailabilityEstimate {
+    pub developer_id: i64,
+    pub developer_name: String,
+    pub hours: f64,
+    /// `None` when the task cannot be finished within the search horizon.
+    pub earliest_start: Option<String>,
+    pub earliest_finish: Option<String>,
+    pub workdays_used: i64,
+}
+
+/// Holiday cache contents for one region, year and source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolidayYearSummary {
+    pub region: String,
+    pub year: i32,
+    /// `timor`, `bundled`, `nager`, `file:<name>` or `unknown` for rows cached before sources were tracked.
+    pub source: String,
+    pub entries: i64,
+    pub holidays: i64,
+    pub makeup_workdays: i64,
+}
+
+/// A named work calendar: the region whose holidays apply plus which weekdays are the weekend.
+/// Developers without a calendar use the default one (中国大陆).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionCalendar {
+    pub id: i64,
+    pub name: String,
+    /// Holiday region code: `CN` for the mainland China arrangement (with makeup workdays),
+    /// otherwise an ISO 3166 country code such as `DE` or `US`.
+    pub region: String,
+    /// Weekend days, 0 = Monday .. 6 = Sunday.
+    pub weekend_days: Vec<u32>,
+    pub is_default: bool,
+}
+
+impl Default for RegionCalendar {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: "中国大陆".to_string(),
+            region: "CN".to_string(),
+            weekend_days: vec![5, 6],
+            is_default: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRegionCalendarDto {
+    pub name: String,
+    pub region: String,
+    pub weekend_days: Option<Vec<u32>>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRegionCalendarDto {
+    pub id: i64,
+    pub name: Option<String>,
+    pub region: Option<String>,
+    pub weekend_days: Option<Vec<u32>>,
+    pub is_default: Option<bool>,
+}
+
+/// A manual day off or workday that takes precedence over synced holiday data and
+/// survives a resync. `calendar_id = None` applies to every calendar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarOverride {
+    pub id: i64,
+    pub calendar_id: Option<i64>,
+    pub date: String,
+    pub is_workday: bool,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCalendarOverrideDto {
+    pub calendar_id: Option<i64>,
+    pub date: String,
+    pub is_workday: bool,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCalendarOverrideDto {
+    pub id: i64,
+    pub date: Option<String>,
+    pub is_workday: Option<bool>,
+    pub name: Option<String>,
+}
+
+/// Overtime rules: `weekend` is `none` / `saturday` / `sunday` / `both`; `custom_dates` are
+/// extra `YYYY-MM-DD` workdays. Stored globally in `schedule.overtime_days` and optionally
+/// per developer, where it replaces the global rules for that person.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OvertimeConfig {
+    #[serde(default = "default_overtime_weekend")]
+    pub weekend: String,
+    #[serde(default)]
+    pub custom_dates: Vec<String>,
+}
+
+fn default_overtime_weekend() -> String {
+    "none".to_string()
+}
+
+impl Default for OvertimeConfig {
+    fn default() -> Self {
+        Self { weekend: default_overtime_weekend(), custom_dates: Vec::new() }
+    }
 }
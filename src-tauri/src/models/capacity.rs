@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// A per-developer capacity rule layered on top of `Developer::max_hours_per_day`.
+///
+/// `mode` decides how `value` is applied on matching days:
+/// - `set`    — replace the base capacity with `value` hours (e.g. 4h/day for a month)
+/// - `ratio`  — multiply the capacity by `value` (e.g. 0.5 while shared with another project)
+/// - `reduce` — subtract `value` hours (e.g. 1.5h of meetings every Monday)
+///
+/// A rule matches a date when the date is inside `[start_date, end_date]` (either side
+/// may be open) and, if `weekday` is set, falls on that weekday (0=Mon .. 6=Sun).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityRule {
+    pub id: i64,
+    pub developer_id: i64,
+    pub mode: String,
+    pub value: f64,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub weekday: Option<u32>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCapacityRuleDto {
+    pub developer_id: i64,
+    pub mode: String,
+    pub value: f64,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub weekday: Option<u32>,
+    pub note: Option<String>,
+}
+
+/// Effective capacity of a developer on a single day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyCapacity {
+    pub date: String,
+    pub is_workday: bool,
+    pub base_hours: f64,
+    pub hours: f64,
+}
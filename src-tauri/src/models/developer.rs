@@ -6,18 +6,37 @@ pub struct Developer {
     pub name: String,
     pub roles: Vec<String>,
     pub skills: Vec<String>,
+    /// Proficiency per skill (1-5). Skills listed in `skills` without a level count as `DEFAULT_SKILL_LEVEL`.
+    #[serde(default)]
+    pub skill_levels: Vec<SkillLevel>,
     pub max_hours_per_day: f64,
     pub avatar_color: String,
     pub is_active: bool,
+    /// Region calendar for workday evaluation; `None` uses the default calendar.
+    #[serde(default)]
+    pub calendar_id: Option<i64>,
 }
 
+/// A named skill with a proficiency level from 1 (beginner) to 5 (expert).
+/// On tasks the level is the minimum proficiency required.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillLevel {
+    pub name: String,
+    pub level: u8,
+}
+
+pub const DEFAULT_SKILL_LEVEL: u8 = 3;
+pub const MAX_SKILL_LEVEL: u8 = 5;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateDeveloperDto {
     pub name: String,
     pub roles: Option<Vec<String>>,
     pub skills: Option<Vec<String>>,
+    pub skill_levels: Option<Vec<SkillLevel>>,
     pub max_hours_per_day: Option<f64>,
     pub avatar_color: Option<String>,
+    pub calendar_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +45,66 @@ pub struct UpdateDeveloperDto {
     pub name: Option<String>,
     pub roles: Option<Vec<String>>,
     pub skills: Option<Vec<String>>,
+    pub skill_levels: Option<Vec<SkillLevel>>,
     pub max_hours_per_day: Option<f64>,
     pub avatar_color: Option<String>,
     pub is_active: Option<bool>,
+    pub calendar_id: Option<i64>,
+    /// Drop the developer's calendar so the default calendar applies again.
+    #[serde(default)]
+    pub clear_calendar: bool,
+}
+
+/// Explicit new owner for one task during offboarding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskReassignment {
+    pub task_id: i64,
+    pub developer_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffboardDeveloperDto {
+    pub developer_id: i64,
+    /// Per-task targets; applies to both ownership and co-ownership of that task.
+    pub assignments: Option<Vec<TaskReassignment>>,
+    /// Target for tasks without an explicit assignment. When unset the assignment scorer picks.
+    pub default_assignee_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReassignedTask {
+    pub task_id: i64,
+    pub task_name: String,
+    /// `owner` or `co_owner`
+    pub role: String,
+    /// `None` when nobody could take over (owner cleared / co-owner dropped).
+    pub to_developer_id: Option<i64>,
+    pub to_developer_name: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffboardReport {
+    pub developer_id: i64,
+    pub developer_name: String,
+    pub moved: Vec<ReassignedTask>,
+    /// Completed or cancelled tasks left attributed to the developer.
+    pub retained_history_tasks: usize,
 }
 
 impl Developer {
+    /// Proficiency in `skill` (case-insensitive), or `None` if the developer lacks it.
+    pub fn skill_level(&self, skill: &str) -> Option<u8> {
+        let wanted = skill.trim().to_lowercase();
+        if let Some(s) = self.skill_levels.iter().find(|s| s.name.trim().to_lowercase() == wanted) {
+            return Some(s.level.clamp(1, MAX_SKILL_LEVEL));
+        }
+        self.skills
+            .iter()
+            .any(|s| s.trim().to_lowercase() == wanted)
+            .then_some(DEFAULT_SKILL_LEVEL)
+    }
+
     pub fn default_colors() -> Vec<&'static str> {
         vec![
             "#1890ff", "#52c41a", "#faad14", "#f5222d", "#722ed1",
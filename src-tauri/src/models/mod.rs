@@ -6,3 +6,8 @@ pub mod calendar;
 pub mod settings;
 pub mod batch;
 pub mod standup;
+pub mod capacity;
+pub mod assignment;
+pub mod team;
+pub mod baseline;
+pub mod scenario;
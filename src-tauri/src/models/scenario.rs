@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// A named what-if branch of the live plan. Only the tasks edited in the scenario and
+/// the developers added to it are stored; everything else reads through to the live data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    /// Set once the scenario has been applied to the live plan; it is read-only after that.
+    pub applied_at: Option<String>,
+    #[serde(default)]
+    pub task_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScenarioDto {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A developer that exists only inside a scenario (e.g. a prospective contractor).
+/// Applying the scenario creates the real developer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioDeveloper {
+    pub id: i64,
+    pub scenario_id: i64,
+    pub name: String,
+    pub max_hours_per_day: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScenarioDeveloperDto {
+    pub scenario_id: i64,
+    pub name: String,
+    pub max_hours_per_day: Option<f64>,
+}
+
+/// Planned values of a task inside a scenario. At most one of `owner_id` (a real
+/// developer) and `scenario_developer_id` is set. The `base_*` fields are the live
+/// values when the scenario first edited the task: fields equal to them are not
+/// changed by the scenario, and a live value that moved away from them has drifted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioTask {
+    pub scenario_id: i64,
+    pub task_id: i64,
+    pub task_name: String,
+    pub owner_id: Option<i64>,
+    pub scenario_developer_id: Option<i64>,
+    pub planned_start: Option<String>,
+    pub planned_end: Option<String>,
+    pub planned_hours: Option<f64>,
+    pub base_owner_id: Option<i64>,
+    pub base_start: Option<String>,
+    pub base_end: Option<String>,
+    pub base_hours: Option<f64>,
+}
+
+/// Edit of one task inside a scenario; `None` keeps the scenario's current value.
+/// Setting `scenario_developer_id` reassigns to a scenario-only developer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioTaskEdit {
+    pub scenario_id: i64,
+    pub task_id: i64,
+    pub owner_id: Option<i64>,
+    pub scenario_developer_id: Option<i64>,
+    pub planned_start: Option<String>,
+    pub planned_end: Option<String>,
+    pub planned_hours: Option<f64>,
+}
+
+/// Plan health of the live plan (`scenario_id` none) or a scenario over a date range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioSummary {
+    pub scenario_id: Option<i64>,
+    pub name: String,
+    /// Latest planned end among open tasks.
+    pub finish_date: Option<String>,
+    pub capacity_hours: f64,
+    pub allocated_hours: f64,
+    /// Developer-days booked above capacity, and the hours above it.
+    pub overload_days: i64,
+    pub overload_hours: f64,
+    /// Developer-workdays with capacity but nothing booked, and the unbooked hours overall.
+    pub idle_days: i64,
+    pub idle_hours: f64,
+}
+
+/// A task planned differently in the two compared plans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioTaskDiff {
+    pub task_id: i64,
+    pub task_name: String,
+    pub left_owner_name: Option<String>,
+    pub right_owner_name: Option<String>,
+    pub left_start: Option<String>,
+    pub right_start: Option<String>,
+    pub left_end: Option<String>,
+    pub right_end: Option<String>,
+    /// Calendar days the right plan ends later than the left one.
+    pub end_delta_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioComparison {
+    pub start_date: String,
+    pub end_date: String,
+    pub left: ScenarioSummary,
+    pub right: ScenarioSummary,
+    pub tasks: Vec<ScenarioTaskDiff>,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::team::MemberUtilization;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -53,3 +54,45 @@ pub struct CreateProjectDto {
     pub code: Option<String>,
     pub description: Option<String>,
 }
+
+/// Sprint capacity over the sprint's date range, with each member's workdays evaluated
+/// on their own calendar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintCapacitySummary {
+    pub sprint_id: i64,
+    pub sprint_name: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub capacity_hours: f64,
+    pub allocated_hours: f64,
+    pub utilization: f64,
+    pub members: Vec<MemberUtilization>,
+}
+
+/// A dated checkpoint shown on the calendar, optionally tied to a sprint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub id: i64,
+    pub name: String,
+    pub date: String,
+    pub sprint_id: Option<i64>,
+    pub sprint_name: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMilestoneDto {
+    pub name: String,
+    pub date: String,
+    pub sprint_id: Option<i64>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMilestoneDto {
+    pub id: i64,
+    pub name: Option<String>,
+    pub date: Option<String>,
+    pub sprint_id: Option<i64>,
+    pub note: Option<String>,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::developer::SkillLevel;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -14,12 +15,26 @@ pub struct Task {
     pub priority: Option<String>,
     pub planned_start: Option<String>,
     pub planned_end: Option<String>,
+    /// Optional "HH:MM" on `planned_start`'s day; untimed tasks are packed into the work window.
+    pub planned_start_time: Option<String>,
+    /// Optional "HH:MM" on `planned_end`'s day.
+    pub planned_end_time: Option<String>,
     pub planned_hours: Option<f64>,
     pub parent_task_id: Option<i64>,
     pub parent_number: Option<String>,
     pub parent_name: Option<String>,
     pub status: Option<String>,
     pub co_owners: Option<Vec<CoOwner>>,
+    /// Skills needed for the task; `level` is the minimum proficiency.
+    #[serde(default)]
+    pub required_skills: Vec<SkillLevel>,
+}
+
+impl Task {
+    /// Neither completed nor cancelled.
+    pub fn is_open(&self) -> bool {
+        !matches!(self.status.as_deref(), Some("已完成") | Some("已取消"))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,12 +54,15 @@ pub struct CreateTaskDto {
     pub priority: Option<String>,
     pub planned_start: Option<String>,
     pub planned_end: Option<String>,
+    pub planned_start_time: Option<String>,
+    pub planned_end_time: Option<String>,
     pub planned_hours: Option<f64>,
     pub parent_task_id: Option<i64>,
     pub parent_number: Option<String>,
     pub parent_name: Option<String>,
     pub status: Option<String>,
     pub co_owner_ids: Option<Vec<i64>>,
+    pub required_skills: Option<Vec<SkillLevel>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,12 +77,15 @@ pub struct UpdateTaskDto {
     pub priority: Option<String>,
     pub planned_start: Option<String>,
     pub planned_end: Option<String>,
+    pub planned_start_time: Option<String>,
+    pub planned_end_time: Option<String>,
     pub planned_hours: Option<f64>,
     pub parent_task_id: Option<i64>,
     pub parent_number: Option<String>,
     pub parent_name: Option<String>,
     pub status: Option<String>,
     pub co_owner_ids: Option<Vec<i64>>,
+    pub required_skills: Option<Vec<SkillLevel>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -81,4 +102,36 @@ pub struct TaskFilter {
     pub end_date: Option<String>,
     /// 按ID列表精确过滤 by AI.Coding
     pub task_ids: Option<Vec<i64>>,
+    /// Tasks owned or co-owned by a member of this team.
+    pub team_id: Option<i64>,
+}
+
+/// `successor_id` can't start before `predecessor_id` ends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskDependency {
+    pub predecessor_id: i64,
+    pub successor_id: i64,
+}
+
+/// Hours manually pinned to one day of a task; the task's other hours are distributed automatically.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyAllocation {
+    pub task_id: i64,
+    pub date: String,
+    pub hours: f64,
+}
+
+/// One planned date change produced by `move_task` or resource leveling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMove {
+    pub task_id: i64,
+    pub task_name: String,
+    pub owner_id: Option<i64>,
+    pub old_start: Option<String>,
+    pub old_end: Option<String>,
+    pub new_start: String,
+    pub new_end: String,
+    /// `moved` for the dragged task, `dependency` or `same_owner` for rippled ones;
+    /// leveling uses `extended` and `shifted`.
+    pub reason: String,
 }
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// A named group of developers (e.g. frontend / backend / QA).
+/// A developer may belong to several teams.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub member_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTeamDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub member_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTeamDto {
+    pub id: i64,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    /// Replaces the whole membership when set.
+    pub member_ids: Option<Vec<i64>>,
+}
+
+/// Capacity vs. allocated hours for one member over a date range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberUtilization {
+    pub developer_id: i64,
+    pub developer_name: String,
+    /// Workdays in the range on the member's own calendar.
+    #[serde(default)]
+    pub workdays: i64,
+    pub capacity_hours: f64,
+    pub allocated_hours: f64,
+    pub utilization: f64,
+}
+
+/// Team totals over a date range; `utilization` is allocated / capacity (0 when no capacity).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamCapacitySummary {
+    pub team_id: i64,
+    pub team_name: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub capacity_hours: f64,
+    pub allocated_hours: f64,
+    pub utilization: f64,
+    pub members: Vec<MemberUtilization>,
+}
@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet};
+use rusqlite::Connection;
+use chrono::{Duration, Local, NaiveDate};
+use crate::db::{developer_repo, task_repo};
+use crate::models::assignment::AssignmentScore;
+use crate::models::developer::{Developer, SkillLevel};
+use crate::models::task::{Task, TaskFilter};
+use crate::services::capacity_service::{self, CapacityProfile};
+use crate::holiday::work_calendar::WorkCalendar;
+use crate::services::holiday_service::{self, DeveloperCalendars};
+use crate::services::date_service::parse_ymd;
+use crate::services::settings_service;
+
+pub const ALLOC_EPSILON: f64 = 1e-6;
+pub const MAX_SCHEDULE_DAYS_SCAN: i64 = 3650;
+/// Number of upcoming workdays considered when measuring current load.
+pub const LOAD_WINDOW_WORKDAYS: usize = 10;
+
+/// First day on which `hours` of work starting at `start` is done at `profile`'s capacity.
+pub fn finish_date(calendar: &WorkCalendar, profile: &CapacityProfile, start: NaiveDate, hours: f64) -> Option<NaiveDate> {
+    let mut remaining = hours;
+    let mut day = start;
+    for _ in 0..MAX_SCHEDULE_DAYS_SCAN {
+        if calendar.is_workday(&day) {
+            remaining -= profile.hours_on(&day);
+            if remaining <= ALLOC_EPSILON {
+                return Some(day);
+            }
+        }
+        day += Duration::days(1);
+    }
+    None
+}
+
+/// Manually pinned hours per task and day (`task_daily_allocations`).
+pub type PinnedHours = HashMap<i64, HashMap<NaiveDate, f64>>;
+
+pub fn load_pinned_hours(conn: &Connection) -> Result<PinnedHours, String> {
+    let mut pinned: PinnedHours = HashMap::new();
+    for a in task_repo::get_all_daily_allocations(conn).map_err(|e| e.to_string())? {
+        if let Some(date) = parse_ymd(&a.date) {
+            pinned.entry(a.task_id).or_default().insert(date, a.hours);
+        }
+    }
+    Ok(pinned)
+}
+
+/// Hours already booked per (developer, day): pinned days as pinned, the rest of each
+/// task spread evenly over the owner's other workdays.
+pub fn build_existing_daily_load(
+    calendars: &DeveloperCalendars,
+    all_tasks: &[Task],
+    exclude_task_ids: &HashSet<i64>,
+    pinned: &PinnedHours,
+) -> HashMap<(i64, NaiveDate), f64> {
+    let mut load: HashMap<(i64, NaiveDate), f64> = HashMap::new();
+
+    for t in all_tasks {
+        if exclude_task_ids.contains(&t.id) {
+            continue;
+        }
+        if matches!(t.status.as_deref(), Some("已取消")) {
+            continue;
+        }
+
+        let Some(owner_id) = t.owner_id else { continue };
+        book_task(calendars.of(owner_id), &mut load, owner_id, t, pinned.get(&t.id));
+    }
+
+    load
+}
+
+/// Add `task`'s planned hours to `developer_id`'s daily load. Pinned days get their
+/// pinned hours; the remainder is spread evenly over the other workdays of their
+/// `calendar` (or lands on the last workday when every workday is pinned).
+/// Tasks without a valid date range or hours are ignored.
+pub fn book_task(
+    calendar: &WorkCalendar,
+    load: &mut HashMap<(i64, NaiveDate), f64>,
+    developer_id: i64,
+    task: &Task,
+    pinned: Option<&HashMap<NaiveDate, f64>>,
+) {
+    let Some(start) = task.planned_start.as_deref().and_then(parse_ymd) else {
+        return;
+    };
+    let Some(end) = task.planned_end.as_deref().and_then(parse_ymd) else {
+        return;
+    };
+    if end < start {
+        return;
+    }
+
+    let hours = task.planned_hours.unwrap_or(0.0);
+    if hours <= ALLOC_EPSILON {
+        return;
+    }
+
+    let mut remaining = hours;
+    for (day, h) in pinned.into_iter().flatten() {
+        if *day >= start && *day <= end {
+            *load.entry((developer_id, *day)).or_insert(0.0) += h;
+            remaining -= h;
+        }
+    }
+    if remaining <= ALLOC_EPSILON {
+        return;
+    }
+
+    let workdays = calendar.workdays(start, end);
+    let free: Vec<NaiveDate> = workdays
+        .iter()
+        .copied()
+        .filter(|d| !pinned.is_some_and(|p| p.contains_key(d)))
+        .collect();
+    if free.is_empty() {
+        if let Some(last) = workdays.last() {
+            *load.entry((developer_id, *last)).or_insert(0.0) += remaining;
+        }
+        return;
+    }
+
+    let daily = remaining / (free.len() as f64);
+    for d in free {
+        *load.entry((developer_id, d)).or_insert(0.0) += daily;
+    }
+}
+
+/// (skill weight, load weight) for a task priority. Urgent work favours the best
+/// skill match; low-priority work favours whoever has spare time.
+fn priority_weights(priority: Option<&str>) -> (f64, f64) {
+    match priority.map(|p| p.trim().to_uppercase()).as_deref() {
+        Some("P0") => (0.7, 0.3),
+        Some("P1") => (0.55, 0.45),
+        _ => (0.4, 0.6),
+    }
+}
+
+/// Average coverage of `required` skills in `[0, 1]` plus the skills the developer lacks entirely.
+///
+/// A skill held below the required level counts proportionally (level 2 of 4 = 0.5).
+/// Tasks without required skills fit everyone.
+pub fn skill_fit(developer: &Developer, required: &[SkillLevel]) -> (f64, Vec<String>) {
+    if required.is_empty() {
+        return (1.0, Vec::new());
+    }
+    let mut total = 0.0;
+    let mut missing = Vec::new();
+    for req in required {
+        match developer.skill_level(&req.name) {
+            Some(level) => {
+                let needed = req.level.max(1) as f64;
+                total += (level as f64 / needed).min(1.0);
+            }
+            None => missing.push(req.name.clone()),
+        }
+    }
+    (total / required.len() as f64, missing)
+}
+
+/// Score one developer for `task` given their load ratio over the look-ahead window.
+pub fn score_developer(developer: &Developer, task: &Task, load_ratio: f64) -> AssignmentScore {
+    let (fit, missing_skills) = skill_fit(developer, &task.required_skills);
+    let (skill_w, load_w) = priority_weights(task.priority.as_deref());
+    let free = 1.0 - load_ratio.clamp(0.0, 1.0);
+    let score = skill_w * fit + load_w * free;
+
+    let mut reasons = Vec::new();
+    if !task.required_skills.is_empty() {
+        reasons.push(format!("技能匹配 {:.0}%", fit * 100.0));
+    }
+    if !missing_skills.is_empty() {
+        reasons.push(format!("缺少技能: {}", missing_skills.join(", ")));
+    }
+    reasons.push(format!(
+        "未来{}个工作日负载 {:.0}%",
+        LOAD_WINDOW_WORKDAYS,
+        load_ratio * 100.0
+    ));
+
+    AssignmentScore {
+        developer_id: developer.id,
+        developer_name: developer.name.clone(),
+        score,
+        skill_fit: fit,
+        load_ratio,
+        missing_skills,
+        reasons,
+    }
+}
+
+/// Booked hours divided by available hours over the developer's next `LOAD_WINDOW_WORKDAYS` workdays.
+pub fn load_ratio(
+    calendar: &WorkCalendar,
+    developer_id: i64,
+    profile: &CapacityProfile,
+    load_map: &HashMap<(i64, NaiveDate), f64>,
+    from: NaiveDate,
+) -> f64 {
+    let mut booked = 0.0;
+    let mut available = 0.0;
+    let mut day = calendar.next_workday(from);
+    for _ in 0..LOAD_WINDOW_WORKDAYS {
+        booked += load_map.get(&(developer_id, day)).copied().unwrap_or(0.0);
+        available += profile.hours_on(&day);
+        day = calendar.next_workday(day + Duration::days(1));
+    }
+    if available <= ALLOC_EPSILON {
+        // No capacity at all in the window: treat as fully booked.
+        return 1.0;
+    }
+    booked / available
+}
+
+/// Score every developer that has a profile and return them best first.
+///
+/// Ties are broken by developer id so the ranking is stable between runs.
+pub fn rank_candidates(
+    calendars: &DeveloperCalendars,
+    task: &Task,
+    developers: &[Developer],
+    profiles: &HashMap<i64, CapacityProfile>,
+    load_map: &HashMap<(i64, NaiveDate), f64>,
+    from: NaiveDate,
+) -> Vec<AssignmentScore> {
+    let mut scores: Vec<AssignmentScore> = developers
+        .iter()
+        .filter_map(|d| {
+            let profile = profiles.get(&d.id)?;
+            let ratio = load_ratio(calendars.of(d.id), d.id, profile, load_map, from);
+            Some(score_developer(d, task, ratio))
+        })
+        .collect();
+    scores.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.developer_id.cmp(&b.developer_id))
+    });
+    scores
+}
+
+/// Ranked owner suggestions for a task among active developers.
+pub fn suggest_owner(conn: &Connection, task_id: i64, limit: Option<usize>) -> Result<Vec<AssignmentScore>, String> {
+    let task = task_repo::get_by_id(conn, task_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Task not found".to_string())?;
+    let all_tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let developers: Vec<Developer> = developer_repo::get_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|d| d.is_active)
+        .collect();
+    let hours_per_day = settings_service::default_hours_per_day(conn);
+
+    let today = Local::now().date_naive();
+    holiday_service::ensure_holidays_cached(conn, &today, &(today + Duration::days(60)));
+
+    let profiles = capacity_service::load_profiles(conn, &developers, hours_per_day)?;
+    let calendars = DeveloperCalendars::load(conn)?;
+    let exclude: HashSet<i64> = [task.id].into_iter().collect();
+    let pinned = load_pinned_hours(conn)?;
+    let load_map = build_existing_daily_load(&calendars, &all_tasks, &exclude, &pinned);
+
+    let mut ranked = rank_candidates(&calendars, &task, &developers, &profiles, &load_map, today);
+    if let Some(limit) = limit {
+        ranked.truncate(limit);
+    }
+    Ok(ranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dev(id: i64, levels: &[(&str, u8)]) -> Developer {
+        Developer {
+            id,
+            name: format!("Dev-{}", id),
+            roles: vec![],
+            skills: vec![],
+            skill_levels: levels
+                .iter()
+                .map(|(n, l)| SkillLevel { name: n.to_string(), level: *l })
+                .collect(),
+            max_hours_per_day: 8.0,
+            avatar_color: "#1890ff".to_string(),
+            is_active: true,
+            calendar_id: None,
+        }
+    }
+
+    fn task(priority: &str, required: &[(&str, u8)]) -> Task {
+        Task {
+            id: 1,
+            external_id: None,
+            task_type: None,
+            name: "Task".to_string(),
+            description: None,
+            owner_id: None,
+            owner_name: None,
+            sprint_id: None,
+            sprint_name: None,
+            priority: Some(priority.to_string()),
+            planned_start: None,
+            planned_end: None,
+            planned_start_time: None,
+            planned_end_time: None,
+            planned_hours: Some(8.0),
+            parent_task_id: None,
+            parent_number: None,
+            parent_name: None,
+            status: None,
+            co_owners: None,
+            required_skills: required
+                .iter()
+                .map(|(n, l)| SkillLevel { name: n.to_string(), level: *l })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn skill_fit_is_partial_below_required_level_and_zero_when_missing() {
+        let d = dev(1, &[("Rust", 2)]);
+        let (fit, missing) = skill_fit(&d, &[
+            SkillLevel { name: "rust".to_string(), level: 4 },
+            SkillLevel { name: "SQL".to_string(), level: 3 },
+        ]);
+        assert!((fit - 0.25).abs() < 1e-9);
+        assert_eq!(missing, vec!["SQL".to_string()]);
+    }
+
+    #[test]
+    fn urgent_tasks_prefer_skill_and_low_priority_prefers_spare_time() {
+        let expert = dev(1, &[("Rust", 5)]);
+        let novice = dev(2, &[("Rust", 2)]);
+
+        // Expert is 60% booked, novice is idle.
+        let p0 = task("P0", &[("Rust", 4)]);
+        assert!(score_developer(&expert, &p0, 0.6).score > score_developer(&novice, &p0, 0.0).score);
+
+        let p2 = task("P2", &[("Rust", 4)]);
+        assert!(score_developer(&novice, &p2, 0.0).score > score_developer(&expert, &p2, 0.6).score);
+    }
+}
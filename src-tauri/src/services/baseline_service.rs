@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use crate::db::{baseline_repo, sprint_repo, task_repo};
+use crate::models::baseline::{Baseline, BaselineTask, BaselineVariance, TaskVariance};
+use crate::models::task::{Task, TaskFilter};
+use crate::services::assignment_service::ALLOC_EPSILON;
+use crate::services::date_service::parse_ymd;
+
+/// Tasks a baseline covers: one sprint's, or every task. Cancelled tasks are out of plan.
+fn tasks_in_scope(conn: &Connection, sprint_id: Option<i64>) -> Result<Vec<Task>, String> {
+    let filter = TaskFilter { sprint_id, ..TaskFilter::default() };
+    Ok(task_repo::get_all(conn, &filter)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|t| t.status.as_deref() != Some("已取消"))
+        .collect())
+}
+
+pub fn list_baselines(conn: &Connection, sprint_id: Option<i64>) -> Result<Vec<Baseline>, String> {
+    baseline_repo::get_all(conn, sprint_id).map_err(|e| e.to_string())
+}
+
+/// Snapshot the planned start/end/hours of the tasks in scope under `name`.
+pub fn save_baseline(conn: &Connection, name: &str, sprint_id: Option<i64>) -> Result<Baseline, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("基线名称不能为空".to_string());
+    }
+    if let Some(id) = sprint_id {
+        sprint_repo::get_sprint_by_id(conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "迭代不存在".to_string())?;
+    }
+    let tasks = tasks_in_scope(conn, sprint_id)?;
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = (|| -> rusqlite::Result<i64> {
+        let id = baseline_repo::create(conn, name, sprint_id)?;
+        for task in &tasks {
+            let snapshot = BaselineTask {
+                task_id: task.id,
+                task_name: task.name.clone(),
+                owner_id: task.owner_id,
+                planned_start: task.planned_start.clone(),
+                planned_end: task.planned_end.clone(),
+                planned_hours: task.planned_hours,
+            };
+            baseline_repo::insert_task(conn, id, &snapshot)?;
+        }
+        Ok(id)
+    })();
+    let id = match result {
+        Ok(id) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            id
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            return Err(format!("保存基线失败: {}", e));
+        }
+    };
+    baseline_repo::get_by_id(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "基线不存在".to_string())
+}
+
+pub fn delete_baseline(conn: &Connection, id: i64) -> Result<(), String> {
+    baseline_repo::delete(conn, id).map_err(|e| e.to_string())
+}
+
+/// Signed calendar days from `from` to `to`, when both are valid dates.
+fn slip_days(from: Option<&str>, to: Option<&str>) -> Option<i64> {
+    let from = parse_ymd(from?)?;
+    let to = parse_ymd(to?)?;
+    Some((to - from).num_days())
+}
+
+/// Compare the current plan of a baseline's scope against the snapshot.
+///
+/// Tasks deleted, moved out of the sprint or cancelled since count as removed; tasks
+/// created or moved in count as added. `hours_growth` is the net change in planned
+/// hours across the scope.
+pub fn get_variance(conn: &Connection, baseline_id: i64) -> Result<BaselineVariance, String> {
+    let baseline = baseline_repo::get_by_id(conn, baseline_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "基线不存在".to_string())?;
+    let snapshot = baseline_repo::get_tasks(conn, baseline_id).map_err(|e| e.to_string())?;
+    let current = tasks_in_scope(conn, baseline.sprint_id)?;
+    let current_by_id: HashMap<i64, &Task> = current.iter().map(|t| (t.id, t)).collect();
+
+    let mut tasks = Vec::new();
+    for before in &snapshot {
+        let now = current_by_id.get(&before.task_id).copied();
+        let baseline_hours = before.planned_hours.unwrap_or(0.0);
+        let current_hours = now.and_then(|t| t.planned_hours).unwrap_or(0.0);
+        let (start_slip, end_slip) = match now {
+            Some(t) => (
+                slip_days(before.planned_start.as_deref(), t.planned_start.as_deref()),
+                slip_days(before.planned_end.as_deref(), t.planned_end.as_deref()),
+            ),
+            None => (None, None),
+        };
+        let change = match now {
+            None => "removed",
+            Some(t) if t.planned_start != before.planned_start
+                || t.planned_end != before.planned_end
+                || (current_hours - baseline_hours).abs() > ALLOC_EPSILON => "changed",
+            Some(_) => "unchanged",
+        };
+        tasks.push(TaskVariance {
+            task_id: before.task_id,
+            task_name: now.map(|t| t.name.clone()).unwrap_or_else(|| before.task_name.clone()),
+            owner_name: now.and_then(|t| t.owner_name.clone()),
+            change: change.to_string(),
+            baseline_start: before.planned_start.clone(),
+            baseline_end: before.planned_end.clone(),
+            current_start: now.and_then(|t| t.planned_start.clone()),
+            current_end: now.and_then(|t| t.planned_end.clone()),
+            start_slip_days: start_slip,
+            end_slip_days: end_slip,
+            baseline_hours: before.planned_hours,
+            current_hours: now.and_then(|t| t.planned_hours),
+            hours_delta: if now.is_some() { current_hours - baseline_hours } else { -baseline_hours },
+        });
+    }
+    for task in &current {
+        if snapshot.iter().any(|b| b.task_id == task.id) {
+            continue;
+        }
+        tasks.push(TaskVariance {
+            task_id: task.id,
+            task_name: task.name.clone(),
+            owner_name: task.owner_name.clone(),
+            change: "added".to_string(),
+            baseline_start: None,
+            baseline_end: None,
+            current_start: task.planned_start.clone(),
+            current_end: task.planned_end.clone(),
+            start_slip_days: None,
+            end_slip_days: None,
+            baseline_hours: None,
+            current_hours: task.planned_hours,
+            hours_delta: task.planned_hours.unwrap_or(0.0),
+        });
+    }
+    tasks.sort_by_key(|t| t.task_id);
+
+    let count = |change: &str| tasks.iter().filter(|t| t.change == change).count() as i64;
+    let slips: Vec<i64> = tasks.iter().filter_map(|t| t.end_slip_days).filter(|d| *d > 0).collect();
+    let baseline_hours: f64 = snapshot.iter().filter_map(|t| t.planned_hours).sum();
+    let current_hours: f64 = current.iter().filter_map(|t| t.planned_hours).sum();
+    Ok(BaselineVariance {
+        added_count: count("added"),
+        removed_count: count("removed"),
+        changed_count: count("changed"),
+        slipped_count: slips.len() as i64,
+        max_slip_days: slips.iter().copied().max().unwrap_or(0),
+        baseline_hours,
+        current_hours,
+        hours_growth: current_hours - baseline_hours,
+        baseline,
+        tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute_batch(
+            "INSERT INTO sprints (name, start_date, end_date) VALUES ('S1', '2026-11-02', '2026-11-13');
+             INSERT INTO tasks (name, sprint_id, planned_start, planned_end, planned_hours) VALUES ('A', 1, '2026-11-02', '2026-11-04', 16);
+             INSERT INTO tasks (name, sprint_id, planned_start, planned_end, planned_hours) VALUES ('B', 1, '2026-11-05', '2026-11-06', 8);
+             INSERT INTO tasks (name, sprint_id, planned_start, planned_end, planned_hours) VALUES ('C', 1, '2026-11-09', '2026-11-09', 4);
+             INSERT INTO tasks (name, planned_start, planned_end, planned_hours) VALUES ('other', '2026-11-02', '2026-11-02', 2);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn variance_reports_slips_growth_and_scope_changes() {
+        let conn = setup_conn();
+        let baseline = save_baseline(&conn, "kickoff", Some(1)).unwrap();
+        assert_eq!(baseline.task_count, 3);
+        assert!(save_baseline(&conn, "  ", None).is_err());
+
+        conn.execute_batch(
+            "UPDATE tasks SET planned_end = '2026-11-06', planned_hours = 24 WHERE name = 'A';
+             UPDATE tasks SET status = '已取消' WHERE name = 'C';
+             INSERT INTO tasks (name, sprint_id, planned_start, planned_end, planned_hours) VALUES ('D', 1, '2026-11-10', '2026-11-11', 6);",
+        )
+        .unwrap();
+
+        let variance = get_variance(&conn, baseline.id).unwrap();
+        let summary: Vec<(&str, &str, Option<i64>, f64)> = variance
+            .tasks
+            .iter()
+            .map(|t| (t.task_name.as_str(), t.change.as_str(), t.end_slip_days, t.hours_delta))
+            .collect();
+        assert_eq!(summary, vec![
+            ("A", "changed", Some(2), 8.0),
+            ("B", "unchanged", Some(0), 0.0),
+            ("C", "removed", None, -4.0),
+            ("D", "added", None, 6.0),
+        ]);
+        assert_eq!((variance.added_count, variance.removed_count, variance.changed_count), (1, 1, 1));
+        assert_eq!((variance.slipped_count, variance.max_slip_days), (1, 2));
+        assert_eq!((variance.baseline_hours, variance.current_hours), (28.0, 38.0));
+        assert_eq!(variance.hours_growth, 10.0);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use chrono::{Datelike, NaiveDate};
+use crate::db::{capacity_repo, developer_repo};
+use crate::models::capacity::{CapacityRule, CreateCapacityRuleDto, DailyCapacity};
+use crate::models::developer::Developer;
+use crate::services::{holiday_service, settings_service};
+
+/// Base capacity plus the rules that adjust it, resolved per day.
+#[derive(Debug, Clone)]
+pub struct CapacityProfile {
+    base_hours: f64,
+    rules: Vec<CapacityRule>,
+}
+
+impl CapacityProfile {
+    pub fn new(base_hours: f64, rules: Vec<CapacityRule>) -> Self {
+        Self { base_hours, rules }
+    }
+
+    pub fn base_hours(&self) -> f64 {
+        self.base_hours
+    }
+
+    /// The same rules over a base of at least `floor` hours.
+    pub fn with_min_base(self, floor: f64) -> Self {
+        Self { base_hours: self.base_hours.max(floor), ..self }
+    }
+
+    /// Effective hours available on `date`.
+    ///
+    /// Matching rules are applied in three passes: `set` rules replace the base
+    /// (later rules win), then `ratio` rules scale it, then `reduce` rules subtract.
+    /// The result never goes below zero.
+    pub fn hours_on(&self, date: &NaiveDate) -> f64 {
+        let matching: Vec<&CapacityRule> = self.rules.iter().filter(|r| rule_matches(r, date)).collect();
+
+        let mut hours = self.base_hours;
+        for rule in matching.iter().filter(|r| r.mode == "set") {
+            hours = rule.value;
+        }
+        for rule in matching.iter().filter(|r| r.mode == "ratio") {
+            hours *= rule.value;
+        }
+        for rule in matching.iter().filter(|r| r.mode == "reduce") {
+            hours -= rule.value;
+        }
+        hours.max(0.0)
+    }
+}
+
+fn rule_matches(rule: &CapacityRule, date: &NaiveDate) -> bool {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    if let Some(ref start) = rule.start_date {
+        if date_str.as_str() < start.as_str() {
+            return false;
+        }
+    }
+    if let Some(ref end) = rule.end_date {
+        if date_str.as_str() > end.as_str() {
+            return false;
+        }
+    }
+    match rule.weekday {
+        Some(wd) => date.weekday().num_days_from_monday() == wd,
+        None => true,
+    }
+}
+
+/// Base capacity for a developer, falling back to `default_hours` when unset.
+pub fn base_hours(developer: &Developer, default_hours: f64) -> f64 {
+    if developer.max_hours_per_day > 0.0 {
+        developer.max_hours_per_day
+    } else {
+        default_hours
+    }
+}
+
+pub fn load_profile(conn: &Connection, developer: &Developer, default_hours: f64) -> Result<CapacityProfile, String> {
+    let rules = capacity_repo::get_by_developer(conn, developer.id).map_err(|e| e.to_string())?;
+    Ok(CapacityProfile::new(base_hours(developer, default_hours), rules))
+}
+
+/// Load profiles for many developers with a single query.
+pub fn load_profiles(
+    conn: &Connection,
+    developers: &[Developer],
+    default_hours: f64,
+) -> Result<HashMap<i64, CapacityProfile>, String> {
+    let mut rules_by_dev: HashMap<i64, Vec<CapacityRule>> = HashMap::new();
+    for rule in capacity_repo::get_all(conn).map_err(|e| e.to_string())? {
+        rules_by_dev.entry(rule.developer_id).or_default().push(rule);
+    }
+    Ok(developers
+        .iter()
+        .map(|d| {
+            let rules = rules_by_dev.remove(&d.id).unwrap_or_default();
+            (d.id, CapacityProfile::new(base_hours(d, default_hours), rules))
+        })
+        .collect())
+}
+
+pub fn list_rules(conn: &Connection, developer_id: i64) -> Result<Vec<CapacityRule>, String> {
+    capacity_repo::get_by_developer(conn, developer_id).map_err(|e| e.to_string())
+}
+
+pub fn create_rule(conn: &Connection, dto: &CreateCapacityRuleDto) -> Result<i64, String> {
+    validate_rule(dto)?;
+    capacity_repo::create(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn delete_rule(conn: &Connection, id: i64) -> Result<(), String> {
+    capacity_repo::delete(conn, id).map_err(|e| e.to_string())
+}
+
+fn validate_rule(dto: &CreateCapacityRuleDto) -> Result<(), String> {
+    if !matches!(dto.mode.as_str(), "set" | "ratio" | "reduce") {
+        return Err(format!("未知的容量规则类型: {}", dto.mode));
+    }
+    if dto.value < 0.0 {
+        return Err("容量规则数值不能为负数".to_string());
+    }
+    if let Some(wd) = dto.weekday {
+        if wd > 6 {
+            return Err("weekday 必须在 0(周一) 到 6(周日) 之间".to_string());
+        }
+    }
+    let start = parse_optional_date(&dto.start_date)?;
+    let end = parse_optional_date(&dto.end_date)?;
+    if let (Some(s), Some(e)) = (start, end) {
+        if e < s {
+            return Err("结束日期不能早于开始日期".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn parse_optional_date(value: &Option<String>) -> Result<Option<NaiveDate>, String> {
+    match value {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("日期格式无效: {}", s)),
+        None => Ok(None),
+    }
+}
+
+/// Effective daily capacity of a developer over a date range (inclusive), evaluated
+/// on the developer's own calendar.
+pub fn get_effective_capacity(
+    conn: &Connection,
+    developer_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<DailyCapacity>, String> {
+    let developer = developer_repo::get_by_id(conn, developer_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Developer not found".to_string())?;
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    let region_calendar = holiday_service::calendar_for_developer(conn, &developer);
+    holiday_service::ensure_region_holidays_cached(conn, &region_calendar.region, &start, &end);
+    let calendar = holiday_service::work_calendar_for_developer(conn, &developer);
+    let profile = load_profile(conn, &developer, settings_service::default_hours_per_day(conn))?;
+
+    let mut days = Vec::new();
+    let mut current = start;
+    while current <= end {
+        let is_workday = calendar.is_workday(&current);
+        days.push(DailyCapacity {
+            date: current.format("%Y-%m-%d").to_string(),
+            is_workday,
+            base_hours: profile.base_hours(),
+            hours: if is_workday { profile.hours_on(&current) } else { 0.0 },
+        });
+        current += chrono::Duration::days(1);
+    }
+    Ok(days)
+}
+
+pub fn utilization_ratio(allocated: f64, capacity: f64) -> f64 {
+    if capacity > 0.0 { allocated / capacity } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(mode: &str, value: f64, start: Option<&str>, end: Option<&str>, weekday: Option<u32>) -> CapacityRule {
+        CapacityRule {
+            id: 0,
+            developer_id: 1,
+            mode: mode.to_string(),
+            value,
+            start_date: start.map(|s| s.to_string()),
+            end_date: end.map(|s| s.to_string()),
+            weekday,
+            note: None,
+        }
+    }
+
+    fn ymd(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn date_ranged_set_rule_only_applies_inside_range() {
+        let profile = CapacityProfile::new(8.0, vec![rule("set", 4.0, Some("2026-11-01"), Some("2026-11-30"), None)]);
+        assert_eq!(profile.hours_on(&ymd("2026-10-30")), 8.0);
+        assert_eq!(profile.hours_on(&ymd("2026-11-02")), 4.0);
+        assert_eq!(profile.hours_on(&ymd("2026-11-30")), 4.0);
+        assert_eq!(profile.hours_on(&ymd("2026-12-01")), 8.0);
+    }
+
+    #[test]
+    fn recurring_reduction_stacks_on_top_of_ratio() {
+        let profile = CapacityProfile::new(
+            8.0,
+            vec![
+                // 2026-11-02 is a Monday
+                rule("reduce", 1.5, None, None, Some(0)),
+                rule("ratio", 0.5, Some("2026-11-01"), None, None),
+            ],
+        );
+        assert_eq!(profile.hours_on(&ymd("2026-10-26")), 6.5);
+        assert_eq!(profile.hours_on(&ymd("2026-11-02")), 2.5);
+        assert_eq!(profile.hours_on(&ymd("2026-11-03")), 4.0);
+    }
+
+    #[test]
+    fn capacity_never_goes_negative() {
+        let profile = CapacityProfile::new(2.0, vec![rule("reduce", 3.0, None, None, None)]);
+        assert_eq!(profile.hours_on(&ymd("2026-11-02")), 0.0);
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use chrono::Duration;
+use crate::db::{developer_repo, sprint_repo, task_repo};
+use crate::models::calendar::ScheduleViolation;
+use crate::models::developer::Developer;
+use crate::models::task::{Task, TaskFilter};
+use crate::services::assignment_service::{finish_date, ALLOC_EPSILON};
+use crate::services::date_service::{fmt_ymd, parse_date_range, parse_ymd};
+use crate::services::capacity_service::{self, CapacityProfile};
+use crate::services::holiday_service::{self, DeveloperCalendars};
+use crate::services::{schedule_service, settings_service, team_service};
+
+/// Overloads above this share of the day's capacity are errors rather than warnings.
+const OVERLOAD_ERROR_RATIO: f64 = 1.5;
+
+fn violation(kind: &str, severity: &str, task: Option<&Task>, message: String, suggestion: String) -> ScheduleViolation {
+    ScheduleViolation {
+        kind: kind.to_string(),
+        severity: severity.to_string(),
+        task_id: task.map(|t| t.id),
+        task_name: task.map(|t| t.name.clone()),
+        developer_id: task.and_then(|t| t.owner_id),
+        developer_name: task.and_then(|t| t.owner_name.clone()),
+        date: task.and_then(|t| t.planned_start.clone()),
+        message,
+        suggestion,
+    }
+}
+
+/// Scan `[start_date, end_date]` for schedule problems, optionally limited to one team.
+///
+/// Reports developer-days booked over capacity (from the workload allocation, so pinned
+/// hours count), and open tasks touching the range that end before they start, fall
+/// entirely on non-workdays, can't fit their planned hours at the owner's capacity, lie
+/// outside their sprint, or start before a predecessor ends. Errors come first.
+pub fn scan_violations(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    team_id: Option<i64>,
+) -> Result<Vec<ScheduleViolation>, String> {
+    let (range_start, range_end) = parse_date_range(start_date, end_date)?;
+    let developers = team_service::active_developers(conn, team_id)?;
+    holiday_service::ensure_holidays_cached(conn, &range_start, &(range_end + Duration::days(365)));
+
+    let mut violations = Vec::new();
+    for dev in &developers {
+        violations.extend(overload_violations(conn, dev, start_date, end_date)?);
+    }
+
+    let all_tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let in_scope = |t: &Task| match team_id {
+        Some(_) => t.owner_id.is_some_and(|id| developers.iter().any(|d| d.id == id)),
+        None => true,
+    };
+    let touches_range = |t: &Task| {
+        let start = t.planned_start.as_deref().and_then(parse_ymd);
+        let end = t.planned_end.as_deref().and_then(parse_ymd);
+        match (start, end) {
+            (Some(s), Some(e)) => s.min(e) <= range_end && s.max(e) >= range_start,
+            (Some(d), None) | (None, Some(d)) => d >= range_start && d <= range_end,
+            (None, None) => false,
+        }
+    };
+    let tasks: Vec<&Task> = all_tasks.iter().filter(|t| t.is_open() && in_scope(t) && touches_range(t)).collect();
+
+    let calendars = DeveloperCalendars::load(conn)?;
+    let default_hours = settings_service::default_hours_per_day(conn);
+    let all_developers = developer_repo::get_all(conn).map_err(|e| e.to_string())?;
+    let profiles = capacity_service::load_profiles(conn, &all_developers, default_hours)?;
+    let default_profile = CapacityProfile::new(default_hours, Vec::new());
+    let sprints: HashMap<i64, (Option<String>, Option<String>)> = sprint_repo::get_all_sprints(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| (s.id, (s.start_date, s.end_date)))
+        .collect();
+
+    for task in &tasks {
+        let (Some(start), Some(end)) = (
+            task.planned_start.as_deref().and_then(parse_ymd),
+            task.planned_end.as_deref().and_then(parse_ymd),
+        ) else {
+            continue;
+        };
+        let calendar = calendars.for_owner(task.owner_id);
+
+        if end < start {
+            violations.push(violation(
+                "invalid_range",
+                "error",
+                Some(task),
+                format!("结束日期 {} 早于开始日期 {}", fmt_ymd(end), fmt_ymd(start)),
+                format!("将结束日期改为 {} 或之后", fmt_ymd(start)),
+            ));
+            continue;
+        }
+
+        let workdays = calendar.workdays(start, end);
+        if workdays.is_empty() {
+            violations.push(violation(
+                "non_workday",
+                "warning",
+                Some(task),
+                format!("{} ~ {} 全部为非工作日", fmt_ymd(start), fmt_ymd(end)),
+                format!("移至下一个工作日 {}，或确认为加班安排", fmt_ymd(calendar.next_workday(start))),
+            ));
+        } else if let Some(hours) = task.planned_hours.filter(|h| *h > ALLOC_EPSILON) {
+            let profile = task.owner_id.and_then(|id| profiles.get(&id)).unwrap_or(&default_profile);
+            let available: f64 = workdays.iter().map(|d| profile.hours_on(d)).sum();
+            if available + ALLOC_EPSILON < hours {
+                let suggestion = match finish_date(calendar, profile, start, hours) {
+                    Some(finish) => format!(
+                        "将结束日期延后至 {}，或将计划工时减少至 {:.1}h",
+                        fmt_ymd(finish),
+                        available
+                    ),
+                    None => "负责人在可见范围内没有可用工时，请改派".to_string(),
+                };
+                violations.push(violation(
+                    "insufficient_capacity",
+                    "error",
+                    Some(task),
+                    format!("计划工时 {:.1}h，区间内负责人可用工时仅 {:.1}h", hours, available),
+                    suggestion,
+                ));
+            }
+        }
+
+        if let Some((Some(sprint_start), Some(sprint_end))) = task.sprint_id.and_then(|id| sprints.get(&id)) {
+            let (sprint_start, sprint_end) = (sprint_start.as_str(), sprint_end.as_str());
+            let (task_start, task_end) = (fmt_ymd(start), fmt_ymd(end));
+            if task_start.as_str() < sprint_start || task_end.as_str() > sprint_end {
+                violations.push(violation(
+                    "outside_sprint",
+                    "warning",
+                    Some(task),
+                    format!(
+                        "任务 {} ~ {} 超出迭代「{}」{} ~ {}",
+                        task_start,
+                        task_end,
+                        task.sprint_name.as_deref().unwrap_or(""),
+                        sprint_start,
+                        sprint_end
+                    ),
+                    format!("调整到迭代区间 {} ~ {} 内，或移至其他迭代", sprint_start, sprint_end),
+                ));
+            }
+        }
+    }
+
+    let by_id: HashMap<i64, &Task> = all_tasks.iter().map(|t| (t.id, t)).collect();
+    for dep in task_repo::get_all_dependencies(conn).map_err(|e| e.to_string())? {
+        let (Some(pred), Some(succ)) = (by_id.get(&dep.predecessor_id), by_id.get(&dep.successor_id)) else {
+            continue;
+        };
+        if !succ.is_open() || !tasks.iter().any(|t| t.id == succ.id) {
+            continue;
+        }
+        let (Some(pred_end), Some(succ_start)) = (
+            pred.planned_end.as_deref().and_then(parse_ymd),
+            succ.planned_start.as_deref().and_then(parse_ymd),
+        ) else {
+            continue;
+        };
+        if succ_start <= pred_end {
+            let earliest = calendars.for_owner(succ.owner_id).next_workday(pred_end + Duration::days(1));
+            violations.push(violation(
+                "dependency",
+                "error",
+                Some(succ),
+                format!("前置任务「{}」{} 结束，本任务 {} 已开始", pred.name, fmt_ymd(pred_end), fmt_ymd(succ_start)),
+                format!("将开始日期调整为 {} 或之后", fmt_ymd(earliest)),
+            ));
+        }
+    }
+
+    violations.sort_by(|a, b| {
+        (a.severity != "error", &a.date, a.task_id, a.developer_id).cmp(&(b.severity != "error", &b.date, b.task_id, b.developer_id))
+    });
+    Ok(violations)
+}
+
+/// Developer-days in the range booked above that day's capacity.
+pub fn overload_violations(
+    conn: &Connection,
+    developer: &Developer,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<ScheduleViolation>, String> {
+    let days = schedule_service::get_developer_workload(conn, developer.id, start_date, end_date, false)?;
+    Ok(days
+        .into_iter()
+        .filter(|d| d.allocated_hours > d.max_hours + ALLOC_EPSILON)
+        .map(|d| {
+            let severity = if d.max_hours <= ALLOC_EPSILON || d.allocated_hours > d.max_hours * OVERLOAD_ERROR_RATIO {
+                "error"
+            } else {
+                "warning"
+            };
+            let booked: Vec<String> = d.tasks.iter().map(|t| format!("{}({:.1}h)", t.task_name, t.daily_hours)).collect();
+            ScheduleViolation {
+                kind: "overload".to_string(),
+                severity: severity.to_string(),
+                task_id: None,
+                task_name: None,
+                developer_id: Some(developer.id),
+                developer_name: Some(developer.name.clone()),
+                date: Some(d.date.clone()),
+                message: format!("已安排 {:.1}h，超出可用 {:.1}h：{}", d.allocated_hours, d.max_hours, booked.join("、")),
+                suggestion: "顺延或改派低优先级任务，或延长相关任务的结束日期".to_string(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute_batch(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2026-10-01', 1, 0, '国庆节', 2026);
+             INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2027-01-01', 1, 0, '元旦', 2027);
+             INSERT INTO developers (name) VALUES ('张三');
+             INSERT INTO sprints (name, start_date, end_date) VALUES ('S1', '2026-11-02', '2026-11-13');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn kinds(violations: &[ScheduleViolation]) -> Vec<(&str, &str, Option<i64>)> {
+        violations.iter().map(|v| (v.kind.as_str(), v.severity.as_str(), v.task_id)).collect()
+    }
+
+    #[test]
+    fn reports_each_kind_of_violation() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            "INSERT INTO tasks (id, name, owner_id, sprint_id, planned_start, planned_end, planned_hours) VALUES
+                (1, 'overlap-a', 1, 1, '2026-11-02', '2026-11-02', 8),
+                (2, 'overlap-b', 1, 1, '2026-11-02', '2026-11-02', 3),
+                (3, 'weekend', 1, 1, '2026-11-07', '2026-11-08', 4),
+                (4, 'too-big', 1, 1, '2026-11-04', '2026-11-05', 24),
+                (5, 'reversed', 1, 1, '2026-11-10', '2026-11-09', 4),
+                (6, 'late', 1, 1, '2026-11-12', '2026-11-16', 8),
+                (7, 'done', 1, 1, '2026-11-07', '2026-11-07', 4);
+             UPDATE tasks SET status = '已完成' WHERE id = 7;
+             INSERT INTO task_dependencies (predecessor_id, successor_id) VALUES (6, 4);",
+        )
+        .unwrap();
+
+        let violations = scan_violations(&conn, "2026-11-01", "2026-11-30", None).unwrap();
+        let found = kinds(&violations);
+        assert!(found.contains(&("overload", "warning", None)));
+        assert!(found.contains(&("non_workday", "warning", Some(3))));
+        assert!(found.contains(&("insufficient_capacity", "error", Some(4))));
+        assert!(found.contains(&("invalid_range", "error", Some(5))));
+        assert!(found.contains(&("outside_sprint", "warning", Some(6))));
+        assert!(found.contains(&("dependency", "error", Some(4))));
+        assert!(found.iter().all(|(_, _, id)| *id != Some(7)));
+        // Errors are listed before warnings.
+        let first_warning = violations.iter().position(|v| v.severity == "warning").unwrap();
+        assert!(violations[first_warning..].iter().all(|v| v.severity == "warning"));
+
+        let too_big = violations.iter().find(|v| v.kind == "insufficient_capacity").unwrap();
+        assert!(too_big.suggestion.contains("2026-11-06"));
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use crate::models::sprint::Sprint;
+
+/// Longest span assumed for a task whose end date is written without a year. An end
+/// a little before the start stays in the start's year, to be flagged as a typo,
+/// rather than becoming a task about a year long.
+const MAX_YEARLESS_SPAN_DAYS: i64 = 90;
+
+/// What a date written without a year ("3月1日", "03-01") belongs to: dates within
+/// `window` when one is known, otherwise `year`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YearHint {
+    pub year: i32,
+    pub window: Option<(NaiveDate, NaiveDate)>,
+}
+
+impl YearHint {
+    /// The current year.
+    pub fn current() -> Self {
+        YearHint { year: Local::now().year(), window: None }
+    }
+
+    /// The dates of a sprint, or the current year for a sprint without dates.
+    pub fn for_sprint(sprint: Option<&Sprint>) -> Self {
+        let parse = |d: &Option<String>| d.as_deref().and_then(parse_ymd);
+        match sprint.map(|s| (parse(&s.start_date), parse(&s.end_date))) {
+            Some((Some(start), Some(end))) if start <= end => YearHint { year: start.year(), window: Some((start, end)) },
+            Some((Some(date), _)) | Some((None, Some(date))) => YearHint { year: date.year(), window: None },
+            _ => YearHint::current(),
+        }
+    }
+
+    /// The months from `start` on, for an end date written without a year: "1月5日" after
+    /// a start of 2026-12-28 is in 2027, "3月1日" after 2026-03-05 stays in 2026.
+    pub fn after(start: NaiveDate) -> Self {
+        YearHint { year: start.year(), window: Some((start, start + Duration::days(MAX_YEARLESS_SPAN_DAYS))) }
+    }
+
+    /// The year of a month and day: inside the window if any candidate year is, else
+    /// the one nearest to it; without a window, `year`.
+    fn resolve(&self, month: u32, day: u32) -> Option<NaiveDate> {
+        let Some((start, end)) = self.window else {
+            return NaiveDate::from_ymd_opt(self.year, month, day);
+        };
+        let distance = |d: &NaiveDate| {
+            if *d < start { (start - *d).num_days() } else if *d > end { (*d - end).num_days() } else { 0 }
+        };
+        [start.year(), start.year() + 1, start.year() - 1]
+            .into_iter()
+            .filter_map(|y| NaiveDate::from_ymd_opt(y, month, day))
+            .min_by_key(distance)
+    }
+}
+
+/// Parse a stored `YYYY-MM-DD` date.
+pub fn parse_ymd(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+/// Format a date as the stored `YYYY-MM-DD`.
+pub fn fmt_ymd(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// A date range given as `YYYY-MM-DD` strings, end not before start.
+pub fn parse_date_range(start_date: &str, end_date: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = parse_ymd(start_date).ok_or_else(|| format!("日期格式无效: {}", start_date))?;
+    let end = parse_ymd(end_date).ok_or_else(|| format!("日期格式无效: {}", end_date))?;
+    if end < start {
+        return Err("结束日期不能早于开始日期".to_string());
+    }
+    Ok((start, end))
+}
+
+/// Parse a date as people write it in plans: "2026-03-01", "2026/3/1", "2026.03.01",
+/// "20260301", "2026年3月1日", or without a year as "3/1", "03-01", "3.1" or "3月1日",
+/// the year then coming from `hint`. A time after the date ("2026-03-01 09:00:00",
+/// "2026-03-01T09:00") and a note in brackets ("3月2日(周一)") are ignored.
+pub fn parse_date(raw: &str, hint: &YearHint) -> Option<NaiveDate> {
+    let text = raw.trim();
+    let text = text.split(['(', '（']).next().unwrap_or(text).trim();
+    let text = text.split([' ', 'T']).next().unwrap_or(text);
+    if text.is_empty() {
+        return None;
+    }
+    if text.len() == 8 && text.bytes().all(|b| b.is_ascii_digit()) {
+        return NaiveDate::parse_from_str(text, "%Y%m%d").ok();
+    }
+    if !text.chars().all(|c| c.is_ascii_digit() || "-/.年月日".contains(c)) {
+        return None;
+    }
+
+    let parts: Vec<&str> = text.split(|c: char| !c.is_ascii_digit()).filter(|p| !p.is_empty()).collect();
+    let number = |p: &str| p.parse::<u32>().ok();
+    match parts.as_slice() {
+        [y, m, d] if y.len() == 4 && m.len() <= 2 && d.len() <= 2 => {
+            NaiveDate::from_ymd_opt(y.parse().ok()?, number(m)?, number(d)?)
+        }
+        [m, d] if m.len() <= 2 && d.len() <= 2 => hint.resolve(number(m)?, number(d)?),
+        _ => None,
+    }
+}
+
+/// `parse_date`, formatted as the `YYYY-MM-DD` the rest of the app stores.
+pub fn normalize_date(raw: &str, hint: &YearHint) -> Option<String> {
+    parse_date(raw, hint).map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Normalize the start and end of a task in place, an end without a year following
+/// the start. Returns the values that are not dates, which are cleared.
+pub fn normalize_date_range(
+    start: &mut Option<String>,
+    end: &mut Option<String>,
+    hint: &YearHint,
+) -> Vec<(&'static str, String)> {
+    let mut unparsed = Vec::new();
+    let parsed_start = start.take().and_then(|raw| match parse_date(&raw, hint) {
+        Some(date) => Some(date),
+        None => {
+            unparsed.push(("planned_start", raw));
+            None
+        }
+    });
+    let end_hint = parsed_start.map(YearHint::after).unwrap_or(*hint);
+    *start = parsed_start.map(|d| d.format("%Y-%m-%d").to_string());
+    *end = end.take().and_then(|raw| match normalize_date(&raw, &end_hint) {
+        Some(date) => Some(date),
+        None => {
+            unparsed.push(("planned_end", raw));
+            None
+        }
+    });
+    unparsed
+}
+
+/// `normalize_date_range` for dates typed, pasted or returned by a model for one
+/// task, where a value that is not a date is an error naming the task.
+pub fn require_task_dates(
+    task: &str,
+    start: &mut Option<String>,
+    end: &mut Option<String>,
+    hint: &YearHint,
+) -> Result<(), String> {
+    match normalize_date_range(start, end, hint).first() {
+        Some((field, raw)) => {
+            let label = if *field == "planned_start" { "计划开始" } else { "计划结束" };
+            Err(format!("任务「{}」的{}「{}」不是有效日期", task, label, raw))
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(s: &str) -> NaiveDate {
+        parse_ymd(s).unwrap()
+    }
+
+    #[test]
+    fn written_dates_are_normalized() {
+        let hint = YearHint { year: 2026, window: None };
+        let normalize = |raw: &str| normalize_date(raw, &hint);
+        for raw in [
+            "2026-03-01", "2026/3/1", "2026.03.01", "20260301", "2026年3月1日", "3月1日",
+            "03-01", "3/1", "3.1", "2026-03-01 00:00:00", "2026-03-01T08:30:00", "3月1日(周日)",
+        ] {
+            assert_eq!(normalize(raw).as_deref(), Some("2026-03-01"), "{}", raw);
+        }
+        for raw in ["", "下周一", "2026-02-30", "13/1", "Q1", "1", "26/3/1", "2026-03"] {
+            assert_eq!(normalize(raw), None, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn year_comes_from_the_sprint_or_the_start_date() {
+        let sprint = Sprint {
+            id: 1,
+            name: "S12".to_string(),
+            project_id: None,
+            start_date: Some("2026-12-21".to_string()),
+            end_date: Some("2027-01-08".to_string()),
+            phase: None,
+            task_count: 0,
+        };
+        let hint = YearHint::for_sprint(Some(&sprint));
+        assert_eq!(parse_date("12月28日", &hint), Some(ymd("2026-12-28")));
+        assert_eq!(parse_date("1/5", &hint), Some(ymd("2027-01-05")));
+        assert_eq!(YearHint::for_sprint(None).year, Local::now().year());
+
+        let (mut start, mut end) = (Some("12-30".to_string()), Some("1月4日".to_string()));
+        let unparsed = normalize_date_range(&mut start, &mut end, &YearHint { year: 2026, window: None });
+        assert!(unparsed.is_empty());
+        assert_eq!((start.as_deref(), end.as_deref()), (Some("2026-12-30"), Some("2027-01-04")));
+
+        let (mut start, mut end) = (Some("2026-03-05".to_string()), Some("3月1日".to_string()));
+        normalize_date_range(&mut start, &mut end, &hint);
+        assert_eq!(end.as_deref(), Some("2026-03-01"));
+
+        let (mut start, mut end) = (Some("待定".to_string()), None);
+        let unparsed = normalize_date_range(&mut start, &mut end, &hint);
+        assert_eq!(unparsed, vec![("planned_start", "待定".to_string())]);
+        assert_eq!(start, None);
+    }
+}
@@ -1,6 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use rusqlite::Connection;
-use crate::db::developer_repo;
-use crate::models::developer::{Developer, CreateDeveloperDto, UpdateDeveloperDto};
+use chrono::{Duration, Local};
+use crate::db::{developer_repo, task_repo};
+use crate::models::developer::{
+    Developer, CreateDeveloperDto, UpdateDeveloperDto, OffboardDeveloperDto, OffboardReport, ReassignedTask,
+    SkillLevel, MAX_SKILL_LEVEL,
+};
+use crate::models::task::{Task, TaskFilter};
+use crate::services::{assignment_service, capacity_service, holiday_service, settings_service};
 
 pub fn list_developers(conn: &Connection) -> Result<Vec<Developer>, String> {
     developer_repo::get_all(conn).map_err(|e| e.to_string())
@@ -10,18 +17,297 @@ pub fn get_developer(conn: &Connection, id: i64) -> Result<Option<Developer>, St
     developer_repo::get_by_id(conn, id).map_err(|e| e.to_string())
 }
 
+/// Skill levels run from 1 to `MAX_SKILL_LEVEL`.
+fn validate_skill_levels(levels: Option<&Vec<SkillLevel>>) -> Result<(), String> {
+    for skill in levels.into_iter().flatten() {
+        if skill.level < 1 || skill.level > MAX_SKILL_LEVEL {
+            return Err(format!("技能「{}」的熟练度 {} 超出范围（1-{}）", skill.name, skill.level, MAX_SKILL_LEVEL));
+        }
+    }
+    Ok(())
+}
+
 pub fn create_developer(conn: &Connection, dto: &CreateDeveloperDto) -> Result<i64, String> {
+    validate_skill_levels(dto.skill_levels.as_ref())?;
     developer_repo::create(conn, dto).map_err(|e| e.to_string())
 }
 
 pub fn update_developer(conn: &Connection, dto: &UpdateDeveloperDto) -> Result<(), String> {
+    validate_skill_levels(dto.skill_levels.as_ref())?;
     developer_repo::update(conn, dto).map_err(|e| e.to_string())
 }
 
+/// Delete a developer that nothing refers to. Developers with tasks or standup
+/// history must be offboarded instead so their records stay attributed.
 pub fn delete_developer(conn: &Connection, id: i64) -> Result<(), String> {
+    let refs = developer_repo::count_references(conn, id).map_err(|e| e.to_string())?;
+    if refs > 0 {
+        return Err(format!(
+            "该成员仍有 {} 条任务或站会记录，无法删除，请使用离职交接停用该成员",
+            refs
+        ));
+    }
     developer_repo::delete(conn, id).map_err(|e| e.to_string())
 }
 
+/// Deactivate a developer and hand their open work to others.
+///
+/// Each open task they own or co-own goes to the explicit assignment for that task,
+/// else `default_assignee_id`, else the assignment scorer's best active candidate.
+/// Completed and cancelled tasks, and standup entries, keep pointing at the developer.
+/// Everything runs in one transaction.
+pub fn offboard_developer(conn: &Connection, dto: &OffboardDeveloperDto) -> Result<OffboardReport, String> {
+    let developer = developer_repo::get_by_id(conn, dto.developer_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Developer not found".to_string())?;
+
+    let candidates: Vec<Developer> = developer_repo::get_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|d| d.is_active && d.id != developer.id)
+        .collect();
+    let candidate_names: HashMap<i64, String> =
+        candidates.iter().map(|d| (d.id, d.name.clone())).collect();
+
+    let explicit: HashMap<i64, i64> = dto
+        .assignments
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|a| (a.task_id, a.developer_id))
+        .collect();
+    for target in explicit.values().chain(dto.default_assignee_id.iter()) {
+        if !candidate_names.contains_key(target) {
+            return Err(format!("交接目标成员 {} 不存在或已停用", target));
+        }
+    }
+
+    let all_tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let is_co_owner = |t: &Task, dev_id: i64| {
+        t.co_owners
+            .as_ref()
+            .is_some_and(|c| c.iter().any(|o| o.developer_id == dev_id))
+    };
+    let affected: Vec<&Task> = all_tasks
+        .iter()
+        .filter(|t| t.is_open())
+        .filter(|t| t.owner_id == Some(developer.id) || is_co_owner(t, developer.id))
+        .collect();
+    let retained_history_tasks = all_tasks
+        .iter()
+        .filter(|t| !t.is_open())
+        .filter(|t| t.owner_id == Some(developer.id) || is_co_owner(t, developer.id))
+        .count();
+    let affected_ids: HashSet<i64> = affected.iter().map(|t| t.id).collect();
+    let mut foreign: Vec<i64> = explicit.keys().filter(|id| !affected_ids.contains(id)).copied().collect();
+    if !foreign.is_empty() {
+        foreign.sort_unstable();
+        let ids: Vec<String> = foreign.iter().map(|id| id.to_string()).collect();
+        return Err(format!("任务 {} 不是「{}」负责的未完成任务，不能在交接中指派", ids.join("、"), developer.name));
+    }
+
+    let today = Local::now().date_naive();
+    holiday_service::ensure_holidays_cached(conn, &today, &(today + Duration::days(60)));
+    let profiles = capacity_service::load_profiles(
+        conn,
+        &candidates,
+        settings_service::default_hours_per_day(conn),
+    )?;
+    let calendars = holiday_service::DeveloperCalendars::load(conn)?;
+    let pinned = assignment_service::load_pinned_hours(conn)?;
+    let mut load_map = assignment_service::build_existing_daily_load(&calendars, &all_tasks, &affected_ids, &pinned);
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let mut moved = Vec::new();
+    for task in affected {
+        let owned = task.owner_id == Some(developer.id);
+        // Co-owners can't also be picked as owner, and vice versa.
+        let taken: HashSet<i64> = task
+            .co_owners
+            .iter()
+            .flatten()
+            .map(|c| c.developer_id)
+            .chain(task.owner_id)
+            .filter(|id| *id != developer.id)
+            .collect();
+
+        let (target, reason) = match explicit.get(&task.id).or(dto.default_assignee_id.as_ref()) {
+            Some(id) => (Some(*id), "指定交接".to_string()),
+            None => {
+                let pool: Vec<Developer> = candidates
+                    .iter()
+                    .filter(|d| !taken.contains(&d.id))
+                    .cloned()
+                    .collect();
+                let ranked =
+                    assignment_service::rank_candidates(&calendars, task, &pool, &profiles, &load_map, today);
+                match ranked.first() {
+                    Some(best) => (Some(best.developer_id), format!("评分推荐: {}", best.reasons.join("，"))),
+                    None => (None, "无可用成员接手".to_string()),
+                }
+            }
+        };
+
+        let result = if owned {
+            // A co-owner promoted to owner drops their co-ownership.
+            task_repo::set_owner(conn, task.id, target).and_then(|_| match target {
+                Some(id) if taken.contains(&id) => task_repo::remove_co_owner(conn, task.id, id),
+                _ => Ok(()),
+            })
+        } else {
+            task_repo::remove_co_owner(conn, task.id, developer.id).and_then(|_| match target {
+                Some(id) if !taken.contains(&id) => task_repo::add_co_owner(conn, task.id, id),
+                _ => Ok(()),
+            })
+        };
+        if let Err(e) = result {
+            conn.execute_batch("ROLLBACK").ok();
+            return Err(format!("Failed to reassign task {}: {}", task.id, e));
+        }
+
+        if let (true, Some(id)) = (owned, target) {
+            assignment_service::book_task(calendars.of(id), &mut load_map, id, task, pinned.get(&task.id));
+        }
+        moved.push(ReassignedTask {
+            task_id: task.id,
+            task_name: task.name.clone(),
+            role: if owned { "owner" } else { "co_owner" }.to_string(),
+            to_developer_id: target,
+            to_developer_name: target.and_then(|id| candidate_names.get(&id).cloned()),
+            reason,
+        });
+    }
+
+    if let Err(e) = developer_repo::set_active(conn, developer.id, false) {
+        conn.execute_batch("ROLLBACK").ok();
+        return Err(e.to_string());
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+
+    Ok(OffboardReport {
+        developer_id: developer.id,
+        developer_name: developer.name,
+        moved,
+        retained_history_tasks,
+    })
+}
+
 pub fn find_or_create_by_name(conn: &Connection, name: &str, color_index: usize) -> Result<i64, String> {
     developer_repo::find_or_create_by_name(conn, name, color_index).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::models::developer::TaskReassignment;
+    use chrono::Datelike;
+    use rusqlite::params;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        // Seed the holiday cache so ensure_holidays_cached doesn't go to the network.
+        let today = Local::now().date_naive();
+        for year in [today.year(), (today + Duration::days(60)).year()] {
+            conn.execute(
+                "INSERT OR REPLACE INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES (?1, 0, 1, 'seed', ?2)",
+                params![format!("{}-01-01", year), year],
+            )
+            .unwrap();
+        }
+        for name in ["Leaver", "Alice", "Bob"] {
+            conn.execute("INSERT INTO developers (name) VALUES (?1)", params![name]).unwrap();
+        }
+        // 1 = open owned, 2 = completed owned, 3 = open co-owned (owner Bob)
+        conn.execute_batch(
+            "INSERT INTO tasks (id, name, owner_id, status) VALUES (1, 'open', 1, '进行中');
+             INSERT INTO tasks (id, name, owner_id, status) VALUES (2, 'done', 1, '已完成');
+             INSERT INTO tasks (id, name, owner_id, status) VALUES (3, 'shared', 3, '待开始');
+             INSERT INTO task_co_owners (task_id, developer_id) VALUES (3, 1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn offboard_moves_open_work_and_keeps_history() {
+        let conn = setup_conn();
+        let report = offboard_developer(
+            &conn,
+            &OffboardDeveloperDto {
+                developer_id: 1,
+                assignments: Some(vec![TaskReassignment { task_id: 1, developer_id: 2 }]),
+                default_assignee_id: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.moved.len(), 2);
+        assert_eq!(report.retained_history_tasks, 1);
+
+        let open = task_repo::get_by_id(&conn, 1).unwrap().unwrap();
+        assert_eq!(open.owner_id, Some(2));
+        let done = task_repo::get_by_id(&conn, 2).unwrap().unwrap();
+        assert_eq!(done.owner_id, Some(1));
+        // Bob owns task 3, so the scorer can only hand the co-ownership to Alice.
+        let shared = task_repo::get_by_id(&conn, 3).unwrap().unwrap();
+        let co: Vec<i64> = shared.co_owners.unwrap().iter().map(|c| c.developer_id).collect();
+        assert_eq!(co, vec![2]);
+
+        assert!(!developer_repo::get_by_id(&conn, 1).unwrap().unwrap().is_active);
+        assert!(offboard_developer(
+            &conn,
+            &OffboardDeveloperDto {
+                developer_id: 2,
+                assignments: Some(vec![TaskReassignment { task_id: 2, developer_id: 3 }]),
+                default_assignee_id: None,
+            },
+        )
+        .unwrap_err()
+        .contains("任务 2"));
+        assert!(delete_developer(&conn, 1).is_err());
+    }
+
+    #[test]
+    fn calendar_can_be_cleared() {
+        let conn = setup_conn();
+        conn.execute("INSERT INTO region_calendars (id, name, region) VALUES (7, '美国', 'US')", []).unwrap();
+        let dto = |calendar_id, clear_calendar| UpdateDeveloperDto {
+            id: 2,
+            name: None,
+            roles: None,
+            skills: None,
+            skill_levels: None,
+            max_hours_per_day: None,
+            avatar_color: None,
+            is_active: None,
+            calendar_id,
+            clear_calendar,
+        };
+        update_developer(&conn, &dto(Some(7), false)).unwrap();
+        update_developer(&conn, &dto(None, false)).unwrap();
+        assert_eq!(developer_repo::get_by_id(&conn, 2).unwrap().unwrap().calendar_id, Some(7));
+        update_developer(&conn, &dto(None, true)).unwrap();
+        assert_eq!(developer_repo::get_by_id(&conn, 2).unwrap().unwrap().calendar_id, None);
+    }
+
+    #[test]
+    fn skill_levels_must_be_in_range() {
+        let conn = setup_conn();
+        let dto = |level| CreateDeveloperDto {
+            name: format!("dev{}", level),
+            roles: None,
+            skills: None,
+            skill_levels: Some(vec![SkillLevel { name: "Rust".to_string(), level }]),
+            max_hours_per_day: None,
+            avatar_color: None,
+            calendar_id: None,
+        };
+        assert!(create_developer(&conn, &dto(0)).is_err());
+        assert!(create_developer(&conn, &dto(6)).is_err());
+        assert!(create_developer(&conn, &dto(5)).is_ok());
+    }
+}
@@ -1,170 +1,625 @@
 use rusqlite::Connection;
 use chrono::{NaiveDate, Datelike};
-use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::rc::Rc;
+use crate::db::calendar_repo;
+use crate::holiday::bundled_provider::BundledHolidayProvider;
+use crate::holiday::file_provider::FileHolidayProvider;
+use crate::holiday::nager_provider::NagerHolidayProvider;
+use crate::holiday::provider::{HolidayProvider, HolidayRecord};
+use crate::holiday::timor_provider::TimorHolidayProvider;
+use crate::holiday::work_calendar::WorkCalendar;
+use crate::models::calendar::{
+    CalendarOverride, CreateCalendarOverrideDto, CreateRegionCalendarDto, HolidayYearSummary, OvertimeConfig,
+    RegionCalendar, UpdateCalendarOverrideDto, UpdateRegionCalendarDto,
+};
+use crate::models::developer::Developer;
 
-#[derive(Debug, Deserialize)]
-struct HolidayApiResponse {
-    code: i32,
-    holiday: Option<HashMap<String, HolidayEntry>>,
+/// Region of the mainland China arrangement (timor.tech / bundled data).
+pub const DEFAULT_REGION: &str = "CN";
+
+/// Replace the cached arrangement for `region`/`year` with `records`, tagging each row with `source`.
+pub fn store_year(
+    conn: &Connection,
+    region: &str,
+    year: i32,
+    records: &[HolidayRecord],
+    source: &str,
+) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM holiday_cache WHERE region = ?1 AND year = ?2",
+        rusqlite::params![region, year],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for entry in records {
+        // is_holiday == true means it's a day off (holiday)
+        // is_holiday == false means it's a makeup workday (补班, weekend but work)
+        let is_holiday = if entry.is_holiday { 1 } else { 0 };
+        let is_workday = if entry.is_holiday { 0 } else { 1 };
+
+        conn.execute(
+            "INSERT OR REPLACE INTO holiday_cache (region, date, is_holiday, is_workday, name, year, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![region, entry.date, is_holiday, is_workday, entry.name, year, source],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(records.len())
 }
 
-#[derive(Debug, Deserialize)]
-struct HolidayEntry {
-    holiday: bool,   // true = day off, false = makeup workday
-    name: String,
-    date: String,
+/// Fetch `year` from one provider and cache it under `region`. Providers with no data for
+/// the year are an error so callers can fall through to the next source.
+pub fn sync_from_provider(
+    conn: &Connection,
+    region: &str,
+    year: i32,
+    provider: &dyn HolidayProvider,
+) -> Result<usize, String> {
+    let records = provider.fetch_year(year)?;
+    if records.is_empty() {
+        return Err(format!("{} 没有 {} 年的节假日数据", provider.source(), year));
+    }
+    store_year(conn, region, year, &records, &provider.source())
 }
 
-/// Sync holiday data for a given year from timor.tech API and cache in DB.
-pub fn sync_holidays_for_year(conn: &Connection, year: i32) -> Result<usize, String> {
-    let url = format!("https://timor.tech/api/holiday/year/{}", year);
+/// Sync holiday data for a region and year. `CN` tries the timor.tech API first, then the
+/// bundled dataset when offline; other regions use the Nager.Date API.
+pub fn sync_holidays_for_year(conn: &Connection, region: &str, year: i32) -> Result<usize, String> {
+    if !region.eq_ignore_ascii_case(DEFAULT_REGION) {
+        let provider = NagerHolidayProvider { country_code: region.to_string() };
+        return sync_from_provider(conn, region, year, &provider);
+    }
 
-    let resp: HolidayApiResponse = ureq::get(&url)
-        .call()
-        .map_err(|e| format!("HTTP request failed: {}", e))?
-        .into_json()
-        .map_err(|e| format!("JSON parse failed: {}", e))?;
+    let providers: [&dyn HolidayProvider; 2] = [&TimorHolidayProvider, &BundledHolidayProvider];
+    let mut errors = Vec::new();
+    for provider in providers {
+        match sync_from_provider(conn, region, year, provider) {
+            Ok(n) => return Ok(n),
+            Err(e) => {
+                log::warn!("Holiday provider {} failed for {}: {}", provider.source(), year, e);
+                errors.push(e);
+            }
+        }
+    }
+    Err(errors.join("; "))
+}
 
-    if resp.code != 0 {
-        return Err(format!("API returned error code: {}", resp.code));
+/// Sync a year from a named source: `timor`, `bundled`, `nager`, or `None` for the
+/// region's default chain.
+pub fn sync_holidays_with_source(
+    conn: &Connection,
+    region: &str,
+    year: i32,
+    source: Option<&str>,
+) -> Result<usize, String> {
+    match source {
+        None | Some("auto") => sync_holidays_for_year(conn, region, year),
+        Some("timor") => sync_from_provider(conn, region, year, &TimorHolidayProvider),
+        Some("bundled") => sync_from_provider(conn, region, year, &BundledHolidayProvider),
+        Some("nager") => {
+            let provider = NagerHolidayProvider { country_code: region.to_string() };
+            sync_from_provider(conn, region, year, &provider)
+        }
+        Some(other) => Err(format!("未知的节假日数据源: {}", other)),
     }
+}
 
-    let holidays = resp.holiday.unwrap_or_default();
+/// Import every year found in a local JSON/CSV/ICS file into `region`, replacing those years.
+pub fn import_holidays_from_file(
+    conn: &Connection,
+    region: &str,
+    path: &str,
+) -> Result<Vec<HolidayYearSummary>, String> {
+    let provider = FileHolidayProvider::load(Path::new(path))?;
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    for year in provider.years() {
+        if let Err(e) = sync_from_provider(conn, region, year, &provider) {
+            conn.execute_batch("ROLLBACK").ok();
+            return Err(e);
+        }
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+
+    let years = provider.years();
+    Ok(list_cached_years(conn)?
+        .into_iter()
+        .filter(|s| s.region == region && years.contains(&s.year))
+        .collect())
+}
 
-    // Clear existing cache for this year
-    conn.execute("DELETE FROM holiday_cache WHERE year = ?1", rusqlite::params![year])
+/// Cached years per region with where their data came from.
+pub fn list_cached_years(conn: &Connection) -> Result<Vec<HolidayYearSummary>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT region, year, COALESCE(source, 'unknown'), COUNT(*), SUM(is_holiday), SUM(is_workday) \
+             FROM holiday_cache GROUP BY region, year, source ORDER BY region, year",
+        )
         .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(HolidayYearSummary {
+                region: row.get(0)?,
+                year: row.get(1)?,
+                source: row.get(2)?,
+                entries: row.get(3)?,
+                holidays: row.get(4)?,
+                makeup_workdays: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
 
-    let mut count = 0;
-    for (_key, entry) in &holidays {
-        // entry.holiday == true means it's a day off (holiday)
-        // entry.holiday == false means it's a makeup workday (补班, weekend but work)
-        let is_holiday = if entry.holiday { 1 } else { 0 };
-        let is_workday = if entry.holiday { 0 } else { 1 };
+/// Cached holidays and makeup workdays of `region` within `[start_date, end_date]`, by date.
+pub fn list_holidays(conn: &Connection, region: &str, start_date: &str, end_date: &str) -> Result<Vec<HolidayRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT date, is_holiday, COALESCE(name, '') FROM holiday_cache \
+             WHERE region = ?1 AND date >= ?2 AND date <= ?3 ORDER BY date",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![region, start_date, end_date], |row| {
+            Ok(HolidayRecord {
+                date: row.get(0)?,
+                is_holiday: row.get::<_, i32>(1)? != 0,
+                name: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
 
-        conn.execute(
-            "INSERT OR REPLACE INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![entry.date, is_holiday, is_workday, entry.name, year],
-        ).map_err(|e| e.to_string())?;
-        count += 1;
+/// The calendar used by developers without one of their own.
+pub fn default_calendar(conn: &Connection) -> RegionCalendar {
+    calendar_repo::get_default(conn).ok().flatten().unwrap_or_default()
+}
+
+/// The developer's assigned calendar, or the default calendar.
+pub fn calendar_for_developer(conn: &Connection, developer: &Developer) -> RegionCalendar {
+    developer
+        .calendar_id
+        .and_then(|id| calendar_repo::get_by_id(conn, id).ok().flatten())
+        .unwrap_or_else(|| default_calendar(conn))
+}
+
+pub fn list_calendars(conn: &Connection) -> Result<Vec<RegionCalendar>, String> {
+    calendar_repo::get_all(conn).map_err(|e| e.to_string())
+}
+
+fn validate_calendar(name: Option<&str>, region: Option<&str>, weekend_days: Option<&[u32]>) -> Result<(), String> {
+    if name.is_some_and(|n| n.trim().is_empty()) {
+        return Err("日历名称不能为空".to_string());
+    }
+    if region.is_some_and(|r| r.trim().is_empty()) {
+        return Err("日历地区不能为空".to_string());
     }
+    if let Some(days) = weekend_days {
+        if days.iter().any(|d| *d > 6) {
+            return Err("周末必须在 0(周一) 到 6(周日) 之间".to_string());
+        }
+        if days.len() >= 7 {
+            return Err("一周至少需要一个工作日".to_string());
+        }
+    }
+    Ok(())
+}
 
-    Ok(count)
+pub fn create_calendar(conn: &Connection, dto: &CreateRegionCalendarDto) -> Result<i64, String> {
+    validate_calendar(Some(&dto.name), Some(&dto.region), dto.weekend_days.as_deref())?;
+    let dto = CreateRegionCalendarDto {
+        region: dto.region.trim().to_uppercase(),
+        ..dto.clone()
+    };
+    calendar_repo::create(conn, &dto).map_err(|e| e.to_string())
 }
 
-/// Read overtime configuration from app_settings.
-/// Returns (weekend_mode, custom_dates_set).
-fn read_overtime_config(conn: &Connection) -> (String, HashSet<String>) {
-    let json_str: Option<String> = conn
-        .query_row(
-            "SELECT value FROM app_settings WHERE key = 'schedule.overtime_days'",
-            [],
-            |row| row.get(0),
-        )
-        .ok();
-
-    match json_str {
-        Some(s) => {
-            #[derive(Deserialize)]
-            struct OvertimeCfg {
-                weekend: Option<String>,
-                custom_dates: Option<Vec<String>>,
-            }
-            if let Ok(cfg) = serde_json::from_str::<OvertimeCfg>(&s) {
-                let weekend = cfg.weekend.unwrap_or_else(|| "none".to_string());
-                let dates: HashSet<String> = cfg
-                    .custom_dates
-                    .unwrap_or_default()
-                    .into_iter()
-                    .collect();
-                (weekend, dates)
-            } else {
-                ("none".to_string(), HashSet::new())
+pub fn update_calendar(conn: &Connection, dto: &UpdateRegionCalendarDto) -> Result<(), String> {
+    validate_calendar(dto.name.as_deref(), dto.region.as_deref(), dto.weekend_days.as_deref())?;
+    if dto.is_default == Some(false) {
+        return Err("请将其他日历设为默认，而不是取消默认".to_string());
+    }
+    let dto = UpdateRegionCalendarDto {
+        region: dto.region.as_ref().map(|r| r.trim().to_uppercase()),
+        ..dto.clone()
+    };
+    calendar_repo::update(conn, &dto).map_err(|e| e.to_string())
+}
+
+/// Delete a calendar; its developers move back to the default calendar.
+pub fn delete_calendar(conn: &Connection, id: i64) -> Result<(), String> {
+    let calendar = calendar_repo::get_by_id(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "日历不存在".to_string())?;
+    if calendar.is_default {
+        return Err("默认日历不能删除".to_string());
+    }
+    calendar_repo::delete(conn, id).map_err(|e| e.to_string())
+}
+
+pub fn list_overrides(
+    conn: &Connection,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<Vec<CalendarOverride>, String> {
+    calendar_repo::get_overrides(conn, start_date, end_date).map_err(|e| e.to_string())
+}
+
+fn check_override_date(conn: &Connection, calendar_id: Option<i64>, date: &str, exclude_id: Option<i64>) -> Result<(), String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("日期格式无效: {}", date))?;
+    let existing = calendar_repo::find_override(conn, calendar_id, date, exclude_id).map_err(|e| e.to_string())?;
+    if existing.is_some() {
+        return Err(format!("{} 已有日历调整，请直接修改", date));
+    }
+    Ok(())
+}
+
+/// Add a manual day off / workday. Overrides live outside `holiday_cache`, so resyncing
+/// holidays never removes them.
+pub fn create_override(conn: &Connection, dto: &CreateCalendarOverrideDto) -> Result<i64, String> {
+    if let Some(id) = dto.calendar_id {
+        calendar_repo::get_by_id(conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "日历不存在".to_string())?;
+    }
+    check_override_date(conn, dto.calendar_id, &dto.date, None)?;
+    calendar_repo::create_override(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn update_override(conn: &Connection, dto: &UpdateCalendarOverrideDto) -> Result<(), String> {
+    let current = calendar_repo::get_override_by_id(conn, dto.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "日历调整不存在".to_string())?;
+    if let Some(ref date) = dto.date {
+        check_override_date(conn, current.calendar_id, date, Some(dto.id))?;
+    }
+    calendar_repo::update_override(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn delete_override(conn: &Connection, id: i64) -> Result<(), String> {
+    calendar_repo::delete_override(conn, id).map_err(|e| e.to_string())
+}
+
+/// Every developer's working-day calendar, built once for services that schedule many people.
+/// Developers sharing a calendar share one [`WorkCalendar`].
+#[derive(Debug, Clone, Default)]
+pub struct DeveloperCalendars {
+    default: Rc<WorkCalendar>,
+    by_developer: HashMap<i64, Rc<WorkCalendar>>,
+}
+
+impl DeveloperCalendars {
+    pub fn load(conn: &Connection) -> Result<Self, String> {
+        let global = read_overtime_config(conn);
+        let default_region = default_calendar(conn);
+        let region_by_dev: HashMap<i64, RegionCalendar> = calendar_repo::get_developer_calendars(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+        let overtime_by_dev: HashMap<i64, OvertimeConfig> = calendar_repo::get_all_developer_overtime(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+
+        // Base calendars per region calendar, and shared ones with the global overtime rules.
+        let mut bases: HashMap<i64, WorkCalendar> = HashMap::new();
+        let mut shared: HashMap<i64, Rc<WorkCalendar>> = HashMap::new();
+        let mut calendar_with = |region: &RegionCalendar, overtime: Option<&OvertimeConfig>| {
+            let base = bases
+                .entry(region.id)
+                .or_insert_with(|| base_work_calendar(conn, region))
+                .clone();
+            let with_overtime = |config: &OvertimeConfig| {
+                let mut work = base.clone();
+                apply_overtime(&mut work, config);
+                Rc::new(work)
+            };
+            match overtime {
+                Some(config) => with_overtime(config),
+                None => shared.entry(region.id).or_insert_with(|| with_overtime(&global)).clone(),
             }
+        };
+
+        let default = calendar_with(&default_region, None);
+        let mut by_developer = HashMap::new();
+        let developer_ids: HashSet<i64> = region_by_dev.keys().chain(overtime_by_dev.keys()).copied().collect();
+        for developer_id in developer_ids {
+            let region = region_by_dev.get(&developer_id).unwrap_or(&default_region);
+            by_developer.insert(developer_id, calendar_with(region, overtime_by_dev.get(&developer_id)));
+        }
+        Ok(Self { default, by_developer })
+    }
+
+    pub fn of(&self, developer_id: i64) -> &WorkCalendar {
+        self.by_developer.get(&developer_id).unwrap_or(&self.default)
+    }
+
+    /// The owner's calendar, or the default calendar for unassigned tasks.
+    pub fn for_owner(&self, owner_id: Option<i64>) -> &WorkCalendar {
+        match owner_id {
+            Some(id) => self.of(id),
+            None => &self.default,
         }
-        None => ("none".to_string(), HashSet::new()),
     }
 }
 
-/// Check if a date is a workday considering Chinese holidays and overtime config.
-/// Priority: holiday_cache > overtime config > default Mon-Fri
-/// 1. holiday_cache hit: is_holiday=1 → false, is_workday=1 → true
-/// 2. overtime config: weekend match or custom_dates match → true
-/// 3. Fallback: Mon-Fri = workday
-pub fn is_workday(conn: &Connection, date: &NaiveDate) -> bool {
-    let date_str = date.format("%Y-%m-%d").to_string();
+/// Read the global overtime configuration from app_settings.
+fn read_overtime_config(conn: &Connection) -> OvertimeConfig {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = 'schedule.overtime_days'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+fn apply_overtime(work: &mut WorkCalendar, config: &OvertimeConfig) {
+    if config.weekend == "saturday" || config.weekend == "both" {
+        work.add_overtime_weekday(5);
+    }
+    if config.weekend == "sunday" || config.weekend == "both" {
+        work.add_overtime_weekday(6);
+    }
+    for date in &config.custom_dates {
+        if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            work.add_overtime_date(date);
+        }
+    }
+}
 
-    // Check holiday cache first (highest priority)
-    let result: Option<(i32, i32)> = conn.query_row(
-        "SELECT is_holiday, is_workday FROM holiday_cache WHERE date = ?1",
-        rusqlite::params![date_str],
-        |row| Ok((row.get(0)?, row.get(1)?)),
-    ).ok();
+/// Holidays, makeup days and manual overrides for `calendar`, without overtime.
+fn base_work_calendar(conn: &Connection, calendar: &RegionCalendar) -> WorkCalendar {
+    let mut work = WorkCalendar::new(&calendar.weekend_days);
 
-    if let Some((is_holiday, is_makeup_workday)) = result {
+    let cached: Vec<(String, i32, i32)> = conn
+        .prepare("SELECT date, is_holiday, is_workday FROM holiday_cache WHERE region = ?1")
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![calendar.region], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect()
+        })
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load {} holiday cache: {}", calendar.region, e);
+            Vec::new()
+        });
+    for (date, is_holiday, is_makeup_workday) in cached {
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else { continue };
         if is_holiday == 1 {
-            return false; // It's a holiday, not a workday
+            work.set_special_day(date, false);
+        } else if is_makeup_workday == 1 {
+            work.set_special_day(date, true);
         }
-        if is_makeup_workday == 1 {
-            return true; // It's a makeup workday (e.g., Saturday but work)
+    }
+
+    // Manual overrides win over synced data; calendar-specific ones are applied last.
+    let overrides = calendar_repo::get_overrides_for_calendar(conn, calendar.id).unwrap_or_else(|e| {
+        log::warn!("Failed to load calendar overrides: {}", e);
+        Vec::new()
+    });
+    for o in overrides {
+        if let Ok(date) = NaiveDate::parse_from_str(&o.date, "%Y-%m-%d") {
+            work.set_special_day(date, o.is_workday);
         }
     }
+    work
+}
 
-    // Check overtime config (second priority)
-    let (weekend_mode, custom_dates) = read_overtime_config(conn);
-    let weekday = date.weekday().num_days_from_monday(); // 0=Mon .. 6=Sun
+/// Build the in-memory working-day calendar for `calendar` with the global overtime rules.
+/// Priority: calendar_overrides > region holiday_cache > overtime config > the calendar's weekend days
+/// 1. manual override (global, then calendar-specific)
+/// 2. holiday_cache hit: is_holiday=1 → day off, is_workday=1 → makeup workday
+/// 3. overtime config: weekend match or custom_dates match → workday
+/// 4. Fallback: any day outside `weekend_days` is a workday
+///
+/// Build once per request and reuse it; callers should run [`ensure_holidays_cached`] first.
+pub fn work_calendar(conn: &Connection, calendar: &RegionCalendar) -> WorkCalendar {
+    let mut work = base_work_calendar(conn, calendar);
+    apply_overtime(&mut work, &read_overtime_config(conn));
+    work
+}
 
-    // Check weekend overtime
-    if weekday == 5 && (weekend_mode == "saturday" || weekend_mode == "both") {
-        return true;
-    }
-    if weekday == 6 && (weekend_mode == "sunday" || weekend_mode == "both") {
-        return true;
-    }
+/// Working-day calendar for developers without a calendar of their own.
+pub fn default_work_calendar(conn: &Connection) -> WorkCalendar {
+    work_calendar(conn, &default_calendar(conn))
+}
+
+/// Working-day calendar for one developer: their region calendar plus their own overtime
+/// rules, or the global ones when they have none.
+pub fn work_calendar_for_developer(conn: &Connection, developer: &Developer) -> WorkCalendar {
+    let mut work = base_work_calendar(conn, &calendar_for_developer(conn, developer));
+    let overtime = calendar_repo::get_developer_overtime(conn, developer.id)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| read_overtime_config(conn));
+    apply_overtime(&mut work, &overtime);
+    work
+}
 
-    // Check custom overtime dates
-    if custom_dates.contains(&date_str) {
-        return true;
+/// A developer's own overtime rules; `None` means they follow the global setting.
+pub fn get_developer_overtime(conn: &Connection, developer_id: i64) -> Result<Option<OvertimeConfig>, String> {
+    calendar_repo::get_developer_overtime(conn, developer_id).map_err(|e| e.to_string())
+}
+
+/// Set a developer's own overtime rules, or clear them with `None` to follow the global setting.
+pub fn set_developer_overtime(
+    conn: &Connection,
+    developer_id: i64,
+    config: Option<&OvertimeConfig>,
+) -> Result<(), String> {
+    let Some(config) = config else {
+        return calendar_repo::clear_developer_overtime(conn, developer_id).map_err(|e| e.to_string());
+    };
+    if !matches!(config.weekend.as_str(), "none" | "saturday" | "sunday" | "both") {
+        return Err(format!("未知的周末加班设置: {}", config.weekend));
+    }
+    for date in &config.custom_dates {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("日期格式无效: {}", date))?;
     }
+    crate::db::developer_repo::get_by_id(conn, developer_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Developer not found".to_string())?;
+    calendar_repo::set_developer_overtime(conn, developer_id, config).map_err(|e| e.to_string())
+}
 
-    // Fallback: simple weekday check
-    weekday < 5
+/// Check if a single date is a workday on the default calendar.
+/// Loops should build a [`WorkCalendar`] once instead.
+pub fn is_workday(conn: &Connection, date: &NaiveDate) -> bool {
+    default_work_calendar(conn).is_workday(date)
 }
 
-/// Count working days between two dates (inclusive) considering holidays.
+/// Count working days between two dates (inclusive) on the default calendar.
 pub fn count_working_days(conn: &Connection, start: &NaiveDate, end: &NaiveDate) -> i64 {
-    let mut count = 0i64;
-    let mut current = *start;
-    while current <= *end {
-        if is_workday(conn, &current) {
-            count += 1;
-        }
-        current += chrono::Duration::days(1);
-    }
-    count.max(1)
+    default_work_calendar(conn).workdays_between(*start, *end).max(1)
 }
 
-/// Ensure holiday data is cached for the years covered by a date range.
-/// Auto-syncs any missing years.
+/// Ensure holiday data is cached for the years covered by a date range, for every region
+/// used by a calendar. Auto-syncs any missing years, falling back to the bundled dataset
+/// when offline.
 pub fn ensure_holidays_cached(conn: &Connection, start: &NaiveDate, end: &NaiveDate) {
-    let start_year = start.year();
-    let end_year = end.year();
+    let regions = calendar_repo::get_regions(conn).unwrap_or_default();
+    if regions.is_empty() {
+        ensure_region_holidays_cached(conn, DEFAULT_REGION, start, end);
+    }
+    for region in regions {
+        ensure_region_holidays_cached(conn, &region, start, end);
+    }
+}
 
-    for year in start_year..=end_year {
+/// Ensure one region's holiday data is cached for the years covered by a date range.
+pub fn ensure_region_holidays_cached(conn: &Connection, region: &str, start: &NaiveDate, end: &NaiveDate) {
+    for year in start.year()..=end.year() {
         // Check if we already have data for this year
         let cached: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM holiday_cache WHERE year = ?1",
-            rusqlite::params![year],
+            "SELECT COUNT(*) FROM holiday_cache WHERE region = ?1 AND year = ?2",
+            rusqlite::params![region, year],
             |row| row.get(0),
         ).unwrap_or(0);
 
         if cached == 0 {
-            // Try to sync; if it fails (no network), just log and continue
-            match sync_holidays_for_year(conn, year) {
-                Ok(n) => log::info!("Synced {} holiday entries for year {}", n, year),
-                Err(e) => log::warn!("Failed to sync holidays for {}: {}", year, e),
+            // Try to sync; if every source fails, just log and continue with the plain work week
+            match sync_holidays_for_year(conn, region, year) {
+                Ok(n) => log::info!("Synced {} {} holiday entries for year {}", n, region, year),
+                Err(e) => log::warn!("Failed to sync {} holidays for {}: {}", region, year, e),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    #[test]
+    fn bundled_source_handles_makeup_days_and_is_recorded() {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+
+        sync_holidays_with_source(&conn, "CN", 2026, Some("bundled")).unwrap();
+
+        // Saturday makeup workday after Spring Festival, and a Monday inside the break.
+        assert!(is_workday(&conn, &NaiveDate::from_ymd_opt(2026, 2, 28).unwrap()));
+        assert!(!is_workday(&conn, &NaiveDate::from_ymd_opt(2026, 2, 16).unwrap()));
+
+        let years = list_cached_years(&conn).unwrap();
+        assert_eq!(years.len(), 1);
+        assert_eq!(years[0].source, "bundled");
+        assert_eq!(years[0].makeup_workdays, 6);
+    }
+
+    #[test]
+    fn developer_calendar_applies_region_holidays_and_weekend() {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+
+        let calendar_id = create_calendar(&conn, &CreateRegionCalendarDto {
+            name: "Berlin".to_string(),
+            region: "de".to_string(),
+            weekend_days: Some(vec![4, 5]),
+            is_default: None,
+        })
+        .unwrap();
+        let holiday = HolidayRecord { date: "2026-10-06".to_string(), is_holiday: true, name: "Firmentag".to_string() };
+        store_year(&conn, "DE", 2026, &[holiday], "file:berlin.csv").unwrap();
+        conn.execute(
+            "INSERT INTO developers (name, roles, skills, max_hours_per_day, avatar_color, calendar_id) \
+             VALUES ('Anna', '[]', '[]', 8.0, '#1890ff', ?1)",
+            rusqlite::params![calendar_id],
+        )
+        .unwrap();
+        let dev_id = conn.last_insert_rowid();
+
+        // Mon 10-05 .. Sun 10-11: Tuesday is a DE holiday, Fri/Sat are the weekend.
+        let days = crate::services::capacity_service::get_effective_capacity(&conn, dev_id, "2026-10-05", "2026-10-11").unwrap();
+        let workdays: Vec<&str> = days.iter().filter(|d| d.is_workday).map(|d| d.date.as_str()).collect();
+        assert_eq!(workdays, vec!["2026-10-05", "2026-10-07", "2026-10-08", "2026-10-11"]);
+
+        // The default (CN) calendar is unaffected by the DE cache.
+        let sunday = NaiveDate::from_ymd_opt(2026, 10, 11).unwrap();
+        assert!(!is_workday(&conn, &sunday));
+        let calendars = DeveloperCalendars::load(&conn).unwrap();
+        assert!(calendars.of(dev_id).is_workday(&sunday));
+        assert!(!calendars.of(dev_id + 1).is_workday(&sunday));
+    }
+
+    #[test]
+    fn overtime_rules_apply_per_developer() {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        for name in ["A", "B"] {
+            conn.execute(
+                "INSERT INTO developers (name, roles, skills, max_hours_per_day, avatar_color) VALUES (?1, '[]', '[]', 8.0, '#1890ff')",
+                rusqlite::params![name],
+            )
+            .unwrap();
+        }
+        let saturdays = OvertimeConfig { weekend: "saturday".to_string(), custom_dates: vec![] };
+        set_developer_overtime(&conn, 1, Some(&saturdays)).unwrap();
+        assert!(set_developer_overtime(&conn, 1, Some(&OvertimeConfig { weekend: "daily".to_string(), custom_dates: vec![] })).is_err());
+
+        let saturday = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let calendars = DeveloperCalendars::load(&conn).unwrap();
+        assert!(calendars.of(1).is_workday(&saturday));
+        assert!(!calendars.of(2).is_workday(&saturday));
+        assert!(!is_workday(&conn, &saturday));
+
+        set_developer_overtime(&conn, 1, None).unwrap();
+        assert_eq!(get_developer_overtime(&conn, 1).unwrap(), None);
+        assert!(!DeveloperCalendars::load(&conn).unwrap().of(1).is_workday(&saturday));
+    }
+
+    #[test]
+    fn overrides_take_precedence_and_survive_resync() {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+
+        // 2026-02-28 is a bundled makeup workday; the company gives it off instead,
+        // and adds a company-wide day off on an ordinary Friday.
+        let override_dto = |date: &str, is_workday: bool| CreateCalendarOverrideDto {
+            calendar_id: None,
+            date: date.to_string(),
+            is_workday,
+            name: Some("公司年会".to_string()),
+        };
+        create_override(&conn, &override_dto("2026-02-28", false)).unwrap();
+        create_override(&conn, &override_dto("2026-03-06", false)).unwrap();
+        assert!(create_override(&conn, &override_dto("2026-03-06", true)).is_err());
+
+        sync_holidays_with_source(&conn, "CN", 2026, Some("bundled")).unwrap();
+        sync_holidays_with_source(&conn, "CN", 2026, Some("bundled")).unwrap();
+
+        let cal = default_work_calendar(&conn);
+        assert!(!cal.is_workday(&NaiveDate::from_ymd_opt(2026, 2, 28).unwrap()));
+        assert!(!cal.is_workday(&NaiveDate::from_ymd_opt(2026, 3, 6).unwrap()));
+        assert!(cal.is_workday(&NaiveDate::from_ymd_opt(2026, 3, 5).unwrap()));
+        assert_eq!(list_overrides(&conn, Some("2026-03-01"), None).unwrap().len(), 1);
+    }
+}
@@ -1,10 +1,16 @@
 use rusqlite::Connection;
+use std::collections::HashMap;
 use crate::db::{task_repo, developer_repo, sprint_repo};
-use crate::models::task::{CreateTaskDto, UpdateTaskDto};
+use crate::models::enums::{Priority, TaskStatus, TaskType};
+use crate::models::task::{CreateTaskDto, Task, UpdateTaskDto};
+use crate::services::date_service::{self, YearHint};
 use crate::services::settings_service;
+use crate::services::value_mapping_service::{NormalizedValue, UnmappedValue, ValueNormalizer};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ImportResult {
+    /// The `import_history` row, for rolling the import back.
+    pub import_id: i64,
     pub rows_imported: usize,
     pub rows_updated: usize,
     pub rows_skipped: usize,
@@ -13,6 +19,46 @@ pub struct ImportResult {
     pub errors: Vec<String>,
 }
 
+/// Where imported rows came from, recorded in `import_history`.
+#[derive(Debug, Clone)]
+pub struct ImportSource {
+    pub file_name: String,
+    pub file_path: Option<String>,
+    pub sheet_name: Option<String>,
+}
+
+impl ImportSource {
+    pub fn file(file_path: &str, sheet_name: &str) -> Self {
+        let file_name = std::path::Path::new(file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+        ImportSource {
+            file_name,
+            file_path: Some(file_path.to_string()),
+            sheet_name: Some(sheet_name.to_string()),
+        }
+    }
+
+    pub fn pasted() -> Self {
+        ImportSource { file_name: "粘贴的表格".to_string(), file_path: None, sheet_name: None }
+    }
+}
+
+/// What rolling back an import undid. Developers and sprints it created that other
+/// tasks have since started using are left in place and listed in `kept`; tasks
+/// edited by hand after the import are left as they are and listed in `edited`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportRollback {
+    pub import_id: i64,
+    pub tasks_deleted: usize,
+    pub tasks_restored: usize,
+    pub developers_deleted: usize,
+    pub sprints_deleted: usize,
+    pub kept: Vec<String>,
+    pub edited: Vec<String>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ImportConflict {
     pub row_index: usize,
@@ -26,8 +72,8 @@ pub struct ImportConflict {
 
 pub fn detect_import_conflicts(
     conn: &Connection,
-    rows: &[std::collections::HashMap<String, String>],
-    column_mapping: &std::collections::HashMap<String, String>,
+    rows: &[HashMap<String, String>],
+    column_mapping: &HashMap<String, String>,
 ) -> Result<Vec<ImportConflict>, String> {
     let mut conflicts = Vec::new();
 
@@ -87,13 +133,406 @@ pub fn detect_import_conflicts(
     Ok(conflicts)
 }
 
+/// One field a dry-run import would write: the task's current value (`None` for a new
+/// task or an empty field) and the value from the row.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: String,
+}
+
+/// A problem found in a row. `level` is "error" (the row is not imported), "warning"
+/// (imported, but the value is probably wrong) or "info" (a side effect such as a new
+/// developer or sprint).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportIssue {
+    pub field: Option<String>,
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportRowPreview {
+    pub row_index: usize,
+    pub name: String,
+    pub action: String, // "create" | "update" | "skip"
+    pub existing_task_id: Option<i64>,
+    pub changes: Vec<FieldChange>,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// What an import would do, row by row, without writing anything.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportDryRun {
+    pub rows: Vec<ImportRowPreview>,
+    pub rows_to_create: usize,
+    pub rows_to_update: usize,
+    pub rows_to_skip: usize,
+    pub developers_to_create: Vec<String>,
+    pub sprints_to_create: Vec<String>,
+    /// Task type, priority and status values no mapping recognised, with how often
+    /// each occurs, for the user to map before importing.
+    pub unmapped_values: Vec<UnmappedValue>,
+}
+
+/// Task fields read from one row through the column mapping; blank cells are `None`.
+struct RowFields {
+    external_id: Option<String>,
+    task_type: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    owner: Option<String>,
+    sprint: Option<String>,
+    priority: Option<String>,
+    planned_start: Option<String>,
+    planned_end: Option<String>,
+    planned_hours: Option<String>,
+    parent_number: Option<String>,
+    parent_name: Option<String>,
+    status: Option<String>,
+}
+
+/// A field name with the row's value and the stored one.
+type FieldPair<'a> = (&'static str, Option<&'a String>, Option<&'a String>);
+
+impl RowFields {
+    fn read(row: &HashMap<String, String>, column_mapping: &HashMap<String, String>) -> Self {
+        let get = |field: &str| -> Option<String> {
+            column_mapping.get(field)
+                .and_then(|col| row.get(col))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        RowFields {
+            external_id: get("external_id"),
+            task_type: get("task_type"),
+            name: get("name"),
+            description: get("description"),
+            owner: get("owner"),
+            sprint: get("sprint"),
+            priority: get("priority"),
+            planned_start: get("planned_start"),
+            planned_end: get("planned_end"),
+            planned_hours: get("planned_hours"),
+            parent_number: get("parent_number"),
+            parent_name: get("parent_name"),
+            status: get("status"),
+        }
+    }
+
+    /// Replace task type, priority and status with the values the app uses where the
+    /// normalizer recognises them. Returns each field's outcome with the value as read.
+    fn normalize(&mut self, normalizer: &ValueNormalizer) -> Vec<(&'static str, String, NormalizedValue)> {
+        let mut outcomes = Vec::new();
+        for (field, value) in [
+            ("task_type", &mut self.task_type),
+            ("priority", &mut self.priority),
+            ("status", &mut self.status),
+        ] {
+            if let Some(raw) = value.take() {
+                let normalized = normalizer.normalize(field, &raw);
+                *value = Some(normalized.value.clone());
+                outcomes.push((field, raw, normalized));
+            }
+        }
+        outcomes
+    }
+
+    /// Rewrite the planned dates as `YYYY-MM-DD`, a date without a year taking it from
+    /// the row's sprint or the current year. Values that are not dates are dropped and
+    /// returned with their field.
+    fn normalize_dates(&mut self, conn: &Connection) -> Vec<(&'static str, String)> {
+        let sprint = self.sprint.as_ref()
+            .and_then(|name| sprint_repo::find_sprint_by_name(conn, name).ok().flatten());
+        let hint = YearHint::for_sprint(sprint.as_ref());
+        date_service::normalize_date_range(&mut self.planned_start, &mut self.planned_end, &hint)
+    }
+
+    /// Text fields in the order they are shown, paired with the stored value they replace.
+    fn text_fields<'a>(&'a self, existing: Option<&'a Task>) -> Vec<FieldPair<'a>> {
+        let old = |f: fn(&Task) -> Option<&String>| existing.and_then(f);
+        vec![
+            ("external_id", self.external_id.as_ref(), old(|t| t.external_id.as_ref())),
+            ("task_type", self.task_type.as_ref(), old(|t| t.task_type.as_ref())),
+            ("name", self.name.as_ref(), old(|t| Some(&t.name))),
+            ("description", self.description.as_ref(), old(|t| t.description.as_ref())),
+            ("owner", self.owner.as_ref(), old(|t| t.owner_name.as_ref())),
+            ("sprint", self.sprint.as_ref(), old(|t| t.sprint_name.as_ref())),
+            ("priority", self.priority.as_ref(), old(|t| t.priority.as_ref())),
+            ("planned_start", self.planned_start.as_ref(), old(|t| t.planned_start.as_ref())),
+            ("planned_end", self.planned_end.as_ref(), old(|t| t.planned_end.as_ref())),
+            ("parent_number", self.parent_number.as_ref(), old(|t| t.parent_number.as_ref())),
+            ("parent_name", self.parent_name.as_ref(), old(|t| t.parent_name.as_ref())),
+            ("status", self.status.as_ref(), old(|t| t.status.as_ref())),
+        ]
+    }
+}
+
+/// What importing a row does under the conflict mode, with the matched task's id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowAction {
+    Create,
+    Update(i64),
+    Skip,
+}
+
+fn row_action(conn: &Connection, fields: &RowFields, name: &str, conflict_mode: &str) -> RowAction {
+    if conflict_mode == "create_new" {
+        return RowAction::Create;
+    }
+    match (find_existing_task(conn, &fields.external_id, name), conflict_mode) {
+        (Some(_), "skip") => RowAction::Skip,
+        (Some(task_id), "update") => RowAction::Update(task_id),
+        _ => RowAction::Create,
+    }
+}
+
+/// Report what `import_tasks_from_rows` would do with the same arguments: the action
+/// per row, the fields it would change, and values that are missing, unrecognised or
+/// would create developers and sprints. Nothing is written.
+pub fn dry_run_import(
+    conn: &Connection,
+    rows: &[HashMap<String, String>],
+    column_mapping: &HashMap<String, String>,
+    conflict_mode: &str,
+) -> Result<ImportDryRun, String> {
+    let hours_per_day = settings_service::default_hours_per_day(conn);
+    let mut result = ImportDryRun {
+        rows: Vec::new(),
+        rows_to_create: 0,
+        rows_to_update: 0,
+        rows_to_skip: 0,
+        developers_to_create: Vec::new(),
+        sprints_to_create: Vec::new(),
+        unmapped_values: Vec::new(),
+    };
+    let normalizer = ValueNormalizer::load(conn)?;
+    // Rows created earlier in the file are matched by later rows once imported.
+    let mut created_keys: HashMap<String, usize> = HashMap::new();
+
+    for (idx, row) in rows.iter().enumerate() {
+        let mut fields = RowFields::read(row, column_mapping);
+        let normalized = fields.normalize(&normalizer);
+        let unparsed_dates = fields.normalize_dates(conn);
+        let mut preview = ImportRowPreview {
+            row_index: idx,
+            name: fields.name.clone().unwrap_or_default(),
+            action: "skip".to_string(),
+            existing_task_id: None,
+            changes: Vec::new(),
+            issues: Vec::new(),
+        };
+        let name = match fields.name.clone() {
+            Some(n) => n,
+            None => {
+                preview.issues.push(issue(Some("name"), "error", "缺少任务名称，该行不会导入".to_string()));
+                result.rows_to_skip += 1;
+                result.rows.push(preview);
+                continue;
+            }
+        };
+
+        let mut action = row_action(conn, &fields, &name, conflict_mode);
+        let mut repeats_earlier_row = false;
+        if action == RowAction::Create && conflict_mode != "create_new" {
+            let earlier = fields.external_id.as_ref().and_then(|e| created_keys.get(&format!("#{}", e)))
+                .or_else(|| created_keys.get(&name));
+            if let Some(&earlier) = earlier {
+                preview.issues.push(issue(None, "info", format!("与第 {} 行为同一任务", earlier + 1)));
+                repeats_earlier_row = true;
+                if conflict_mode == "skip" {
+                    action = RowAction::Skip;
+                }
+            }
+        }
+
+        let existing = match action {
+            RowAction::Update(task_id) => task_repo::get_by_id(conn, task_id).map_err(|e| e.to_string())?,
+            _ => None,
+        };
+        preview.existing_task_id = existing.as_ref().map(|t| t.id);
+        match action {
+            RowAction::Skip => {
+                preview.issues.push(issue(None, "info", "已存在同名或同编号任务，将跳过".to_string()));
+                result.rows_to_skip += 1;
+                result.rows.push(preview);
+                continue;
+            }
+            RowAction::Update(_) => {
+                preview.action = "update".to_string();
+                result.rows_to_update += 1;
+            }
+            // Updates the task an earlier row creates.
+            RowAction::Create if repeats_earlier_row => {
+                preview.action = "update".to_string();
+                result.rows_to_update += 1;
+            }
+            RowAction::Create => {
+                preview.action = "create".to_string();
+                result.rows_to_create += 1;
+                created_keys.entry(name.clone()).or_insert(idx);
+                if let Some(ref external_id) = fields.external_id {
+                    created_keys.entry(format!("#{}", external_id)).or_insert(idx);
+                }
+            }
+        }
+
+        for (field, new_value, old_value) in fields.text_fields(existing.as_ref()) {
+            if let Some(new_value) = new_value {
+                if old_value != Some(new_value) {
+                    preview.changes.push(FieldChange {
+                        field: field.to_string(),
+                        old_value: old_value.cloned(),
+                        new_value: new_value.clone(),
+                    });
+                }
+            }
+        }
+        if let Some(ref raw) = fields.planned_hours {
+            match parse_days_to_hours(raw, hours_per_day) {
+                Some(hours) if existing.as_ref().and_then(|t| t.planned_hours) != Some(hours) => {
+                    preview.changes.push(FieldChange {
+                        field: "planned_hours".to_string(),
+                        old_value: existing.as_ref().and_then(|t| t.planned_hours).map(|h| h.to_string()),
+                        new_value: hours.to_string(),
+                    });
+                }
+                Some(_) => {}
+                None => preview.issues.push(issue(Some("planned_hours"), "warning", format!("无法识别的工时「{}」，将被忽略", raw))),
+            }
+        }
+
+        for (field, raw) in unparsed_dates {
+            preview.issues.push(issue(Some(field), "warning", format!("无法识别的日期「{}」，将被忽略", raw)));
+        }
+        for (field, raw, outcome) in normalized {
+            match outcome.method.as_str() {
+                "fuzzy" => preview.issues.push(issue(Some(field), "info", format!("「{}」按近似值识别为「{}」", raw, outcome.value))),
+                "unmapped" => match result.unmapped_values.iter_mut().find(|u| u.field == field && u.value == outcome.value) {
+                    Some(unmapped) => unmapped.count += 1,
+                    None => result.unmapped_values.push(UnmappedValue {
+                        field: field.to_string(),
+                        suggestion: normalizer.suggest(field, &outcome.value),
+                        value: outcome.value,
+                        count: 1,
+                    }),
+                },
+                _ => {}
+            }
+        }
+        validate_row(conn, &fields, existing.as_ref(), &mut preview.issues);
+        for found in &preview.issues {
+            if found.level != "info" {
+                continue;
+            }
+            match found.field.as_deref() {
+                Some("owner") => result.developers_to_create.extend(fields.owner.clone()),
+                Some("sprint") => result.sprints_to_create.extend(fields.sprint.clone()),
+                _ => {}
+            }
+        }
+        result.rows.push(preview);
+    }
+
+    result.developers_to_create.sort();
+    result.developers_to_create.dedup();
+    result.sprints_to_create.sort();
+    result.sprints_to_create.dedup();
+    Ok(result)
+}
+
+fn issue(field: Option<&str>, level: &str, message: String) -> ImportIssue {
+    ImportIssue { field: field.map(str::to_string), level: level.to_string(), message }
+}
+
+/// An enum field, its value, its label and whether a value is one of the known ones.
+type EnumCheck<'a> = (&'static str, &'a Option<String>, &'static str, fn(&str) -> bool);
+
+/// Values the import stores as given but the rest of the app does not understand:
+/// unknown enum values and an end before the start (against the task's current dates
+/// for a partial update). Dates are already normalized. New developers and sprints
+/// are noted.
+fn validate_row(conn: &Connection, fields: &RowFields, existing: Option<&Task>, issues: &mut Vec<ImportIssue>) {
+    let enums: [EnumCheck; 3] = [
+        ("task_type", &fields.task_type, "任务类型", |v| TaskType::from_str(v).is_some()),
+        ("priority", &fields.priority, "优先级", |v| Priority::from_str(v).is_some()),
+        ("status", &fields.status, "状态", |v| TaskStatus::from_str(v).is_some()),
+    ];
+    for (field, value, label, known) in enums {
+        if let Some(value) = value {
+            if !known(value) {
+                issues.push(issue(Some(field), "warning", format!("未知的{}「{}」，将原样保存", label, value)));
+            }
+        }
+    }
+
+    let date = |value: &Option<String>, current: Option<&String>| {
+        value.as_ref().or(current).and_then(|v| date_service::parse_ymd(v))
+    };
+    let dates = [
+        date(&fields.planned_start, existing.and_then(|t| t.planned_start.as_ref())),
+        date(&fields.planned_end, existing.and_then(|t| t.planned_end.as_ref())),
+    ];
+    if let (Some(start), Some(end)) = (dates[0], dates[1]) {
+        if end < start {
+            issues.push(issue(Some("planned_end"), "warning", format!("计划结束 {} 早于计划开始 {}", end, start)));
+        }
+    }
+
+    if let Some(ref owner) = fields.owner {
+        if developer_repo::find_by_name(conn, owner).ok().flatten().is_none() {
+            issues.push(issue(Some("owner"), "info", format!("将新建人员「{}」", owner)));
+        }
+    }
+    if let Some(ref sprint) = fields.sprint {
+        if sprint_repo::find_sprint_by_name(conn, sprint).ok().flatten().is_none() {
+            issues.push(issue(Some("sprint"), "info", format!("将新建迭代「{}」", sprint)));
+        }
+    }
+}
+
+/// Import rows as tasks in one transaction: a database error on any row undoes the
+/// whole import. Every task, developer and sprint it creates or updates is logged
+/// against its `import_history` row so `rollback_import` can revert it.
 pub fn import_tasks_from_rows(
     conn: &Connection,
-    rows: &[std::collections::HashMap<String, String>],
-    column_mapping: &std::collections::HashMap<String, String>,
+    rows: &[HashMap<String, String>],
+    column_mapping: &HashMap<String, String>,
+    conflict_mode: &str,
+    source: &ImportSource,
+) -> Result<ImportResult, String> {
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match import_rows(conn, rows, column_mapping, conflict_mode, source) {
+        Ok(result) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(result)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            Err(e)
+        }
+    }
+}
+
+fn import_rows(
+    conn: &Connection,
+    rows: &[HashMap<String, String>],
+    column_mapping: &HashMap<String, String>,
     conflict_mode: &str,
+    source: &ImportSource,
 ) -> Result<ImportResult, String> {
+    // Record import history first: its id tags everything the import changes.
+    let mapping_json = serde_json::to_string(column_mapping).unwrap_or_default();
+    conn.execute(
+        "INSERT INTO import_history (file_name, file_path, import_date, sheet_name, column_mapping, rows_imported) \
+         VALUES (?1, ?2, datetime('now'), ?3, ?4, 0)",
+        rusqlite::params![source.file_name, source.file_path, source.sheet_name, mapping_json],
+    ).map_err(|e| e.to_string())?;
+    let import_id = conn.last_insert_rowid();
+
     let mut result = ImportResult {
+        import_id,
         rows_imported: 0,
         rows_updated: 0,
         rows_skipped: 0,
@@ -103,23 +542,15 @@ pub fn import_tasks_from_rows(
     };
 
     let mut dev_color_index = 0usize;
-
-    // Read hours_per_day from settings (default 8)
-    let hours_per_day = settings_service::get_setting(conn, "work_hours.hours_per_day")
-        .ok()
-        .flatten()
-        .and_then(|v| v.parse::<f64>().ok())
-        .unwrap_or(8.0);
+    let hours_per_day = settings_service::default_hours_per_day(conn);
+    let normalizer = ValueNormalizer::load(conn)?;
 
     for (idx, row) in rows.iter().enumerate() {
-        let get_mapped = |field: &str| -> Option<String> {
-            column_mapping.get(field)
-                .and_then(|col| row.get(col))
-                .map(|v| v.trim().to_string())
-                .filter(|v| !v.is_empty())
-        };
+        let mut fields = RowFields::read(row, column_mapping);
+        fields.normalize(&normalizer);
+        let unparsed_dates = fields.normalize_dates(conn);
 
-        let name = match get_mapped("name") {
+        let name = match fields.name.clone() {
             Some(n) => n,
             None => {
                 result.errors.push(format!("Row {}: missing task name", idx + 1));
@@ -127,163 +558,123 @@ pub fn import_tasks_from_rows(
             }
         };
 
-        // Check for existing task when conflict_mode is not "create_new"
-        let existing_task_id = if conflict_mode != "create_new" {
-            find_existing_task(conn, &get_mapped("external_id"), &name)
-        } else {
-            None
-        };
-
-        // Handle conflict modes
-        if let Some(task_id) = existing_task_id {
-            match conflict_mode {
-                "skip" => {
-                    result.rows_skipped += 1;
-                    continue;
-                }
-                "update" => {
-                    // Resolve owner for update
-                    let owner_id = if let Some(owner_name) = get_mapped("owner") {
-                        match developer_repo::find_or_create_by_name(conn, &owner_name, dev_color_index) {
-                            Ok(id) => {
-                                if developer_repo::find_by_name(conn, &owner_name)
-                                    .ok()
-                                    .flatten()
-                                    .is_some()
-                                {
-                                    // Already existed
-                                } else {
-                                    result.developers_created.push(owner_name.clone());
-                                }
-                                dev_color_index += 1;
-                                Some(id)
-                            }
-                            Err(e) => {
-                                result.errors.push(format!("Row {}: failed to create developer '{}': {}", idx + 1, owner_name, e));
-                                None
-                            }
-                        }
-                    } else {
-                        None
-                    };
-
-                    // Resolve sprint for update
-                    let sprint_id = if let Some(sprint_name) = get_mapped("sprint") {
-                        match sprint_repo::find_or_create_sprint(conn, &sprint_name) {
-                            Ok(id) => {
-                                result.sprints_created.push(sprint_name.clone());
-                                Some(id)
-                            }
-                            Err(e) => {
-                                result.errors.push(format!("Row {}: failed to create sprint '{}': {}", idx + 1, sprint_name, e));
-                                None
-                            }
-                        }
-                    } else {
-                        None
-                    };
-
-                    let update_dto = UpdateTaskDto {
-                        id: task_id,
-                        external_id: get_mapped("external_id"),
-                        task_type: get_mapped("task_type"),
-                        name: Some(name),
-                        description: get_mapped("description"),
-                        owner_id,
-                        sprint_id,
-                        priority: get_mapped("priority"),
-                        planned_start: get_mapped("planned_start"),
-                        planned_end: get_mapped("planned_end"),
-                        planned_hours: get_mapped("planned_hours").and_then(|h| parse_days_to_hours(&h, hours_per_day)),
-                        parent_task_id: None,
-                        parent_number: get_mapped("parent_number"),
-                        parent_name: get_mapped("parent_name"),
-                        status: get_mapped("status"),
-                        co_owner_ids: None,
-                    };
-
-                    match task_repo::update(conn, &update_dto) {
-                        Ok(_) => result.rows_updated += 1,
-                        Err(e) => result.errors.push(format!("Row {}: failed to update task: {}", idx + 1, e)),
-                    }
-                    continue;
-                }
-                _ => {} // "create_new" - fall through to create
-            }
+        let action = row_action(conn, &fields, &name, conflict_mode);
+        if action == RowAction::Skip {
+            result.rows_skipped += 1;
+            continue;
+        }
+        for (field, raw) in unparsed_dates {
+            let label = if field == "planned_start" { "计划开始" } else { "计划结束" };
+            result.errors.push(format!("Row {}: 无法识别的{}「{}」，已忽略", idx + 1, label, raw));
         }
 
         // Resolve owner
-        let owner_id = if let Some(owner_name) = get_mapped("owner") {
-            match developer_repo::find_or_create_by_name(conn, &owner_name, dev_color_index) {
-                Ok(id) => {
-                    if developer_repo::find_by_name(conn, &owner_name)
-                        .ok()
-                        .flatten()
-                        .is_some()
-                    {
-                        // Already existed
-                    } else {
+        let owner_id = match fields.owner {
+            Some(ref owner_name) => {
+                let existing = developer_repo::find_by_name(conn, owner_name).map_err(|e| e.to_string())?;
+                Some(match existing {
+                    Some(dev) => dev.id,
+                    None => {
+                        let id = developer_repo::find_or_create_by_name(conn, owner_name, dev_color_index)
+                            .map_err(|e| format!("Row {}: failed to create developer '{}': {}", idx + 1, owner_name, e))?;
+                        record_change(conn, import_id, "developer", id, "create", None, None)?;
                         result.developers_created.push(owner_name.clone());
+                        dev_color_index += 1;
+                        id
                     }
-                    dev_color_index += 1;
-                    Some(id)
-                }
-                Err(e) => {
-                    result.errors.push(format!("Row {}: failed to create developer '{}': {}", idx + 1, owner_name, e));
-                    None
-                }
+                })
             }
-        } else {
-            None
+            None => None,
         };
 
         // Resolve sprint
-        let sprint_id = if let Some(sprint_name) = get_mapped("sprint") {
-            match sprint_repo::find_or_create_sprint(conn, &sprint_name) {
-                Ok(id) => {
-                    result.sprints_created.push(sprint_name.clone());
-                    Some(id)
-                }
-                Err(e) => {
-                    result.errors.push(format!("Row {}: failed to create sprint '{}': {}", idx + 1, sprint_name, e));
-                    None
-                }
+        let sprint_id = match fields.sprint {
+            Some(ref sprint_name) => {
+                let existing = sprint_repo::find_sprint_by_name(conn, sprint_name).map_err(|e| e.to_string())?;
+                Some(match existing {
+                    Some(sprint) => sprint.id,
+                    None => {
+                        let id = sprint_repo::find_or_create_sprint(conn, sprint_name)
+                            .map_err(|e| format!("Row {}: failed to create sprint '{}': {}", idx + 1, sprint_name, e))?;
+                        record_change(conn, import_id, "sprint", id, "create", None, None)?;
+                        result.sprints_created.push(sprint_name.clone());
+                        id
+                    }
+                })
             }
-        } else {
-            None
+            None => None,
         };
 
+        let planned_hours = fields.planned_hours.as_deref().and_then(|h| parse_days_to_hours(h, hours_per_day));
+
+        if let RowAction::Update(task_id) = action {
+            let prior = task_repo::get_by_id(conn, task_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Row {}: task {} no longer exists", idx + 1, task_id))?;
+            let prior_json = serde_json::to_string(&prior).map_err(|e| e.to_string())?;
+
+            let update_dto = UpdateTaskDto {
+                id: task_id,
+                external_id: fields.external_id,
+                task_type: fields.task_type,
+                name: Some(name),
+                description: fields.description,
+                owner_id,
+                sprint_id,
+                priority: fields.priority,
+                planned_start: fields.planned_start,
+                planned_end: fields.planned_end,
+                planned_start_time: None,
+                planned_end_time: None,
+                planned_hours,
+                parent_task_id: None,
+                parent_number: fields.parent_number,
+                parent_name: fields.parent_name,
+                status: fields.status,
+                co_owner_ids: None,
+                required_skills: None,
+            };
+
+            task_repo::update(conn, &update_dto)
+                .map_err(|e| format!("Row {}: failed to update task: {}", idx + 1, e))?;
+            let after_json = task_snapshot(conn, task_id)?;
+            record_change(conn, import_id, "task", task_id, "update", Some(&prior_json), Some(&after_json))?;
+            result.rows_updated += 1;
+            continue;
+        }
+
         let dto = CreateTaskDto {
-            external_id: get_mapped("external_id"),
-            task_type: get_mapped("task_type"),
+            external_id: fields.external_id,
+            task_type: fields.task_type,
             name,
-            description: get_mapped("description"),
+            description: fields.description,
             owner_id,
             sprint_id,
-            priority: get_mapped("priority"),
-            planned_start: get_mapped("planned_start"),
-            planned_end: get_mapped("planned_end"),
-            planned_hours: get_mapped("planned_hours").and_then(|h| parse_days_to_hours(&h, hours_per_day)),
+            priority: fields.priority,
+            planned_start: fields.planned_start,
+            planned_end: fields.planned_end,
+            planned_start_time: None,
+            planned_end_time: None,
+            planned_hours,
             parent_task_id: None,
-            parent_number: get_mapped("parent_number"),
-            parent_name: get_mapped("parent_name"),
-            status: get_mapped("status"),
+            parent_number: fields.parent_number,
+            parent_name: fields.parent_name,
+            status: fields.status,
             co_owner_ids: None,
+            required_skills: None,
         };
 
-        match task_repo::create(conn, &dto) {
-            Ok(_) => result.rows_imported += 1,
-            Err(e) => result.errors.push(format!("Row {}: failed to insert task: {}", idx + 1, e)),
-        }
+        let task_id = task_repo::create(conn, &dto)
+            .map_err(|e| format!("Row {}: failed to insert task: {}", idx + 1, e))?;
+        let after_json = task_snapshot(conn, task_id)?;
+        record_change(conn, import_id, "task", task_id, "create", None, Some(&after_json))?;
+        result.rows_imported += 1;
     }
 
-    // Record import history
-    let mapping_json = serde_json::to_string(column_mapping).unwrap_or_default();
-    let total_affected = (result.rows_imported + result.rows_updated) as i64;
-    let _ = conn.execute(
-        "INSERT INTO import_history (file_name, import_date, column_mapping, rows_imported) VALUES (?1, datetime('now'), ?2, ?3)",
-        rusqlite::params!["excel_import", mapping_json, total_affected],
-    );
+    conn.execute(
+        "UPDATE import_history SET rows_imported = ?1, rows_updated = ?2 WHERE id = ?3",
+        rusqlite::params![result.rows_imported as i64, result.rows_updated as i64, import_id],
+    ).map_err(|e| e.to_string())?;
 
     // Deduplicate
     result.developers_created.sort();
@@ -294,6 +685,178 @@ pub fn import_tasks_from_rows(
     Ok(result)
 }
 
+fn record_change(
+    conn: &Connection,
+    import_id: i64,
+    entity_type: &str,
+    entity_id: i64,
+    action: &str,
+    prior_values: Option<&str>,
+    after_values: Option<&str>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO import_changes (import_id, entity_type, entity_id, action, prior_values, after_values) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![import_id, entity_type, entity_id, action, prior_values, after_values],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// A task as the import left it, to tell later hand edits apart on rollback.
+fn task_snapshot(conn: &Connection, task_id: i64) -> Result<String, String> {
+    let task = task_repo::get_by_id(conn, task_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Task {} not found", task_id))?;
+    serde_json::to_string(&task).map_err(|e| e.to_string())
+}
+
+/// Whether two snapshots agree on the fields an import writes.
+fn same_imported_fields(a: &Task, b: &Task) -> bool {
+    a.external_id == b.external_id
+        && a.task_type == b.task_type
+        && a.name == b.name
+        && a.description == b.description
+        && a.owner_id == b.owner_id
+        && a.sprint_id == b.sprint_id
+        && a.priority == b.priority
+        && a.planned_start == b.planned_start
+        && a.planned_end == b.planned_end
+        && a.planned_hours == b.planned_hours
+        && a.parent_number == b.parent_number
+        && a.parent_name == b.parent_name
+        && a.status == b.status
+}
+
+/// Revert an import: delete the tasks it created, restore the fields of the tasks it
+/// updated, then delete the developers and sprints it created if nothing else uses
+/// them. Tasks edited since the import are kept. Refused while a later import that
+/// touched the same tasks is still applied, and for imports without a change log.
+pub fn rollback_import(conn: &Connection, import_id: i64) -> Result<ImportRollback, String> {
+    let (rolled_back_at, rows_written): (Option<String>, i64) = conn.query_row(
+        "SELECT rolled_back_at, rows_imported + rows_updated FROM import_history WHERE id = ?1",
+        rusqlite::params![import_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|_| format!("导入记录 {} 不存在", import_id))?;
+    if rolled_back_at.is_some() {
+        return Err("该导入已撤销".to_string());
+    }
+    let logged: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM import_changes WHERE import_id = ?1",
+        rusqlite::params![import_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    if logged == 0 && rows_written > 0 {
+        return Err("该导入没有变更记录（早于撤销功能的导入），无法撤销".to_string());
+    }
+
+    let later: Option<i64> = conn.query_row(
+        "SELECT MIN(c.import_id) FROM import_changes c \
+         JOIN import_history h ON h.id = c.import_id \
+         WHERE c.import_id > ?1 AND h.rolled_back_at IS NULL AND c.entity_type = 'task' \
+           AND c.entity_id IN (SELECT entity_id FROM import_changes WHERE import_id = ?1 AND entity_type = 'task')",
+        rusqlite::params![import_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    if let Some(later) = later {
+        return Err(format!("请先撤销之后的导入 #{}，它修改了本次导入涉及的任务", later));
+    }
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match revert_changes(conn, import_id) {
+        Ok(result) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(result)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            Err(e)
+        }
+    }
+}
+
+fn revert_changes(conn: &Connection, import_id: i64) -> Result<ImportRollback, String> {
+    let mut stmt = conn.prepare(
+        "SELECT entity_type, entity_id, action, prior_values, after_values FROM import_changes \
+         WHERE import_id = ?1 ORDER BY id DESC",
+    ).map_err(|e| e.to_string())?;
+    let changes = stmt
+        .query_map(rusqlite::params![import_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())?;
+
+    let mut result = ImportRollback {
+        import_id,
+        tasks_deleted: 0,
+        tasks_restored: 0,
+        developers_deleted: 0,
+        sprints_deleted: 0,
+        kept: Vec::new(),
+        edited: Vec::new(),
+    };
+
+    // Newest first, so each row's task goes before the developer and sprint made for it.
+    for (entity_type, entity_id, action, prior_values, after_values) in changes {
+        if entity_type == "task" {
+            // Tasks deleted since the import are left alone, and so are hand edits made
+            // after it.
+            let Some(current) = task_repo::get_by_id(conn, entity_id).map_err(|e| e.to_string())? else { continue };
+            if let Some(after) = after_values.as_deref() {
+                let after: Task = serde_json::from_str(after)
+                    .map_err(|e| format!("导入记录中的任务快照无法解析: {}", e))?;
+                if !same_imported_fields(&current, &after) {
+                    result.edited.push(current.name);
+                    continue;
+                }
+            }
+        }
+        match (entity_type.as_str(), action.as_str()) {
+            ("task", "create") => {
+                task_repo::delete(conn, entity_id).map_err(|e| e.to_string())?;
+                result.tasks_deleted += 1;
+            }
+            ("task", "update") => {
+                let prior: Task = serde_json::from_str(prior_values.as_deref().unwrap_or_default())
+                    .map_err(|e| format!("导入记录中的任务快照无法解析: {}", e))?;
+                task_repo::restore_imported_fields(conn, &prior).map_err(|e| e.to_string())?;
+                result.tasks_restored += 1;
+            }
+            ("developer", _) => {
+                let Some(dev) = developer_repo::get_by_id(conn, entity_id).map_err(|e| e.to_string())? else { continue };
+                if developer_repo::count_references(conn, entity_id).map_err(|e| e.to_string())? > 0 {
+                    result.kept.push(dev.name);
+                } else {
+                    developer_repo::delete(conn, entity_id).map_err(|e| e.to_string())?;
+                    result.developers_deleted += 1;
+                }
+            }
+            ("sprint", _) => {
+                let Some(sprint) = sprint_repo::get_sprint_by_id(conn, entity_id).map_err(|e| e.to_string())? else { continue };
+                if sprint_repo::get_task_count_by_sprint(conn, entity_id).map_err(|e| e.to_string())? > 0 {
+                    result.kept.push(sprint.name);
+                } else {
+                    sprint_repo::delete_sprint(conn, entity_id).map_err(|e| e.to_string())?;
+                    result.sprints_deleted += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    conn.execute(
+        "UPDATE import_history SET rolled_back_at = datetime('now') WHERE id = ?1",
+        rusqlite::params![import_id],
+    ).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
 /// Find an existing task by external_id or name.
 /// Returns the task id if found.
 fn find_existing_task(conn: &Connection, external_id: &Option<String>, name: &str) -> Option<i64> {
@@ -339,3 +902,163 @@ fn parse_days_to_hours(raw: &str, hours_per_day: f64) -> Option<f64> {
     }
     Some(days * hours_per_day)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute_batch(
+            "INSERT INTO developers (name) VALUES ('张三');
+             INSERT INTO tasks (name, external_id, owner_id, planned_start, planned_end, planned_hours, status)
+                 VALUES ('登录', 'T-1', 1, '2026-03-02', '2026-03-04', 16, '进行中');",
+        ).expect("seed");
+        conn
+    }
+
+    fn rows(data: &[&[(&str, &str)]]) -> Vec<HashMap<String, String>> {
+        data.iter()
+            .map(|r| r.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+            .collect()
+    }
+
+    fn mapping() -> HashMap<String, String> {
+        ["external_id", "name", "owner", "sprint", "planned_start", "planned_end", "planned_hours", "status"]
+            .iter()
+            .map(|f| (f.to_string(), f.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn dry_run_reports_actions_changes_and_issues_without_writing() {
+        let conn = setup_conn();
+        let rows = rows(&[
+            &[("external_id", "T-1"), ("name", "登录"), ("planned_end", "2026/3/1"), ("planned_hours", "3"), ("status", "Done")],
+            &[("name", "导出"), ("owner", "李四"), ("sprint", "S1"), ("planned_start", "下周一"), ("status", "待评估")],
+            &[("name", "导出"), ("planned_hours", "两天"), ("status", "已完成了")],
+            &[("owner", "张三")],
+        ]);
+
+        let run = dry_run_import(&conn, &rows, &mapping(), "update").unwrap();
+        assert_eq!((run.rows_to_create, run.rows_to_update, run.rows_to_skip), (1, 2, 1));
+        assert_eq!(run.developers_to_create, vec!["李四"]);
+        assert_eq!(run.sprints_to_create, vec!["S1"]);
+
+        let update = &run.rows[0];
+        assert_eq!((update.action.as_str(), update.existing_task_id), ("update", Some(1)));
+        let changed: Vec<(&str, Option<&str>, &str)> = update.changes.iter()
+            .map(|c| (c.field.as_str(), c.old_value.as_deref(), c.new_value.as_str()))
+            .collect();
+        assert_eq!(changed, vec![
+            ("planned_end", Some("2026-03-04"), "2026-03-01"),
+            ("status", Some("进行中"), "已完成"),
+            ("planned_hours", Some("16"), "24"),
+        ]);
+        let fields = |i: usize| -> Vec<(Option<String>, String)> {
+            run.rows[i].issues.iter().map(|x| (x.field.clone(), x.level.clone())).collect()
+        };
+        assert_eq!(fields(0), vec![(Some("planned_end".into()), "warning".into())]);
+        assert_eq!(fields(1), vec![
+            (Some("planned_start".into()), "warning".into()),
+            (Some("status".into()), "warning".into()),
+            (Some("owner".into()), "info".into()),
+            (Some("sprint".into()), "info".into()),
+        ]);
+        assert_eq!(fields(2), vec![
+            (None, "info".into()),
+            (Some("planned_hours".into()), "warning".into()),
+            (Some("status".into()), "info".into()),
+        ]);
+        let unmapped: Vec<(&str, &str, usize)> = run.unmapped_values.iter()
+            .map(|u| (u.field.as_str(), u.value.as_str(), u.count))
+            .collect();
+        assert_eq!(unmapped, vec![("status", "待评估", 1)]);
+        assert_eq!(run.rows[3].action, "skip");
+        assert_eq!(fields(3), vec![(Some("name".into()), "error".into())]);
+
+        assert_eq!(task_repo::count_tasks(&conn).unwrap(), 1);
+        assert!(developer_repo::find_by_name(&conn, "李四").unwrap().is_none());
+
+        let result = import_tasks_from_rows(&conn, &rows, &mapping(), "update", &ImportSource::pasted()).unwrap();
+        assert_eq!((result.rows_imported, result.rows_updated), (1, 2));
+        assert_eq!(result.errors, vec!["Row 2: 无法识别的计划开始「下周一」，已忽略", "Row 4: missing task name"]);
+        assert_eq!(result.developers_created, vec!["李四"]);
+        assert_eq!(result.sprints_created, vec!["S1"]);
+        assert_eq!(task_repo::get_by_id(&conn, 1).unwrap().unwrap().status.as_deref(), Some("已完成"));
+    }
+
+    #[test]
+    fn import_is_recorded_and_rolled_back_as_one_batch() {
+        let conn = setup_conn();
+        let source = ImportSource::file("/data/plan.xlsx", "任务");
+        let first = import_tasks_from_rows(&conn, &rows(&[
+            &[("external_id", "T-1"), ("name", "登录"), ("owner", "李四"), ("planned_end", "2026-03-06")],
+            &[("name", "导出"), ("owner", "李四"), ("sprint", "S1")],
+        ]), &mapping(), "update", &source).unwrap();
+        assert_eq!((first.rows_imported, first.rows_updated), (1, 1));
+        let (file_name, rows_imported, rows_updated): (String, i64, i64) = conn.query_row(
+            "SELECT file_name, rows_imported, rows_updated FROM import_history WHERE id = ?1",
+            [first.import_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        ).unwrap();
+        assert_eq!((file_name.as_str(), rows_imported, rows_updated), ("plan.xlsx", 1, 1));
+
+        // A later import touching the same task blocks rolling back the first one.
+        let second = import_tasks_from_rows(&conn, &rows(&[&[("name", "导出"), ("status", "已完成")]]), &mapping(), "update", &source).unwrap();
+        assert!(rollback_import(&conn, first.import_id).unwrap_err().contains(&format!("#{}", second.import_id)));
+        rollback_import(&conn, second.import_id).unwrap();
+
+        let undone = rollback_import(&conn, first.import_id).unwrap();
+        assert_eq!((undone.tasks_deleted, undone.tasks_restored, undone.developers_deleted, undone.sprints_deleted), (1, 1, 1, 1));
+        let task = task_repo::get_by_id(&conn, 1).unwrap().unwrap();
+        assert_eq!((task.owner_id, task.planned_end.as_deref()), (Some(1), Some("2026-03-04")));
+        assert_eq!(task_repo::count_tasks(&conn).unwrap(), 1);
+        assert!(developer_repo::find_by_name(&conn, "李四").unwrap().is_none());
+        assert_eq!(rollback_import(&conn, first.import_id).unwrap_err(), "该导入已撤销");
+    }
+
+    #[test]
+    fn rollback_keeps_hand_edits_and_needs_a_change_log() {
+        let conn = setup_conn();
+        let source = ImportSource::file("/data/plan.xlsx", "任务");
+        let import = import_tasks_from_rows(&conn, &rows(&[
+            &[("name", "导出"), ("owner", "张三")],
+            &[("name", "报表"), ("owner", "张三")],
+        ]), &mapping(), "create_new", &source).unwrap();
+        conn.execute("UPDATE tasks SET planned_hours = 12 WHERE name = '报表'", []).unwrap();
+
+        let undone = rollback_import(&conn, import.import_id).unwrap();
+        assert_eq!(undone.tasks_deleted, 1);
+        assert_eq!(undone.edited, vec!["报表".to_string()]);
+        assert_eq!(task_repo::count_tasks(&conn).unwrap(), 2);
+
+        conn.execute(
+            "INSERT INTO import_history (file_name, import_date, rows_imported) VALUES ('old.xlsx', '2026-01-05', 3)",
+            [],
+        ).unwrap();
+        let legacy = conn.last_insert_rowid();
+        assert!(rollback_import(&conn, legacy).unwrap_err().contains("没有变更记录"));
+    }
+
+    #[test]
+    fn failing_row_leaves_nothing_behind() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            "CREATE TRIGGER reject_broken BEFORE INSERT ON tasks WHEN NEW.name = '坏行'
+             BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+        ).unwrap();
+        let err = import_tasks_from_rows(&conn, &rows(&[
+            &[("name", "导出"), ("owner", "李四")],
+            &[("name", "坏行")],
+        ]), &mapping(), "create_new", &ImportSource::pasted()).unwrap_err();
+        assert!(err.starts_with("Row 2"));
+        assert_eq!(task_repo::count_tasks(&conn).unwrap(), 1);
+        assert!(developer_repo::find_by_name(&conn, "李四").unwrap().is_none());
+        let history: i64 = conn.query_row("SELECT COUNT(*) FROM import_history", [], |r| r.get(0)).unwrap();
+        assert_eq!(history, 0);
+    }
+}
@@ -6,6 +6,7 @@ use crate::models::developer::Developer;
 use crate::models::settings::LlmConfig;
 use crate::models::sprint::Sprint;
 use crate::models::task::{Task, UpdateTaskDto};
+use crate::services::date_service::{self, YearHint};
 use rusqlite::Connection;
 
 pub fn chat_with_context(
@@ -82,7 +83,7 @@ fn build_chat_messages(
         .map(|d| {
             format!(
                 "  - {} (ID:{}, 角色:{:?}, 技能:{:?}, 日最大工时:{}h)",
-                d.name, d.id, d.roles, d.skills, d.max_hours_per_day
+                d.name, d.id, d.roles, describe_skills(d), d.max_hours_per_day
             )
         })
         .collect();
@@ -224,9 +225,22 @@ pub fn execute_chat_action(
 ) -> Result<String, String> {
     match action.action_type.as_str() {
         "batch_update" => {
-            let updates: Vec<UpdateTaskDto> =
+            let mut updates: Vec<UpdateTaskDto> =
                 serde_json::from_value(action.payload["updates"].clone())
                     .map_err(|e| format!("Invalid update payload: {}", e))?;
+            for update in &mut updates {
+                let sprint_id = update.sprint_id.or_else(|| {
+                    crate::db::task_repo::get_by_id(conn, update.id).ok().flatten().and_then(|t| t.sprint_id)
+                });
+                let sprint = sprints.iter().find(|s| Some(s.id) == sprint_id);
+                // Models do not always follow the prompt and answer "3月1日" or "2026/3/1".
+                date_service::require_task_dates(
+                    &format!("#{}", update.id),
+                    &mut update.planned_start,
+                    &mut update.planned_end,
+                    &YearHint::for_sprint(sprint),
+                )?;
+            }
 
             let result = crate::services::task_service::batch_update_tasks(conn, &updates)?;
             Ok(format!(
@@ -305,6 +319,8 @@ pub fn execute_chat_action(
                         .get("planned_end")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string()),
+                    planned_start_time: None,
+                    planned_end_time: None,
                     planned_hours: ai_task.get("planned_hours").and_then(|v| v.as_f64()),
                     parent_task_id: ai_task.get("parent_task_id").and_then(|v| v.as_i64()),
                     parent_number: ai_task
@@ -320,6 +336,9 @@ pub fn execute_chat_action(
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string()),
                     co_owner_ids: None,
+                    required_skills: ai_task
+                        .get("required_skills")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok()),
                 };
                 create_dtos.push(dto);
             }
@@ -344,7 +363,7 @@ pub fn ai_smart_schedule(
         .iter()
         .map(|t| {
             format!(
-                "{{id:{},name:\"{}\",type:\"{}\",priority:\"{}\",hours:{},status:\"{}\"}}",
+                "{{id:{},name:\"{}\",type:\"{}\",priority:\"{}\",hours:{},status:\"{}\",needs:{:?}}}",
                 t.id,
                 t.name,
                 t.task_type.as_deref().unwrap_or("-"),
@@ -352,7 +371,11 @@ pub fn ai_smart_schedule(
                 t.planned_hours
                     .map(|h| h.to_string())
                     .unwrap_or("-".to_string()),
-                t.status.as_deref().unwrap_or("-")
+                t.status.as_deref().unwrap_or("-"),
+                t.required_skills
+                    .iter()
+                    .map(|s| format!("{}:{}", s.name, s.level))
+                    .collect::<Vec<_>>()
             )
         })
         .collect();
@@ -362,7 +385,7 @@ pub fn ai_smart_schedule(
         .map(|d| {
             format!(
                 "{{id:{},name:\"{}\",roles:{:?},skills:{:?},max_h:{}}}",
-                d.id, d.name, d.roles, d.skills, d.max_hours_per_day
+                d.id, d.name, d.roles, describe_skills(d), d.max_hours_per_day
             )
         })
         .collect();
@@ -375,7 +398,7 @@ pub fn ai_smart_schedule(
 人员:[{}]
 今天:{}
 说明:任务hours字段单位是小时；若用户界面显示为“天”，换算为 1天={}小时。
-规则:每个开发者每天不得超过其max_h；排除周末和法定节假日；尽量均衡分配并匹配技能。
+规则:每个开发者每天不得超过其max_h；排除周末和法定节假日；尽量均衡分配并匹配技能(技能格式为 名称:等级1-5，needs为任务所需最低等级)。
 直接返回JSON数组,不要输出任何其他文字。每项:task_id(int),developer_id(int),planned_start(YYYY-MM-DD),planned_end(YYYY-MM-DD),reasoning(简短一句话)"#,
         tasks.len(),
         tasks_desc.join(","),
@@ -489,7 +512,7 @@ pub fn ai_auto_fill_tasks(
         .map(|d| {
             format!(
                 "{{id:{},name:\"{}\",roles:{:?},skills:{:?}}}",
-                d.id, d.name, d.roles, d.skills
+                d.id, d.name, d.roles, describe_skills(d)
             )
         })
         .collect();
@@ -532,6 +555,18 @@ pub fn ai_auto_fill_tasks(
     })
 }
 
+/// Skills as `name:level` so prompts carry proficiency, e.g. `["Rust:4", "SQL:3"]`.
+fn describe_skills(developer: &Developer) -> Vec<String> {
+    developer
+        .skills
+        .iter()
+        .map(|name| {
+            let level = developer.skill_level(name).unwrap_or(crate::models::developer::DEFAULT_SKILL_LEVEL);
+            format!("{}:{}", name, level)
+        })
+        .collect()
+}
+
 fn extract_json_array(content: &str) -> String {
     // Try to extract JSON array from markdown code blocks or raw content
     let trimmed = content.trim();
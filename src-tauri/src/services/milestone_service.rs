@@ -0,0 +1,40 @@
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use crate::db::sprint_repo;
+use crate::models::sprint::{CreateMilestoneDto, Milestone, UpdateMilestoneDto};
+
+pub fn list_milestones(conn: &Connection, start_date: Option<&str>, end_date: Option<&str>) -> Result<Vec<Milestone>, String> {
+    sprint_repo::get_milestones(conn, start_date, end_date).map_err(|e| e.to_string())
+}
+
+fn validate_milestone(conn: &Connection, name: Option<&str>, date: Option<&str>, sprint_id: Option<i64>) -> Result<(), String> {
+    if name.is_some_and(|n| n.trim().is_empty()) {
+        return Err("里程碑名称不能为空".to_string());
+    }
+    if let Some(date) = date {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("日期格式无效: {}", date))?;
+    }
+    if let Some(id) = sprint_id {
+        if sprint_repo::get_sprint_by_id(conn, id).map_err(|e| e.to_string())?.is_none() {
+            return Err(format!("迭代不存在: {}", id));
+        }
+    }
+    Ok(())
+}
+
+pub fn create_milestone(conn: &Connection, dto: &CreateMilestoneDto) -> Result<i64, String> {
+    validate_milestone(conn, Some(&dto.name), Some(&dto.date), dto.sprint_id)?;
+    sprint_repo::create_milestone(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn update_milestone(conn: &Connection, dto: &UpdateMilestoneDto) -> Result<(), String> {
+    if sprint_repo::get_milestone_by_id(conn, dto.id).map_err(|e| e.to_string())?.is_none() {
+        return Err("里程碑不存在".to_string());
+    }
+    validate_milestone(conn, dto.name.as_deref(), dto.date.as_deref(), dto.sprint_id)?;
+    sprint_repo::update_milestone(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn delete_milestone(conn: &Connection, id: i64) -> Result<(), String> {
+    sprint_repo::delete_milestone(conn, id).map_err(|e| e.to_string())
+}
@@ -6,3 +6,14 @@ pub mod holiday_service;
 pub mod settings_service;
 pub mod llm_service;
 pub mod standup_service;
+pub mod capacity_service;
+pub mod assignment_service;
+pub mod team_service;
+pub mod reschedule_service;
+pub mod conflict_service;
+pub mod baseline_service;
+pub mod scenario_service;
+pub mod time_slot_service;
+pub mod value_mapping_service;
+pub mod date_service;
+pub mod milestone_service;
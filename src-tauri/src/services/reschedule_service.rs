@@ -0,0 +1,623 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use rusqlite::Connection;
+use chrono::{Duration, NaiveDate};
+use crate::db::{developer_repo, sprint_repo, task_repo};
+use crate::holiday::work_calendar::WorkCalendar;
+use crate::models::calendar::{LevelingPlan, ScheduleViolation};
+use crate::models::task::{DailyAllocation, Task, TaskFilter, TaskMove, UpdateTaskDto};
+use crate::services::assignment_service::{self, finish_date, ALLOC_EPSILON};
+use crate::services::date_service::{fmt_ymd, parse_date_range, parse_ymd};
+use crate::services::capacity_service::{self, CapacityProfile};
+use crate::services::holiday_service::{self, DeveloperCalendars};
+use crate::services::{conflict_service, schedule_service, settings_service, team_service};
+
+/// How a moved task keeps its length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveMode {
+    /// Same number of workdays on the owner's calendar.
+    Workdays,
+    /// `planned_hours` at the owner's daily capacity.
+    Hours,
+}
+
+impl MoveMode {
+    pub fn parse(mode: Option<&str>) -> Result<Self, String> {
+        match mode.unwrap_or("workdays") {
+            "workdays" => Ok(Self::Workdays),
+            "hours" => Ok(Self::Hours),
+            other => Err(format!("未知的移动方式: {}", other)),
+        }
+    }
+}
+
+/// Lays tasks out on their owner's calendar and capacity.
+struct Planner {
+    calendars: DeveloperCalendars,
+    profiles: HashMap<i64, CapacityProfile>,
+    default_profile: CapacityProfile,
+    mode: MoveMode,
+}
+
+impl Planner {
+    fn calendar(&self, task: &Task) -> &WorkCalendar {
+        self.calendars.for_owner(task.owner_id)
+    }
+
+    /// Last day of `task` when it starts on `start` (already a workday).
+    fn end_from(&self, task: &Task, start: NaiveDate) -> Result<NaiveDate, String> {
+        let calendar = self.calendar(task);
+        let hours = task.planned_hours.unwrap_or(0.0);
+        if self.mode == MoveMode::Hours && hours > ALLOC_EPSILON {
+            let profile = task
+                .owner_id
+                .and_then(|id| self.profiles.get(&id))
+                .unwrap_or(&self.default_profile);
+            return finish_date(calendar, profile, start, hours)
+                .ok_or_else(|| format!("任务「{}」在负责人可用工时内无法排完", task.name));
+        }
+
+        // Workday mode, and hours mode without planned hours: keep the current length.
+        let span = match (
+            task.planned_start.as_deref().and_then(parse_ymd),
+            task.planned_end.as_deref().and_then(parse_ymd),
+        ) {
+            (Some(s), Some(e)) => calendar.workdays_between(s, e).max(1),
+            _ => 1,
+        };
+        Ok(calendar.add_workdays(start, span - 1))
+    }
+}
+
+/// Move `task_id` to start on `new_start` (snapped to the owner's next workday) and work
+/// out its new end with `mode`.
+///
+/// With `ripple`, tasks that would now overlap are pushed to the next workday after it:
+/// successors through `task_dependencies`, and the same owner's later tasks. Pushes cascade.
+/// Completed and cancelled tasks never move. Returns every changed task, the dragged task
+/// first; nothing is saved unless `apply`, in which case all changes commit together.
+pub fn move_task(
+    conn: &Connection,
+    task_id: i64,
+    new_start: &str,
+    mode: MoveMode,
+    ripple: bool,
+    apply: bool,
+) -> Result<Vec<TaskMove>, String> {
+    let requested = parse_ymd(new_start).ok_or_else(|| format!("日期格式无效: {}", new_start))?;
+    let tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let by_id: HashMap<i64, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+    let task = *by_id.get(&task_id).ok_or_else(|| "Task not found".to_string())?;
+
+    holiday_service::ensure_holidays_cached(conn, &requested, &(requested + Duration::days(365)));
+    let developers = developer_repo::get_all(conn).map_err(|e| e.to_string())?;
+    let default_hours = settings_service::default_hours_per_day(conn);
+    let planner = Planner {
+        calendars: DeveloperCalendars::load(conn)?,
+        profiles: capacity_service::load_profiles(conn, &developers, default_hours)?,
+        default_profile: CapacityProfile::new(default_hours, Vec::new()),
+        mode,
+    };
+
+    // Current (start, end) of every dated task, updated as tasks move.
+    let mut dates: HashMap<i64, (NaiveDate, NaiveDate)> = tasks
+        .iter()
+        .filter_map(|t| {
+            let start = t.planned_start.as_deref().and_then(parse_ymd)?;
+            let end = t.planned_end.as_deref().and_then(parse_ymd).unwrap_or(start);
+            Some((t.id, (start, end.max(start))))
+        })
+        .collect();
+    let mut successors: HashMap<i64, Vec<i64>> = HashMap::new();
+    for dep in task_repo::get_all_dependencies(conn).map_err(|e| e.to_string())? {
+        successors.entry(dep.predecessor_id).or_default().push(dep.successor_id);
+    }
+
+    let start = planner.calendar(task).next_workday(requested);
+    dates.insert(task_id, (start, planner.end_from(task, start)?));
+    let mut order = vec![(task_id, "moved")];
+    let mut queue = VecDeque::from([task_id]);
+
+    let mut steps = 0;
+    while let Some(id) = queue.pop_front() {
+        if !ripple {
+            break;
+        }
+        steps += 1;
+        if steps > tasks.len() * tasks.len() + 1 {
+            return Err("连带顺延的任务过多，无法完成重排，请检查任务依赖是否存在循环".to_string());
+        }
+        let (moved_start, moved_end) = dates[&id];
+        let owner = by_id[&id].owner_id;
+
+        let dependents = successors.get(&id).into_iter().flatten().map(|&c| (c, "dependency"));
+        let followers = tasks
+            .iter()
+            .filter(|t| owner.is_some() && t.owner_id == owner && t.id != id)
+            .filter(|t| dates.get(&t.id).is_some_and(|&(s, _)| s >= moved_start))
+            .map(|t| (t.id, "same_owner"));
+        let candidates: Vec<(i64, &str)> = dependents.chain(followers).collect();
+
+        for (candidate_id, reason) in candidates {
+            let Some(candidate) = by_id.get(&candidate_id).copied() else { continue };
+            if candidate_id == task_id || !candidate.is_open() {
+                continue;
+            }
+            let Some(&(candidate_start, _)) = dates.get(&candidate_id) else { continue };
+            if candidate_start > moved_end {
+                continue;
+            }
+            let start = planner.calendar(candidate).next_workday(moved_end + Duration::days(1));
+            dates.insert(candidate_id, (start, planner.end_from(candidate, start)?));
+            if !order.iter().any(|(id, _)| *id == candidate_id) {
+                order.push((candidate_id, reason));
+            }
+            queue.push_back(candidate_id);
+        }
+    }
+
+    let changes: Vec<TaskMove> = order
+        .into_iter()
+        .filter_map(|(id, reason)| {
+            let t = by_id[&id];
+            let (s, e) = dates[&id];
+            let (new_start, new_end) = (fmt_ymd(s), fmt_ymd(e));
+            if t.planned_start.as_deref() == Some(new_start.as_str()) && t.planned_end.as_deref() == Some(new_end.as_str()) {
+                return None;
+            }
+            Some(TaskMove {
+                task_id: id,
+                task_name: t.name.clone(),
+                owner_id: t.owner_id,
+                old_start: t.planned_start.clone(),
+                old_end: t.planned_end.clone(),
+                new_start,
+                new_end,
+                reason: reason.to_string(),
+            })
+        })
+        .collect();
+
+    let mut pins: HashMap<i64, Vec<DailyAllocation>> = HashMap::new();
+    for allocation in task_repo::get_all_daily_allocations(conn).map_err(|e| e.to_string())? {
+        pins.entry(allocation.task_id).or_default().push(allocation);
+    }
+    let mut shifted_pins = Vec::new();
+    for change in &changes {
+        if let Some(task_pins) = pins.get(&change.task_id) {
+            let task = by_id[&change.task_id];
+            shifted_pins.push((change.task_id, shift_pins(planner.calendar(task), task, task_pins, &dates[&change.task_id])?));
+        }
+    }
+
+    if apply && !changes.is_empty() {
+        conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+        for change in &changes {
+            if let Err(e) = task_repo::set_dates(conn, change.task_id, &change.new_start, &change.new_end) {
+                conn.execute_batch("ROLLBACK").ok();
+                return Err(format!("Failed to move task {}: {}", change.task_id, e));
+            }
+        }
+        for (task_id, allocations) in &shifted_pins {
+            if let Err(e) = task_repo::set_daily_allocations(conn, *task_id, allocations) {
+                conn.execute_batch("ROLLBACK").ok();
+                return Err(format!("Failed to move task {}: {}", task_id, e));
+            }
+        }
+        conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+    }
+
+    Ok(changes)
+}
+
+/// Pinned days of a moved task, carried along: the pin on the n-th workday from the old
+/// start goes to the n-th workday from the new start, and a pin on a non-workday (such as
+/// weekend overtime) keeps its calendar-day offset from the start. A pin that would land
+/// outside the new range, or on the same day as another pin, fails the move rather than
+/// being dropped.
+fn shift_pins(
+    calendar: &WorkCalendar,
+    task: &Task,
+    pins: &[DailyAllocation],
+    &(new_start, new_end): &(NaiveDate, NaiveDate),
+) -> Result<Vec<DailyAllocation>, String> {
+    let old_start = task.planned_start.as_deref().and_then(parse_ymd);
+    let mut taken = HashSet::new();
+    pins.iter()
+        .map(|pin| {
+            let date = parse_ymd(&pin.date).zip(old_start).map(|(date, old_start)| {
+                if calendar.is_workday(&date) {
+                    calendar.add_workdays(new_start, calendar.workdays_between(old_start, date) - 1)
+                } else {
+                    new_start + (date - old_start)
+                }
+            });
+            match date {
+                Some(date) if date >= new_start && date <= new_end => {
+                    if !taken.insert(date) {
+                        return Err(format!(
+                            "任务「{}」的固定工时移动后有两天落在 {}，请先调整固定工时",
+                            task.name, fmt_ymd(date)
+                        ));
+                    }
+                    Ok(DailyAllocation { task_id: pin.task_id, date: fmt_ymd(date), hours: pin.hours })
+                }
+                _ => Err(format!(
+                    "任务「{}」在 {} 固定的工时移动后超出新的日期范围 {} ~ {}，请先调整固定工时",
+                    task.name, pin.date, new_start, new_end
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Upper bound on leveling adjustments per run.
+const MAX_LEVELING_STEPS: usize = 500;
+
+/// Task id -> planned (start, end).
+type DateRanges = HashMap<i64, (NaiveDate, NaiveDate)>;
+
+/// (task id, new start, new end, reason) produced by one leveling step.
+type Adjustment = (i64, NaiveDate, NaiveDate, &'static str);
+
+/// Final dates, the reason each task changed, and the overloads left over.
+type LevelingOutcome = (DateRanges, HashMap<i64, &'static str>, Vec<ScheduleViolation>);
+
+/// Leveling order: P0 = 0, P1 = 1, …; tasks without a priority come last and move first.
+fn priority_rank(task: &Task) -> u32 {
+    task.priority
+        .as_deref()
+        .map(|p| p.trim().to_uppercase())
+        .and_then(|p| p.strip_prefix('P').and_then(|n| n.parse().ok()))
+        .unwrap_or(u32::MAX)
+}
+
+/// Resolve developer-days over capacity in `[start_date, end_date]` (optionally one team)
+/// without saving anything.
+///
+/// For each overloaded day the lowest-priority task booked on it is extended by a workday
+/// when that stays within its float: before its earliest open successor starts and within
+/// its sprint. When no task on the day has float left, the lowest-priority one that hasn't
+/// started is shifted to the next workday, pushing its successors along. Tasks with pinned
+/// daily hours, in-progress tasks (which may only be extended) and closed tasks are
+/// otherwise left alone. Overloads that can't be resolved are returned in `remaining`.
+pub fn level_resources(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    team_id: Option<i64>,
+) -> Result<LevelingPlan, String> {
+    let (range_start, range_end) = parse_date_range(start_date, end_date)?;
+    let developers = team_service::active_developers(conn, team_id)?;
+    holiday_service::ensure_holidays_cached(conn, &range_start, &(range_end + Duration::days(365)));
+
+    let tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let by_id: HashMap<i64, &Task> = tasks.iter().map(|t| (t.id, t)).collect();
+    let original: DateRanges = tasks
+        .iter()
+        .filter_map(|t| {
+            let start = t.planned_start.as_deref().and_then(parse_ymd)?;
+            let end = t.planned_end.as_deref().and_then(parse_ymd)?;
+            (end >= start).then_some((t.id, (start, end)))
+        })
+        .collect();
+    let pinned: HashSet<i64> = assignment_service::load_pinned_hours(conn)?.into_keys().collect();
+    let mut successors: HashMap<i64, Vec<i64>> = HashMap::new();
+    for dep in task_repo::get_all_dependencies(conn).map_err(|e| e.to_string())? {
+        successors.entry(dep.predecessor_id).or_default().push(dep.successor_id);
+    }
+    let sprint_ends: HashMap<i64, NaiveDate> = sprint_repo::get_all_sprints(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|s| Some((s.id, parse_ymd(s.end_date.as_deref()?)?)))
+        .collect();
+    let calendars = DeveloperCalendars::load(conn)?;
+
+    let movable = |id: i64| by_id.get(&id).is_some_and(|t| t.is_open()) && !pinned.contains(&id);
+    let started = |id: i64| by_id[&id].status.as_deref() == Some("进行中");
+    // Latest allowed end: before the earliest open successor, within the sprint.
+    let limit = |task: &Task, dates: &DateRanges| {
+        let before_successor = successors
+            .get(&task.id)
+            .into_iter()
+            .flatten()
+            .filter(|id| by_id.get(id).is_some_and(|t| t.is_open()))
+            .filter_map(|id| dates.get(id).map(|&(s, _)| s - Duration::days(1)))
+            .min();
+        let sprint_end = task.sprint_id.and_then(|id| sprint_ends.get(&id).copied());
+        match (before_successor, sprint_end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    };
+
+    // Work on the live tables inside a savepoint so the workload engine sees each step,
+    // then roll everything back.
+    conn.execute_batch("SAVEPOINT leveling").map_err(|e| e.to_string())?;
+    let result = (|| -> Result<LevelingOutcome, String> {
+        let mut dates = original.clone();
+        let mut reasons: HashMap<i64, &'static str> = HashMap::new();
+        let mut unresolved: HashSet<(i64, String)> = HashSet::new();
+
+        for _ in 0..MAX_LEVELING_STEPS {
+            let mut acted = false;
+            'developers: for dev in &developers {
+                let days = schedule_service::get_developer_workload(conn, dev.id, start_date, end_date, false)?;
+                for day in days.iter().filter(|d| d.allocated_hours > d.max_hours + ALLOC_EPSILON) {
+                    if unresolved.contains(&(dev.id, day.date.clone())) {
+                        continue;
+                    }
+                    let mut candidates: Vec<&Task> = day
+                        .tasks
+                        .iter()
+                        .filter(|w| movable(w.task_id) && dates.contains_key(&w.task_id))
+                        .map(|w| by_id[&w.task_id])
+                        .collect();
+                    candidates.sort_by_key(|t| (std::cmp::Reverse(priority_rank(t)), std::cmp::Reverse(t.id)));
+
+                    let mut changed: Vec<Adjustment> = Vec::new();
+                    for task in &candidates {
+                        let (start, end) = dates[&task.id];
+                        let extended = calendars.for_owner(task.owner_id).next_workday(end + Duration::days(1));
+                        let within = match limit(task, &dates) {
+                            Some(l) => extended <= l,
+                            None => true,
+                        };
+                        if within {
+                            changed.push((task.id, start, extended, "extended"));
+                            break;
+                        }
+                    }
+                    if changed.is_empty() {
+                        if let Some(task) = candidates.iter().find(|t| !started(t.id)) {
+                            changed = shift_with_successors(task.id, &dates, &by_id, &successors, &calendars, &movable, &started);
+                        }
+                    }
+                    if changed.is_empty() {
+                        unresolved.insert((dev.id, day.date.clone()));
+                        continue;
+                    }
+                    for (id, start, end, reason) in changed {
+                        task_repo::set_dates(conn, id, &fmt_ymd(start), &fmt_ymd(end)).map_err(|e| e.to_string())?;
+                        dates.insert(id, (start, end));
+                        reasons.insert(id, reason);
+                    }
+                    acted = true;
+                    break 'developers;
+                }
+            }
+            if !acted {
+                break;
+            }
+        }
+
+        let mut remaining = Vec::new();
+        for dev in &developers {
+            remaining.extend(conflict_service::overload_violations(conn, dev, start_date, end_date)?);
+        }
+        Ok((dates, reasons, remaining))
+    })();
+    conn.execute_batch("ROLLBACK TO leveling; RELEASE leveling").map_err(|e| e.to_string())?;
+    let (dates, reasons, remaining) = result?;
+
+    let mut changes: Vec<TaskMove> = dates
+        .iter()
+        .filter(|(id, range)| original.get(id) != Some(range))
+        .map(|(&id, &(start, end))| {
+            let t = by_id[&id];
+            TaskMove {
+                task_id: id,
+                task_name: t.name.clone(),
+                owner_id: t.owner_id,
+                old_start: t.planned_start.clone(),
+                old_end: t.planned_end.clone(),
+                new_start: fmt_ymd(start),
+                new_end: fmt_ymd(end),
+                reason: reasons.get(&id).copied().unwrap_or("shifted").to_string(),
+            }
+        })
+        .collect();
+    changes.sort_by_key(|c| c.task_id);
+    let updates = changes
+        .iter()
+        .map(|c| UpdateTaskDto {
+            id: c.task_id,
+            external_id: None,
+            task_type: None,
+            name: None,
+            description: None,
+            owner_id: None,
+            sprint_id: None,
+            priority: None,
+            planned_start: Some(c.new_start.clone()),
+            planned_end: Some(c.new_end.clone()),
+            planned_start_time: None,
+            planned_end_time: None,
+            planned_hours: None,
+            parent_task_id: None,
+            parent_number: None,
+            parent_name: None,
+            status: None,
+            co_owner_ids: None,
+            required_skills: None,
+        })
+        .collect();
+    Ok(LevelingPlan { changes, updates, remaining })
+}
+
+/// Shift `task_id` one workday later keeping its workday span, and push successors that
+/// would then overlap. Empty when a successor in the way can't move.
+fn shift_with_successors(
+    task_id: i64,
+    dates: &DateRanges,
+    by_id: &HashMap<i64, &Task>,
+    successors: &HashMap<i64, Vec<i64>>,
+    calendars: &DeveloperCalendars,
+    movable: &dyn Fn(i64) -> bool,
+    started: &dyn Fn(i64) -> bool,
+) -> Vec<Adjustment> {
+    let shift = |id: i64, not_before: NaiveDate| {
+        let calendar = calendars.for_owner(by_id[&id].owner_id);
+        let (start, end) = dates[&id];
+        let span = calendar.workdays_between(start, end).max(1);
+        let new_start = calendar.next_workday(not_before);
+        (new_start, calendar.add_workdays(new_start, span - 1))
+    };
+
+    let (start, _) = dates[&task_id];
+    let (new_start, new_end) = shift(task_id, start + Duration::days(1));
+    let mut changed = vec![(task_id, new_start, new_end, "shifted")];
+    let mut queue = VecDeque::from([(task_id, new_end)]);
+    while let Some((id, end)) = queue.pop_front() {
+        for &succ in successors.get(&id).into_iter().flatten() {
+            let Some(&(succ_start, _)) = dates.get(&succ) else { continue };
+            if !by_id.get(&succ).is_some_and(|t| t.is_open()) || succ_start > end {
+                continue;
+            }
+            if succ == task_id || !movable(succ) || started(succ) || changed.len() > dates.len() {
+                return Vec::new();
+            }
+            let (s, e) = shift(succ, end + Duration::days(1));
+            changed.retain(|(id, ..)| *id != succ);
+            changed.push((succ, s, e, "dependency"));
+            queue.push_back((succ, e));
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::services::task_service;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute_batch(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2026-10-01', 1, 0, '国庆节', 2026);
+             INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2027-01-01', 1, 0, '元旦', 2027);
+             INSERT INTO developers (name, max_hours_per_day) VALUES ('张三', 4);
+             INSERT INTO developers (name) VALUES ('李四');
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('A', 1, '2026-09-21', '2026-09-23', 12);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('B', 1, '2026-09-28', '2026-09-29', 8);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('C', 2, '2026-09-28', '2026-09-28', 8);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours, status) VALUES ('D', 1, '2026-09-30', '2026-09-30', 4, '已完成');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn keeps_workday_span_and_skips_holidays() {
+        let conn = setup_conn();
+        // Three workdays from Wed 09-30: the 10-01 holiday is skipped.
+        let changes = move_task(&conn, 1, "2026-09-30", MoveMode::Workdays, false, false).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].new_start.as_str(), changes[0].new_end.as_str()), ("2026-09-30", "2026-10-05"));
+        // Preview only.
+        let a = task_repo::get_by_id(&conn, 1).unwrap().unwrap();
+        assert_eq!(a.planned_start.as_deref(), Some("2026-09-21"));
+
+        // 12h at 4h/day also takes three workdays; dropping on Saturday snaps to Monday.
+        let changes = move_task(&conn, 1, "2026-09-26", MoveMode::Hours, false, false).unwrap();
+        assert_eq!((changes[0].new_start.as_str(), changes[0].new_end.as_str()), ("2026-09-28", "2026-09-30"));
+    }
+
+    #[test]
+    fn ripple_pushes_successors_and_same_owner_tasks() {
+        let conn = setup_conn();
+        task_service::add_dependency(&conn, 1, 3).unwrap();
+        assert!(task_service::add_dependency(&conn, 3, 1).is_err());
+
+        let changes = move_task(&conn, 1, "2026-09-25", MoveMode::Workdays, true, true).unwrap();
+        let summary: Vec<(i64, &str, &str, &str)> = changes
+            .iter()
+            .map(|c| (c.task_id, c.new_start.as_str(), c.new_end.as_str(), c.reason.as_str()))
+            .collect();
+        // A: Fri 09-25 .. Tue 09-29; C waits for A, B follows A on the same owner;
+        // the completed task D stays put.
+        assert_eq!(summary, vec![
+            (1, "2026-09-25", "2026-09-29", "moved"),
+            (3, "2026-09-30", "2026-09-30", "dependency"),
+            (2, "2026-09-30", "2026-10-02", "same_owner"),
+        ]);
+        let b = task_repo::get_by_id(&conn, 2).unwrap().unwrap();
+        assert_eq!(b.planned_end.as_deref(), Some("2026-10-02"));
+    }
+
+    #[test]
+    fn pinned_days_move_with_the_task() {
+        let conn = setup_conn();
+        conn.execute("INSERT INTO task_daily_allocations (task_id, date, hours) VALUES (1, '2026-09-22', 3)", [])
+            .unwrap();
+        // The pin on A's second workday lands on the second workday after the move,
+        // past the 10-01 holiday.
+        move_task(&conn, 1, "2026-09-30", MoveMode::Workdays, false, true).unwrap();
+        let pins = task_repo::get_daily_allocations(&conn, 1).unwrap();
+        assert_eq!(pins.iter().map(|p| (p.date.as_str(), p.hours)).collect::<Vec<_>>(), vec![("2026-10-02", 3.0)]);
+
+        // A pin that no longer fits the task refuses the move.
+        conn.execute("INSERT INTO task_daily_allocations (task_id, date, hours) VALUES (1, '2026-10-09', 1)", [])
+            .unwrap();
+        assert!(move_task(&conn, 1, "2026-10-12", MoveMode::Workdays, false, true).unwrap_err().contains("2026-10-09"));
+        assert_eq!(task_repo::get_by_id(&conn, 1).unwrap().unwrap().planned_start.as_deref(), Some("2026-09-30"));
+    }
+
+    #[test]
+    fn weekend_pins_keep_their_day_offset() {
+        let conn = setup_conn();
+        // Mon 11-02 .. Sat 11-07 with a Friday pin and Saturday overtime.
+        conn.execute_batch(
+            "INSERT INTO tasks (id, name, owner_id, planned_start, planned_end, planned_hours) \
+                 VALUES (5, 'E', 2, '2026-11-02', '2026-11-07', 20);
+             INSERT INTO task_daily_allocations (task_id, date, hours) VALUES (5, '2026-11-06', 2);
+             INSERT INTO task_daily_allocations (task_id, date, hours) VALUES (5, '2026-11-07', 3);",
+        )
+        .unwrap();
+        move_task(&conn, 5, "2026-11-03", MoveMode::Workdays, false, true).unwrap();
+        let mut pins: Vec<(String, f64)> =
+            task_repo::get_daily_allocations(&conn, 5).unwrap().into_iter().map(|p| (p.date, p.hours)).collect();
+        pins.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(pins, vec![("2026-11-08".to_string(), 3.0), ("2026-11-09".to_string(), 2.0)]);
+    }
+
+    #[test]
+    fn leveling_extends_within_float_then_shifts_low_priority_work() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            "INSERT INTO developers (name) VALUES ('王五');
+             INSERT INTO tasks (id, name, owner_id, priority, planned_start, planned_end, planned_hours) VALUES
+                (10, 'high', 3, 'P0', '2026-11-02', '2026-11-02', 8),
+                (11, 'low', 3, 'P2', '2026-11-02', '2026-11-02', 8),
+                (12, 'pinned', 3, 'P3', '2026-11-04', '2026-11-04', 4),
+                (13, 'mid', 3, 'P1', '2026-11-04', '2026-11-04', 8),
+                (14, 'next', 3, 'P1', '2026-11-05', '2026-11-05', 4);
+             INSERT INTO task_daily_allocations (task_id, date, hours) VALUES (12, '2026-11-04', 4);
+             INSERT INTO task_dependencies (predecessor_id, successor_id) VALUES (13, 14);",
+        )
+        .unwrap();
+
+        let plan = level_resources(&conn, "2026-11-02", "2026-11-06", None).unwrap();
+        let summary: Vec<(i64, &str, &str, &str)> = plan
+            .changes
+            .iter()
+            .map(|c| (c.task_id, c.new_start.as_str(), c.new_end.as_str(), c.reason.as_str()))
+            .collect();
+        // `low` has float and is stretched; `mid` is boxed in by `next`, so it is shifted
+        // and pushes `next`. The pinned task and the P0 task stay put.
+        assert_eq!(summary, vec![
+            (11, "2026-11-02", "2026-11-03", "extended"),
+            (13, "2026-11-05", "2026-11-05", "shifted"),
+            (14, "2026-11-06", "2026-11-06", "dependency"),
+        ]);
+        assert!(plan.remaining.is_empty());
+        assert_eq!(plan.updates.len(), 3);
+        assert_eq!(plan.updates[1].planned_start.as_deref(), Some("2026-11-05"));
+
+        // Preview only.
+        let mid = task_repo::get_by_id(&conn, 13).unwrap().unwrap();
+        assert_eq!(mid.planned_start.as_deref(), Some("2026-11-04"));
+    }
+}
@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use chrono::Duration;
+use crate::db::{developer_repo, scenario_repo, task_repo};
+use crate::models::developer::CreateDeveloperDto;
+use crate::models::scenario::{
+    CreateScenarioDeveloperDto, CreateScenarioDto, Scenario, ScenarioComparison, ScenarioDeveloper, ScenarioSummary,
+    ScenarioTask, ScenarioTaskDiff, ScenarioTaskEdit,
+};
+use crate::models::task::{Task, TaskFilter, TaskMove};
+use crate::services::assignment_service::ALLOC_EPSILON;
+use crate::services::date_service::{parse_date_range, parse_ymd};
+use crate::services::reschedule_service::{self, MoveMode};
+use crate::services::{capacity_service, holiday_service, schedule_service, team_service};
+
+/// Scenario developer id -> id of the developer row standing in for it.
+type DeveloperMap = HashMap<i64, i64>;
+
+fn get_scenario(conn: &Connection, id: i64) -> Result<Scenario, String> {
+    scenario_repo::get_by_id(conn, id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "方案不存在".to_string())
+}
+
+fn editable_scenario(conn: &Connection, id: i64) -> Result<Scenario, String> {
+    let scenario = get_scenario(conn, id)?;
+    if scenario.applied_at.is_some() {
+        return Err(format!("方案「{}」已应用，不能再修改", scenario.name));
+    }
+    Ok(scenario)
+}
+
+pub fn list_scenarios(conn: &Connection) -> Result<Vec<Scenario>, String> {
+    scenario_repo::get_all(conn).map_err(|e| e.to_string())
+}
+
+pub fn create_scenario(conn: &Connection, dto: &CreateScenarioDto) -> Result<i64, String> {
+    if dto.name.trim().is_empty() {
+        return Err("方案名称不能为空".to_string());
+    }
+    scenario_repo::create(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn delete_scenario(conn: &Connection, id: i64) -> Result<(), String> {
+    scenario_repo::delete(conn, id).map_err(|e| e.to_string())
+}
+
+pub fn list_tasks(conn: &Connection, scenario_id: i64) -> Result<Vec<ScenarioTask>, String> {
+    scenario_repo::get_tasks(conn, scenario_id).map_err(|e| e.to_string())
+}
+
+pub fn list_developers(conn: &Connection, scenario_id: i64) -> Result<Vec<ScenarioDeveloper>, String> {
+    scenario_repo::get_developers(conn, scenario_id).map_err(|e| e.to_string())
+}
+
+pub fn add_developer(conn: &Connection, dto: &CreateScenarioDeveloperDto) -> Result<i64, String> {
+    editable_scenario(conn, dto.scenario_id)?;
+    let name = dto.name.trim();
+    if name.is_empty() {
+        return Err("成员名称不能为空".to_string());
+    }
+    if dto.max_hours_per_day.is_some_and(|h| h < 0.0) {
+        return Err("每日工时不能为负数".to_string());
+    }
+    let taken = developer_repo::find_by_name(conn, name).map_err(|e| e.to_string())?.is_some()
+        || list_developers(conn, dto.scenario_id)?.iter().any(|d| d.name == name);
+    if taken {
+        return Err(format!("成员「{}」已存在", name));
+    }
+    scenario_repo::create_developer(conn, dto).map_err(|e| e.to_string())
+}
+
+pub fn remove_developer(conn: &Connection, scenario_id: i64, developer_id: i64) -> Result<(), String> {
+    editable_scenario(conn, scenario_id)?;
+    let developer = list_developers(conn, scenario_id)?
+        .into_iter()
+        .find(|d| d.id == developer_id)
+        .ok_or_else(|| "方案成员不存在".to_string())?;
+    // Its tasks have no live owner to fall back to; applying would unassign them.
+    let assigned = list_tasks(conn, scenario_id)?
+        .iter()
+        .filter(|t| t.scenario_developer_id == Some(developer_id))
+        .count();
+    if assigned > 0 {
+        return Err(format!("成员「{}」仍负责方案中的 {} 个任务，请先改派或重置这些任务", developer.name, assigned));
+    }
+    scenario_repo::delete_developer(conn, developer_id).map_err(|e| e.to_string())
+}
+
+/// The scenario's row for a task, starting from the live values on first edit.
+fn current_row(conn: &Connection, scenario_id: i64, task_id: i64) -> Result<ScenarioTask, String> {
+    if let Some(row) = list_tasks(conn, scenario_id)?.into_iter().find(|t| t.task_id == task_id) {
+        return Ok(row);
+    }
+    let task = task_repo::get_by_id(conn, task_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Task not found".to_string())?;
+    Ok(ScenarioTask {
+        scenario_id,
+        task_id,
+        task_name: task.name,
+        owner_id: task.owner_id,
+        scenario_developer_id: None,
+        planned_start: task.planned_start.clone(),
+        planned_end: task.planned_end.clone(),
+        planned_hours: task.planned_hours,
+        base_owner_id: task.owner_id,
+        base_start: task.planned_start,
+        base_end: task.planned_end,
+        base_hours: task.planned_hours,
+    })
+}
+
+/// Which of owner, start, end and hours a scenario row changes from its base.
+fn changed_fields(row: &ScenarioTask) -> [bool; 4] {
+    [
+        row.scenario_developer_id.is_some() || row.owner_id != row.base_owner_id,
+        row.planned_start != row.base_start,
+        row.planned_end != row.base_end,
+        row.planned_hours != row.base_hours,
+    ]
+}
+
+/// Names of tasks edited live, since the scenario first edited them, in a field the
+/// scenario also changes. Applying would overwrite those edits.
+fn drifted_tasks(conn: &Connection, scenario_id: i64) -> Result<Vec<String>, String> {
+    let mut drifted = Vec::new();
+    for row in list_tasks(conn, scenario_id)? {
+        let Some(live) = task_repo::get_by_id(conn, row.task_id).map_err(|e| e.to_string())? else { continue };
+        let moved = [
+            live.owner_id != row.base_owner_id,
+            live.planned_start != row.base_start,
+            live.planned_end != row.base_end,
+            live.planned_hours != row.base_hours,
+        ];
+        if changed_fields(&row).iter().zip(moved).any(|(&changed, moved)| changed && moved) {
+            drifted.push(row.task_name);
+        }
+    }
+    Ok(drifted)
+}
+
+/// Edit or reassign a task inside a scenario; the live task is untouched.
+pub fn update_task(conn: &Connection, edit: &ScenarioTaskEdit) -> Result<ScenarioTask, String> {
+    editable_scenario(conn, edit.scenario_id)?;
+    let mut row = current_row(conn, edit.scenario_id, edit.task_id)?;
+
+    if let Some(dev_id) = edit.scenario_developer_id {
+        if !list_developers(conn, edit.scenario_id)?.iter().any(|d| d.id == dev_id) {
+            return Err("方案成员不存在".to_string());
+        }
+        row.scenario_developer_id = Some(dev_id);
+        row.owner_id = None;
+    } else if let Some(owner_id) = edit.owner_id {
+        developer_repo::get_by_id(conn, owner_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Developer not found".to_string())?;
+        row.owner_id = Some(owner_id);
+        row.scenario_developer_id = None;
+    }
+    for date in [&edit.planned_start, &edit.planned_end].into_iter().flatten() {
+        parse_ymd(date).ok_or_else(|| format!("日期格式无效: {}", date))?;
+    }
+    if edit.planned_start.is_some() {
+        row.planned_start = edit.planned_start.clone();
+    }
+    if edit.planned_end.is_some() {
+        row.planned_end = edit.planned_end.clone();
+    }
+    if let (Some(start), Some(end)) = (&row.planned_start, &row.planned_end) {
+        if end < start {
+            return Err("结束日期不能早于开始日期".to_string());
+        }
+    }
+    if let Some(hours) = edit.planned_hours {
+        if hours < 0.0 {
+            return Err("计划工时不能为负数".to_string());
+        }
+        row.planned_hours = Some(hours);
+    }
+
+    scenario_repo::upsert_task(conn, &row).map_err(|e| e.to_string())?;
+    Ok(row)
+}
+
+/// Discard a scenario's edits to one task.
+pub fn reset_task(conn: &Connection, scenario_id: i64, task_id: i64) -> Result<(), String> {
+    editable_scenario(conn, scenario_id)?;
+    scenario_repo::delete_task(conn, scenario_id, task_id).map_err(|e| e.to_string())
+}
+
+/// Write a scenario over the live tables: create its developers and set the planning
+/// fields its tasks change; fields it left alone keep their live value. Callers own
+/// the surrounding transaction.
+fn materialize(conn: &Connection, scenario_id: i64) -> Result<DeveloperMap, String> {
+    let mut developers = DeveloperMap::new();
+    for dev in list_developers(conn, scenario_id)? {
+        if developer_repo::find_by_name(conn, &dev.name).map_err(|e| e.to_string())?.is_some() {
+            return Err(format!("成员「{}」已存在", dev.name));
+        }
+        let dto = CreateDeveloperDto {
+            name: dev.name.clone(),
+            roles: None,
+            skills: None,
+            skill_levels: None,
+            max_hours_per_day: Some(dev.max_hours_per_day),
+            avatar_color: None,
+            calendar_id: None,
+        };
+        let id = developer_repo::create(conn, &dto).map_err(|e| e.to_string())?;
+        developers.insert(dev.id, id);
+    }
+    for row in list_tasks(conn, scenario_id)? {
+        let Some(live) = task_repo::get_by_id(conn, row.task_id).map_err(|e| e.to_string())? else { continue };
+        let [owner, start, end, hours] = changed_fields(&row);
+        let owner_id = match row.scenario_developer_id {
+            Some(dev_id) => developers.get(&dev_id).copied(),
+            None if owner => row.owner_id,
+            None => live.owner_id,
+        };
+        task_repo::set_plan(
+            conn,
+            row.task_id,
+            owner_id,
+            if start { row.planned_start.as_deref() } else { live.planned_start.as_deref() },
+            if end { row.planned_end.as_deref() } else { live.planned_end.as_deref() },
+            if hours { row.planned_hours } else { live.planned_hours },
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(developers)
+}
+
+/// Run `f` against the live tables with the scenario laid over them, then undo it.
+/// `None` runs `f` on the live plan as is.
+fn in_sandbox<T>(
+    conn: &Connection,
+    scenario_id: Option<i64>,
+    f: impl FnOnce(&DeveloperMap) -> Result<T, String>,
+) -> Result<T, String> {
+    let Some(id) = scenario_id else {
+        return f(&DeveloperMap::new());
+    };
+    conn.execute_batch("SAVEPOINT scenario_sandbox").map_err(|e| e.to_string())?;
+    let result = materialize(conn, id).and_then(|developers| f(&developers));
+    conn.execute_batch("ROLLBACK TO scenario_sandbox; RELEASE scenario_sandbox")
+        .map_err(|e| e.to_string())?;
+    result
+}
+
+/// Run the rescheduler inside a scenario: move a task (rippling to successors and the
+/// owner's later tasks, as in the live plan) and store every resulting change as a
+/// scenario edit.
+pub fn move_task(
+    conn: &Connection,
+    scenario_id: i64,
+    task_id: i64,
+    new_start: &str,
+    mode: MoveMode,
+    ripple: bool,
+) -> Result<Vec<TaskMove>, String> {
+    editable_scenario(conn, scenario_id)?;
+    // Fill the holiday cache outside the sandbox so it isn't rolled back with it.
+    let requested = parse_ymd(new_start).ok_or_else(|| format!("日期格式无效: {}", new_start))?;
+    holiday_service::ensure_holidays_cached(conn, &requested, &(requested + Duration::days(365)));
+    let (mut changes, mut rows) = in_sandbox(conn, Some(scenario_id), |developers| {
+        let changes = reschedule_service::move_task(conn, task_id, new_start, mode, ripple, false)?;
+        let mut rows = Vec::new();
+        for change in &changes {
+            let task = task_repo::get_by_id(conn, change.task_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Task not found".to_string())?;
+            let scenario_developer_id = developers
+                .iter()
+                .find(|(_, &real)| Some(real) == task.owner_id)
+                .map(|(&dev_id, _)| dev_id);
+            rows.push(ScenarioTask {
+                scenario_id,
+                task_id: task.id,
+                task_name: task.name,
+                owner_id: if scenario_developer_id.is_some() { None } else { task.owner_id },
+                scenario_developer_id,
+                planned_start: Some(change.new_start.clone()),
+                planned_end: Some(change.new_end.clone()),
+                planned_hours: task.planned_hours,
+                base_owner_id: None,
+                base_start: None,
+                base_end: None,
+                base_hours: None,
+            });
+        }
+        Ok((changes, rows))
+    })?;
+    // Bases are the live values, read now the sandbox is gone.
+    for row in &mut rows {
+        let prior = current_row(conn, scenario_id, row.task_id)?;
+        row.base_owner_id = prior.base_owner_id;
+        row.base_start = prior.base_start;
+        row.base_end = prior.base_end;
+        row.base_hours = prior.base_hours;
+    }
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    for row in &rows {
+        if let Err(e) = scenario_repo::upsert_task(conn, row) {
+            conn.execute_batch("ROLLBACK").ok();
+            return Err(format!("Failed to move task {}: {}", row.task_id, e));
+        }
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+
+    // Owners standing in for scenario developers only existed inside the sandbox.
+    for (change, row) in changes.iter_mut().zip(&rows) {
+        change.owner_id = row.owner_id;
+    }
+    Ok(changes)
+}
+
+/// Totals of one plan over `[start_date, end_date]`, plus its tasks for diffing.
+fn summarize(
+    conn: &Connection,
+    scenario_id: Option<i64>,
+    name: String,
+    start_date: &str,
+    end_date: &str,
+) -> Result<(ScenarioSummary, Vec<Task>), String> {
+    let tasks = task_repo::get_all(conn, &TaskFilter::default()).map_err(|e| e.to_string())?;
+    let finish_date = tasks
+        .iter()
+        .filter(|t| t.is_open())
+        .filter_map(|t| t.planned_end.clone())
+        .max();
+
+    let mut summary = ScenarioSummary {
+        scenario_id,
+        name,
+        finish_date,
+        capacity_hours: 0.0,
+        allocated_hours: 0.0,
+        overload_days: 0,
+        overload_hours: 0.0,
+        idle_days: 0,
+        idle_hours: 0.0,
+    };
+    for dev in team_service::active_developers(conn, None)? {
+        let workload = schedule_service::get_developer_workload(conn, dev.id, start_date, end_date, false)?;
+        let mut booked: HashMap<String, f64> = HashMap::new();
+        for day in &workload {
+            summary.allocated_hours += day.allocated_hours;
+            booked.insert(day.date.clone(), day.allocated_hours);
+            if day.allocated_hours > day.max_hours + ALLOC_EPSILON {
+                summary.overload_days += 1;
+                summary.overload_hours += day.allocated_hours - day.max_hours;
+            }
+        }
+        for day in capacity_service::get_effective_capacity(conn, dev.id, start_date, end_date)? {
+            if !day.is_workday {
+                continue;
+            }
+            let allocated = booked.get(&day.date).copied().unwrap_or(0.0);
+            summary.capacity_hours += day.hours;
+            summary.idle_hours += (day.hours - allocated).max(0.0);
+            if day.hours > ALLOC_EPSILON && allocated <= ALLOC_EPSILON {
+                summary.idle_days += 1;
+            }
+        }
+    }
+    Ok((summary, tasks))
+}
+
+/// Compare two plans over a date range; `None` on either side is the live plan.
+///
+/// Each side reports its finish date, overload and idle capacity; `tasks` lists the
+/// tasks whose owner or dates differ between them.
+pub fn compare(
+    conn: &Connection,
+    left_id: Option<i64>,
+    right_id: Option<i64>,
+    start_date: &str,
+    end_date: &str,
+) -> Result<ScenarioComparison, String> {
+    let (start, end) = parse_date_range(start_date, end_date)?;
+    // Fill the holiday cache outside the sandboxes so it isn't rolled back with them.
+    holiday_service::ensure_holidays_cached(conn, &start, &(end + Duration::days(365)));
+
+    let side = |id: Option<i64>| -> Result<(ScenarioSummary, Vec<Task>), String> {
+        let name = match id {
+            Some(id) => get_scenario(conn, id)?.name,
+            None => "当前计划".to_string(),
+        };
+        in_sandbox(conn, id, |_| summarize(conn, id, name, start_date, end_date))
+    };
+    let (left, left_tasks) = side(left_id)?;
+    let (right, right_tasks) = side(right_id)?;
+
+    let right_by_id: HashMap<i64, &Task> = right_tasks.iter().map(|t| (t.id, t)).collect();
+    let tasks = left_tasks
+        .iter()
+        .filter_map(|l| {
+            let r = right_by_id.get(&l.id)?;
+            if l.owner_name == r.owner_name && l.planned_start == r.planned_start && l.planned_end == r.planned_end {
+                return None;
+            }
+            let end_delta_days = match (
+                l.planned_end.as_deref().and_then(parse_ymd),
+                r.planned_end.as_deref().and_then(parse_ymd),
+            ) {
+                (Some(a), Some(b)) => Some((b - a).num_days()),
+                _ => None,
+            };
+            Some(ScenarioTaskDiff {
+                task_id: l.id,
+                task_name: l.name.clone(),
+                left_owner_name: l.owner_name.clone(),
+                right_owner_name: r.owner_name.clone(),
+                left_start: l.planned_start.clone(),
+                right_start: r.planned_start.clone(),
+                left_end: l.planned_end.clone(),
+                right_end: r.planned_end.clone(),
+                end_delta_days,
+            })
+        })
+        .collect();
+
+    Ok(ScenarioComparison {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        left,
+        right,
+        tasks,
+    })
+}
+
+/// Apply a scenario to the live plan in one transaction: its developers are created
+/// and its task edits written. Refused when a task it changes was edited live in the
+/// same field since. Returns the number of tasks updated.
+pub fn apply_scenario(conn: &Connection, id: i64) -> Result<usize, String> {
+    editable_scenario(conn, id)?;
+    let drifted = drifted_tasks(conn, id)?;
+    if !drifted.is_empty() {
+        return Err(format!(
+            "以下任务在方案编辑后已被修改：{}。请重置这些任务后再应用",
+            drifted.join("、")
+        ));
+    }
+    let count = list_tasks(conn, id)?.len();
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = materialize(conn, id)
+        .and_then(|_| scenario_repo::mark_applied(conn, id).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(count)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            Err(format!("应用方案失败: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute_batch(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2026-10-01', 1, 0, '国庆节', 2026);
+             INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2027-01-01', 1, 0, '元旦', 2027);
+             INSERT INTO developers (name) VALUES ('张三');
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('A', 1, '2026-11-02', '2026-11-03', 16);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('B', 1, '2026-11-02', '2026-11-03', 16);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) VALUES ('C', 1, '2026-11-04', '2026-11-04', 8);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn live_plan(conn: &Connection, id: i64) -> (Option<i64>, Option<String>, Option<String>) {
+        let t = task_repo::get_by_id(conn, id).unwrap().unwrap();
+        (t.owner_id, t.planned_start, t.planned_end)
+    }
+
+    #[test]
+    fn contractor_scenario_is_sandboxed_until_applied() {
+        let conn = setup_conn();
+        let id = create_scenario(&conn, &CreateScenarioDto { name: "加外包".to_string(), description: None }).unwrap();
+        let contractor = add_developer(
+            &conn,
+            &CreateScenarioDeveloperDto { scenario_id: id, name: "外包A".to_string(), max_hours_per_day: None },
+        )
+        .unwrap();
+        assert!(add_developer(
+            &conn,
+            &CreateScenarioDeveloperDto { scenario_id: id, name: "张三".to_string(), max_hours_per_day: None },
+        )
+        .is_err());
+
+        update_task(&conn, &ScenarioTaskEdit {
+            scenario_id: id,
+            task_id: 2,
+            owner_id: None,
+            scenario_developer_id: Some(contractor),
+            planned_start: None,
+            planned_end: None,
+            planned_hours: None,
+        })
+        .unwrap();
+        // A scheduler run inside the scenario: C is pushed after A, B stays with the contractor.
+        let moves = move_task(&conn, id, 1, "2026-11-04", MoveMode::Workdays, true).unwrap();
+        let summary: Vec<(i64, &str, &str)> =
+            moves.iter().map(|m| (m.task_id, m.new_start.as_str(), m.new_end.as_str())).collect();
+        assert_eq!(summary, vec![(1, "2026-11-04", "2026-11-05"), (3, "2026-11-06", "2026-11-06")]);
+
+        // Nothing leaked into the live tables.
+        assert_eq!(live_plan(&conn, 2), (Some(1), Some("2026-11-02".to_string()), Some("2026-11-03".to_string())));
+        assert_eq!(live_plan(&conn, 3).1.as_deref(), Some("2026-11-04"));
+        assert_eq!(developer_repo::get_all(&conn).unwrap().len(), 1);
+
+        let comparison = compare(&conn, None, Some(id), "2026-10-26", "2026-11-06").unwrap();
+        assert!(comparison.left.overload_days > 0);
+        assert_eq!(comparison.right.overload_days, 0);
+        assert_eq!((comparison.left.idle_days, comparison.right.idle_days), (7, 15));
+        assert_eq!(comparison.right.finish_date.as_deref(), Some("2026-11-06"));
+        let b = comparison.tasks.iter().find(|t| t.task_id == 2).unwrap();
+        assert_eq!(b.right_owner_name.as_deref(), Some("外包A"));
+        let c = comparison.tasks.iter().find(|t| t.task_id == 3).unwrap();
+        assert_eq!(c.end_delta_days, Some(2));
+
+        assert_eq!(apply_scenario(&conn, id).unwrap(), 3);
+        let contractor_id = developer_repo::find_by_name(&conn, "外包A").unwrap().unwrap().id;
+        assert_eq!(live_plan(&conn, 2).0, Some(contractor_id));
+        assert_eq!(live_plan(&conn, 3).1.as_deref(), Some("2026-11-06"));
+        assert!(apply_scenario(&conn, id).is_err());
+    }
+
+    #[test]
+    fn apply_writes_only_edited_fields_and_refuses_drifted_tasks() {
+        let conn = setup_conn();
+        let id = create_scenario(&conn, &CreateScenarioDto { name: "调整".to_string(), description: None }).unwrap();
+        let contractor = add_developer(
+            &conn,
+            &CreateScenarioDeveloperDto { scenario_id: id, name: "外包A".to_string(), max_hours_per_day: None },
+        )
+        .unwrap();
+        let edit = |task_id: i64, scenario_developer_id: Option<i64>, planned_end: Option<&str>| ScenarioTaskEdit {
+            scenario_id: id,
+            task_id,
+            owner_id: None,
+            scenario_developer_id,
+            planned_start: None,
+            planned_end: planned_end.map(str::to_string),
+            planned_hours: None,
+        };
+        update_task(&conn, &edit(1, Some(contractor), None)).unwrap();
+        assert!(remove_developer(&conn, id, contractor).unwrap_err().contains("1 个任务"));
+        reset_task(&conn, id, 1).unwrap();
+        remove_developer(&conn, id, contractor).unwrap();
+
+        // Only B's end date is edited; a later live change of its owner survives apply.
+        update_task(&conn, &edit(2, None, Some("2026-11-05"))).unwrap();
+        conn.execute_batch("INSERT INTO developers (name) VALUES ('李四'); UPDATE tasks SET owner_id = 2 WHERE id = 2;")
+            .unwrap();
+        // C's end date is edited in both places: applying would lose the live edit.
+        update_task(&conn, &edit(3, None, Some("2026-11-06"))).unwrap();
+        task_repo::set_dates(&conn, 3, "2026-11-04", "2026-11-05").unwrap();
+        assert!(apply_scenario(&conn, id).unwrap_err().contains("C"));
+
+        reset_task(&conn, id, 3).unwrap();
+        assert_eq!(apply_scenario(&conn, id).unwrap(), 1);
+        assert_eq!(live_plan(&conn, 2), (Some(2), Some("2026-11-02".to_string()), Some("2026-11-05".to_string())));
+        assert_eq!(live_plan(&conn, 3).2.as_deref(), Some("2026-11-05"));
+    }
+}
@@ -1,10 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use rusqlite::Connection;
 use chrono::NaiveDate;
-use crate::db::{task_repo, developer_repo};
-use crate::models::calendar::{CalendarEvent, CalendarResource, DeveloperWorkload, WorkloadTask, CalendarEventExtProps};
+use crate::db::{calendar_repo, capacity_repo, sprint_repo, task_repo, developer_repo, team_repo};
+use crate::holiday::provider::HolidayRecord;
+use crate::models::calendar::{
+    AvailabilityEstimate, CalendarEvent, CalendarResource, DeveloperWorkload, WorkloadTask, CalendarEventExtProps,
+};
+use crate::models::developer::Developer;
 use crate::models::task::Task;
-use crate::services::holiday_service;
+use crate::services::{assignment_service, capacity_service, holiday_service, settings_service, team_service, time_slot_service};
+use crate::services::time_slot_service::SlotRequest;
 
 /// Internal struct for tracking per-task allocation state
 struct TaskSlot {
@@ -13,15 +18,38 @@ struct TaskSlot {
     remaining: f64,
     start: NaiveDate,
     end: NaiveDate,
+    /// Manually pinned hours within `[start, end]`; excluded from `remaining`.
+    pinned: HashMap<NaiveDate, f64>,
+    /// Fixed "HH:MM" times on the start and end days, in minutes since midnight.
+    start_time: Option<u32>,
+    end_time: Option<u32>,
 }
 
-/// Get calendar events for a date range, optionally filtered by developer
+/// Event types `get_calendar_events` can emit.
+pub const CALENDAR_EVENT_TYPES: [&str; 7] = ["task", "override", "sprint", "milestone", "holiday", "makeup", "absence"];
+
+/// Get calendar events for a date range, optionally filtered by developer.
+///
+/// Besides tasks this emits the planning context: calendar overrides, sprint ranges,
+/// milestones, cached holidays / makeup workdays and developer absences. `event_types`
+/// limits the output to those types; `None` returns everything.
 pub fn get_calendar_events(
     conn: &Connection,
     start_date: &str,
     end_date: &str,
     developer_id: Option<i64>,
+    event_types: Option<&[String]>,
 ) -> Result<Vec<CalendarEvent>, String> {
+    if let Some(types) = event_types {
+        if let Some(unknown) = types.iter().find(|t| !CALENDAR_EVENT_TYPES.contains(&t.as_str())) {
+            return Err(format!("未知的日历事件类型: {}", unknown));
+        }
+    }
+    let wants = |event_type: &str| match event_types {
+        Some(types) => types.iter().any(|t| t == event_type),
+        None => true,
+    };
+
     // Ensure holiday data is cached for the date range
     if let (Ok(s), Ok(e)) = (
         NaiveDate::parse_from_str(start_date, "%Y-%m-%d"),
@@ -30,31 +58,99 @@ pub fn get_calendar_events(
         holiday_service::ensure_holidays_cached(conn, &s, &e);
     }
 
+    let mut events = Vec::new();
+    if wants("task") {
+        events.extend(task_events(conn, start_date, end_date, developer_id)?);
+    }
+
+    let developer = match developer_id {
+        Some(dev_id) => developer_repo::get_by_id(conn, dev_id).map_err(|e| e.to_string())?,
+        None => None,
+    };
+    if wants("override") {
+        // With a developer filter, only overrides on their calendar.
+        let calendar_filter = developer.as_ref().map(|d| holiday_service::calendar_for_developer(conn, d).id);
+        events.extend(override_events(conn, start_date, end_date, calendar_filter)?);
+    }
+    if wants("sprint") {
+        events.extend(sprint_events(conn, start_date, end_date)?);
+    }
+    if wants("milestone") {
+        events.extend(milestone_events(conn, start_date, end_date)?);
+    }
+    if wants("holiday") || wants("makeup") {
+        let holidays = holiday_events(conn, start_date, end_date, developer.as_ref())?;
+        events.extend(holidays.into_iter().filter(|e| {
+            e.ext_props.as_ref().is_some_and(|p| wants(&p.event_type))
+        }));
+    }
+    if wants("absence") {
+        events.extend(absence_events(conn, start_date, end_date, developer_id)?);
+    }
+
+    Ok(events)
+}
+
+/// FullCalendar end dates are exclusive: the day after `date`.
+fn exclusive_end(date: &str) -> Option<String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| (d + chrono::Duration::days(1)).format("%Y-%m-%d").to_string())
+        .ok()
+}
+
+/// A read-only all-day event spanning `[start, end]` (inclusive).
+fn context_event(
+    id: String,
+    title: String,
+    start: &str,
+    end: &str,
+    color: &str,
+    display: Option<&str>,
+    ext_props: CalendarEventExtProps,
+) -> CalendarEvent {
+    CalendarEvent {
+        id,
+        title,
+        start: start.to_string(),
+        end: exclusive_end(end),
+        resource_id: ext_props.owner_id.map(|id| id.to_string()),
+        color: Some(color.to_string()),
+        display: display.map(|d| d.to_string()),
+        editable: Some(false),
+        ext_props: Some(ext_props),
+    }
+}
+
+fn task_events(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    developer_id: Option<i64>,
+) -> Result<Vec<CalendarEvent>, String> {
     let tasks = if let Some(dev_id) = developer_id {
         task_repo::get_tasks_for_developer_in_range(conn, dev_id, start_date, end_date)
     } else {
         task_repo::get_tasks_in_date_range(conn, start_date, end_date)
     }.map_err(|e| e.to_string())?;
+    let window = time_slot_service::load_work_window(conn);
+    let slots = half_timed_slots(conn, &tasks)?;
 
-    let events: Vec<CalendarEvent> = tasks.iter().map(|task| {
+    Ok(tasks.iter().map(|task| {
         let color = task_type_color(task.task_type.as_deref());
+        let (start, end) = task_event_bounds(task, &window, &slots);
         CalendarEvent {
             id: format!("task-{}", task.id),
             title: format!("{}{}", task.name,
                 task.owner_name.as_ref().map(|n| format!(" [{}]", n)).unwrap_or_default()),
-            start: task.planned_start.clone().unwrap_or_default(),
-            end: task.planned_end.clone().map(|d| {
-                // FullCalendar end date is exclusive, add one day
-                if let Ok(date) = NaiveDate::parse_from_str(&d, "%Y-%m-%d") {
-                    (date + chrono::Duration::days(1)).format("%Y-%m-%d").to_string()
-                } else {
-                    d
-                }
-            }),
+            start,
+            end,
             resource_id: task.owner_id.map(|id| id.to_string()),
             color: Some(color.to_string()),
+            display: None,
+            editable: None,
             ext_props: Some(CalendarEventExtProps {
-                task_id: task.id,
+                event_type: "task".to_string(),
+                task_id: Some(task.id),
                 task_type: task.task_type.clone(),
                 priority: task.priority.clone(),
                 status: task.status.clone(),
@@ -65,15 +161,268 @@ pub fn get_calendar_events(
                 sprint_name: task.sprint_name.clone(),
             }),
         }
-    }).collect();
+    }).collect())
+}
+
+/// Workload time slots by (task, date), for owned tasks with only one of a start or end time.
+type TaskSlots = HashMap<(i64, String), (Option<String>, Option<String>)>;
+
+/// The owners' packed time slots of tasks that fix only one side of their time, so the
+/// other side of the event follows the hours actually booked.
+fn half_timed_slots(conn: &Connection, tasks: &[Task]) -> Result<TaskSlots, String> {
+    let mut ranges: HashMap<i64, (String, String)> = HashMap::new();
+    for task in tasks {
+        if task.planned_start_time.is_some() == task.planned_end_time.is_some() {
+            continue;
+        }
+        let (Some(owner), Some(start), Some(end)) = (task.owner_id, &task.planned_start, &task.planned_end) else { continue };
+        let range = ranges.entry(owner).or_insert_with(|| (start.clone(), end.clone()));
+        if *start < range.0 {
+            range.0 = start.clone();
+        }
+        if *end > range.1 {
+            range.1 = end.clone();
+        }
+    }
+    let mut slots = TaskSlots::new();
+    for (owner, (start, end)) in ranges {
+        for day in get_developer_workload(conn, owner, &start, &end, false)? {
+            for t in day.tasks {
+                slots.insert((t.task_id, day.date.clone()), (t.start_time, t.end_time));
+            }
+        }
+    }
+    Ok(slots)
+}
+
+/// Start/end of a task event. Tasks with a start or end time become timed events; the
+/// missing side comes from the owner's packed slot on that day, or for an unassigned
+/// one-day task from its planned hours, else from the working-hours window. Others stay
+/// all-day.
+fn task_event_bounds(task: &Task, window: &time_slot_service::WorkWindow, slots: &TaskSlots) -> (String, Option<String>) {
+    let start_date = task.planned_start.clone().unwrap_or_default();
+    if task.planned_start_time.is_none() && task.planned_end_time.is_none() {
+        // FullCalendar end date is exclusive, add one day
+        return (start_date, task.planned_end.clone().map(|d| exclusive_end(&d).unwrap_or(d)));
+    }
+    let parse = |value: Option<&str>| value.and_then(time_slot_service::parse_hm);
+    let slot = |date: &str| slots.get(&(task.id, date.to_string()));
+    let (fixed_start, fixed_end) = (parse(task.planned_start_time.as_deref()), parse(task.planned_end_time.as_deref()));
+    let one_day = task.planned_end.as_deref().unwrap_or(&start_date) == start_date;
+    let packed = task.planned_hours.filter(|_| one_day).map(|hours| {
+        time_slot_service::pack_day(window, &[SlotRequest { start: fixed_start, end: fixed_end, hours }])[0]
+    });
+
+    let start_minutes = fixed_start
+        .or_else(|| slot(&start_date).and_then(|(s, _)| parse(s.as_deref())))
+        .or(packed.map(|(s, _)| s))
+        .unwrap_or(window.day_start);
+    let end_date = task.planned_end.clone().unwrap_or_else(|| start_date.clone());
+    let end_minutes = fixed_end
+        .or_else(|| slot(&end_date).and_then(|(_, e)| parse(e.as_deref())))
+        .or(packed.map(|(_, e)| e))
+        .unwrap_or(window.day_end);
+    let at = |date: &str, minutes: u32| format!("{}T{}:00", date, time_slot_service::format_hm(minutes));
+    (at(&start_date, start_minutes), task.planned_end.as_ref().map(|d| at(d, end_minutes)))
+}
+
+fn override_events(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    calendar_filter: Option<i64>,
+) -> Result<Vec<CalendarEvent>, String> {
+    let calendar_names: HashMap<i64, String> = calendar_repo::get_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|c| (c.id, c.name))
+        .collect();
+    let overrides = holiday_service::list_overrides(conn, Some(start_date), Some(end_date))?;
+    Ok(overrides
+        .into_iter()
+        .filter(|o| match (calendar_filter, o.calendar_id) {
+            (Some(filter), Some(id)) => filter == id,
+            _ => true,
+        })
+        .map(|o| {
+            let kind = if o.is_workday { "调整上班" } else { "调整休息" };
+            let mut title = match o.name.as_deref().filter(|n| !n.is_empty()) {
+                Some(name) => format!("{}（{}）", name, kind),
+                None => kind.to_string(),
+            };
+            if let Some(name) = o.calendar_id.and_then(|id| calendar_names.get(&id)) {
+                title = format!("[{}] {}", name, title);
+            }
+            context_event(
+                format!("override-{}", o.id),
+                title,
+                &o.date,
+                &o.date,
+                if o.is_workday { "#d9f7be" } else { "#ffccc7" },
+                Some("background"),
+                CalendarEventExtProps::of_type("override"),
+            )
+        })
+        .collect())
+}
+
+/// Sprints with both dates set that overlap the range.
+fn sprint_events(conn: &Connection, start_date: &str, end_date: &str) -> Result<Vec<CalendarEvent>, String> {
+    let sprints = sprint_repo::get_all_sprints(conn).map_err(|e| e.to_string())?;
+    Ok(sprints
+        .into_iter()
+        .filter_map(|s| {
+            let (start, end) = (s.start_date.as_deref()?, s.end_date.as_deref()?);
+            if end < start_date || start > end_date {
+                return None;
+            }
+            let mut props = CalendarEventExtProps::of_type("sprint");
+            props.sprint_id = Some(s.id);
+            props.sprint_name = Some(s.name.clone());
+            Some(context_event(format!("sprint-{}", s.id), s.name.clone(), start, end, "#adc6ff", None, props))
+        })
+        .collect())
+}
 
+fn milestone_events(conn: &Connection, start_date: &str, end_date: &str) -> Result<Vec<CalendarEvent>, String> {
+    let milestones = sprint_repo::get_milestones(conn, Some(start_date), Some(end_date)).map_err(|e| e.to_string())?;
+    Ok(milestones
+        .into_iter()
+        .map(|m| {
+            let mut props = CalendarEventExtProps::of_type("milestone");
+            props.sprint_id = m.sprint_id;
+            props.sprint_name = m.sprint_name.clone();
+            context_event(format!("milestone-{}", m.id), format!("◆ {}", m.name), &m.date, &m.date, "#722ed1", None, props)
+        })
+        .collect())
+}
+
+/// Holidays and makeup workdays from `holiday_cache`, with consecutive days of the same
+/// name merged into one event.
+///
+/// With a developer filter only their region is shown. Otherwise the default region is
+/// shown for everyone, and developers on a calendar of another region get that region's
+/// days on their own resource row.
+fn holiday_events(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    developer: Option<&Developer>,
+) -> Result<Vec<CalendarEvent>, String> {
+    let developers;
+    let mut targets: Vec<(String, Option<&Developer>)> = Vec::new();
+    match developer {
+        Some(dev) => targets.push((holiday_service::calendar_for_developer(conn, dev).region, None)),
+        None => {
+            let default_region = holiday_service::default_calendar(conn).region;
+            targets.push((default_region.clone(), None));
+            developers = developer_repo::get_all(conn).map_err(|e| e.to_string())?;
+            for dev in developers.iter().filter(|d| d.is_active && d.calendar_id.is_some()) {
+                let region = holiday_service::calendar_for_developer(conn, dev).region;
+                if region != default_region {
+                    targets.push((region, Some(dev)));
+                }
+            }
+        }
+    }
+
+    let mut by_region: HashMap<String, Vec<HolidayRecord>> = HashMap::new();
+    let mut events = Vec::new();
+    for (region, owner) in targets {
+        if !by_region.contains_key(&region) {
+            let records = holiday_service::list_holidays(conn, &region, start_date, end_date)?;
+            by_region.insert(region.clone(), records);
+        }
+        for (first, last) in merge_holiday_runs(&by_region[&region]) {
+            let event_type = if first.is_holiday { "holiday" } else { "makeup" };
+            let mut title = match (first.name.is_empty(), first.is_holiday) {
+                (false, true) => first.name.clone(),
+                (false, false) => format!("{}（补班）", first.name),
+                (true, true) => "节假日".to_string(),
+                (true, false) => "补班".to_string(),
+            };
+            let mut props = CalendarEventExtProps::of_type(event_type);
+            if let Some(dev) = owner {
+                title = format!("[{}] {}", region, title);
+                props.owner_id = Some(dev.id);
+                props.owner_name = Some(dev.name.clone());
+            }
+            let id = match owner {
+                Some(dev) => format!("{}-{}-{}-{}", event_type, region, first.date, dev.id),
+                None => format!("{}-{}-{}", event_type, region, first.date),
+            };
+            let color = if first.is_holiday { "#fff1f0" } else { "#fffbe6" };
+            events.push(context_event(id, title, &first.date, &last.date, color, Some("background"), props));
+        }
+    }
     Ok(events)
 }
 
-/// Get calendar resources (developers)
-pub fn get_calendar_resources(conn: &Connection) -> Result<Vec<CalendarResource>, String> {
+/// Group date-sorted records into runs of consecutive days with the same name and kind.
+fn merge_holiday_runs(records: &[HolidayRecord]) -> Vec<(&HolidayRecord, &HolidayRecord)> {
+    let mut runs: Vec<(&HolidayRecord, &HolidayRecord)> = Vec::new();
+    for record in records {
+        if let Some((first, last)) = runs.last_mut() {
+            let adjacent = exclusive_end(&last.date).as_deref() == Some(record.date.as_str());
+            if adjacent && first.name == record.name && first.is_holiday == record.is_holiday {
+                *last = record;
+                continue;
+            }
+        }
+        runs.push((record, record));
+    }
+    runs
+}
+
+/// Leave recorded as capacity rules: dated, non-recurring rules that take a developer's
+/// capacity to zero (`set 0` or `ratio 0`). Open ends are clipped to the range.
+fn absence_events(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    developer_id: Option<i64>,
+) -> Result<Vec<CalendarEvent>, String> {
+    let names: HashMap<i64, String> = developer_repo::get_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|d| (d.id, d.name))
+        .collect();
+    let rules = capacity_repo::get_all(conn).map_err(|e| e.to_string())?;
+    Ok(rules
+        .into_iter()
+        .filter(|r| developer_id.is_none() || developer_id == Some(r.developer_id))
+        .filter(|r| r.weekday.is_none() && matches!(r.mode.as_str(), "set" | "ratio") && r.value <= 1e-6)
+        .filter_map(|r| {
+            let start = r.start_date.as_deref().unwrap_or(start_date).max(start_date);
+            let end = r.end_date.as_deref().unwrap_or(end_date).min(end_date);
+            if end < start {
+                return None;
+            }
+            let name = names.get(&r.developer_id)?;
+            let title = match r.note.as_deref().filter(|n| !n.is_empty()) {
+                Some(note) => format!("{}：{}", name, note),
+                None => format!("{} 请假", name),
+            };
+            let mut props = CalendarEventExtProps::of_type("absence");
+            props.owner_id = Some(r.developer_id);
+            props.owner_name = Some(name.clone());
+            Some(context_event(format!("absence-{}", r.id), title, start, end, "#bfbfbf", None, props))
+        })
+        .collect())
+}
+
+/// Get calendar resources (active developers), optionally limited to one team
+pub fn get_calendar_resources(conn: &Connection, team_id: Option<i64>) -> Result<Vec<CalendarResource>, String> {
     let developers = developer_repo::get_all(conn).map_err(|e| e.to_string())?;
-    Ok(developers.iter().filter(|d| d.is_active).map(|d| {
+    let member_ids = match team_id {
+        Some(id) => Some(team_repo::get_member_ids(conn, id).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let in_team = |id: i64| match &member_ids {
+        Some(ids) => ids.contains(&id),
+        None => true,
+    };
+    Ok(developers.iter().filter(|d| d.is_active && in_team(d.id)).map(|d| {
         CalendarResource {
             id: d.id.to_string(),
             title: d.name.clone(),
@@ -90,6 +439,14 @@ pub fn get_calendar_resources(conn: &Connection) -> Result<Vec<CalendarResource>
 /// hours. This naturally balances load across days:
 ///   - 1.5d (12h) task over 2 days = 8h + 4h (front-loaded)
 ///   - Two overlapping 1.5d tasks = 8h, 8h, 8h (balanced across 3 days)
+///
+/// Daily capacity is resolved through the developer's capacity rules, so reserved
+/// meeting time or part-time periods shrink that day's budget. Workdays follow the
+/// developer's own region calendar.
+///
+/// Days pinned in `task_daily_allocations` are booked first with exactly their pinned
+/// hours (a pinned day off the calendar shows up as overtime); only the task's remaining
+/// hours go through EDF, over its unpinned workdays.
 pub fn get_developer_workload(
     conn: &Connection,
     developer_id: i64,
@@ -110,12 +467,29 @@ pub fn get_developer_workload(
         .map_err(|e| e.to_string())?;
 
     // Build task slots with remaining hours
+    let mut all_pinned = assignment_service::load_pinned_hours(conn)?;
     let mut slots: Vec<TaskSlot> = tasks.iter().filter_map(|t| {
         let hours = t.planned_hours.unwrap_or(0.0);
         if hours <= 0.0 { return None; }
         let ts = NaiveDate::parse_from_str(t.planned_start.as_ref()?, "%Y-%m-%d").ok()?;
         let te = NaiveDate::parse_from_str(t.planned_end.as_ref()?, "%Y-%m-%d").ok()?;
-        Some(TaskSlot { task_id: t.id, task_name: t.name.clone(), remaining: hours, start: ts, end: te })
+        let pinned: HashMap<NaiveDate, f64> = all_pinned
+            .remove(&t.id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(d, _)| *d >= ts && *d <= te)
+            .collect();
+        let remaining = (hours - pinned.values().sum::<f64>()).max(0.0);
+        Some(TaskSlot {
+            task_id: t.id,
+            task_name: t.name.clone(),
+            remaining,
+            start: ts,
+            end: te,
+            pinned,
+            start_time: t.planned_start_time.as_deref().and_then(time_slot_service::parse_hm),
+            end_time: t.planned_end_time.as_deref().and_then(time_slot_service::parse_hm),
+        })
     }).collect();
 
     if slots.is_empty() {
@@ -129,7 +503,9 @@ pub fn get_developer_workload(
 
     // Ensure holidays cached for the full processing range
     let latest_end = slots.iter().map(|s| s.end).max().unwrap().max(view_end);
-    holiday_service::ensure_holidays_cached(conn, &process_start, &latest_end);
+    let region_calendar = holiday_service::calendar_for_developer(conn, &developer);
+    holiday_service::ensure_region_holidays_cached(conn, &region_calendar.region, &process_start, &latest_end);
+    let mut calendar = holiday_service::work_calendar_for_developer(conn, &developer);
 
     // Build overtime day set: non-workdays that have tasks scheduled
     let overtime_set: HashSet<NaiveDate> = if include_overtime {
@@ -137,7 +513,7 @@ pub fn get_developer_workload(
         for slot in &slots {
             let mut d = slot.start;
             while d <= slot.end {
-                if !holiday_service::is_workday(conn, &d) {
+                if !calendar.is_workday(&d) {
                     set.insert(d);
                 }
                 d += chrono::Duration::days(1);
@@ -147,47 +523,73 @@ pub fn get_developer_workload(
     } else {
         HashSet::new()
     };
+    // Overtime days count as workdays for the allocation below
+    for d in &overtime_set {
+        calendar.set_special_day(*d, true);
+    }
 
-    // Local helpers that account for overtime days
-    let is_effective_workday = |date: &NaiveDate| -> bool {
-        holiday_service::is_workday(conn, date) || overtime_set.contains(date)
-    };
-    let count_effective_workdays = |start: &NaiveDate, end: &NaiveDate| -> i64 {
-        let mut count = 0i64;
-        let mut cur = *start;
-        while cur <= *end {
-            if is_effective_workday(&cur) {
-                count += 1;
-            }
-            cur += chrono::Duration::days(1);
-        }
-        count
-    };
-
-    let max_h = developer.max_hours_per_day;
+    let profile = capacity_service::load_profile(conn, &developer, settings_service::default_hours_per_day(conn))?;
+    let window = time_slot_service::load_work_window(conn);
     let mut workloads = Vec::new();
     let mut current = process_start;
 
     while current <= view_end {
-        if !is_effective_workday(&current) {
+        let pinned_today: Vec<(usize, f64)> = (0..slots.len())
+            .filter_map(|i| slots[i].pinned.get(&current).map(|h| (i, *h)))
+            .collect();
+        let is_workday = calendar.is_workday(&current);
+        if !is_workday && pinned_today.is_empty() {
             current += chrono::Duration::days(1);
             continue;
         }
 
-        let is_overtime_day = overtime_set.contains(&current);
-
-        // Collect active task indices, sorted by end date (earliest deadline first)
-        let mut active: Vec<usize> = (0..slots.len())
-            .filter(|&i| slots[i].remaining > 0.0 && current >= slots[i].start && current <= slots[i].end)
-            .collect();
-        active.sort_by_key(|&i| slots[i].end);
+        let is_overtime_day = overtime_set.contains(&current) || !is_workday;
+        let max_h = profile.hours_on(&current);
 
         let mut capacity = max_h;
         let mut daily_tasks: Vec<WorkloadTask> = Vec::new();
+        let mut requests: Vec<SlotRequest> = Vec::new();
         let mut total_hours = 0.0;
+        let slot_request = |slot: &TaskSlot, hours: f64| SlotRequest {
+            start: slot.start_time.filter(|_| current == slot.start),
+            end: slot.end_time.filter(|_| current == slot.end),
+            hours,
+        };
+
+        for &(idx, hours) in &pinned_today {
+            if current >= view_start {
+                daily_tasks.push(WorkloadTask {
+                    task_id: slots[idx].task_id,
+                    task_name: slots[idx].task_name.clone(),
+                    daily_hours: hours,
+                    pinned: true,
+                    start_time: None,
+                    end_time: None,
+                });
+                requests.push(slot_request(&slots[idx], hours));
+                total_hours += hours;
+            }
+            capacity -= hours;
+        }
+
+        // Collect active unpinned task indices, sorted by end date (earliest deadline first)
+        let mut active: Vec<usize> = (0..slots.len())
+            .filter(|&i| {
+                is_workday
+                    && slots[i].remaining > 0.0
+                    && current >= slots[i].start
+                    && current <= slots[i].end
+                    && !slots[i].pinned.contains_key(&current)
+            })
+            .collect();
+        active.sort_by_key(|&i| slots[i].end);
 
         for &idx in &active {
-            let remaining_workdays = count_effective_workdays(&current, &slots[idx].end);
+            let remaining_workdays = calendar
+                .workdays(current, slots[idx].end)
+                .iter()
+                .filter(|d| !slots[idx].pinned.contains_key(d))
+                .count();
             let is_last_day = remaining_workdays <= 1;
 
             let alloc = if is_last_day {
@@ -205,7 +607,11 @@ pub fn get_developer_workload(
                         task_id: slots[idx].task_id,
                         task_name: slots[idx].task_name.clone(),
                         daily_hours: alloc,
+                        pinned: false,
+                        start_time: None,
+                        end_time: None,
                     });
+                    requests.push(slot_request(&slots[idx], alloc));
                     total_hours += alloc;
                 }
                 slots[idx].remaining -= alloc;
@@ -214,6 +620,12 @@ pub fn get_developer_workload(
         }
 
         if current >= view_start {
+            // Pinned hours first, then the EDF order, around any fixed times.
+            let times = time_slot_service::pack_day(&window, &requests);
+            for (task, (start, end)) in daily_tasks.iter_mut().zip(times) {
+                task.start_time = Some(time_slot_service::format_hm(start));
+                task.end_time = Some(time_slot_service::format_hm(end));
+            }
             workloads.push(DeveloperWorkload {
                 developer_id,
                 developer_name: developer.name.clone(),
@@ -232,6 +644,102 @@ pub fn get_developer_workload(
     Ok(workloads)
 }
 
+/// Search windows (in calendar days) tried in turn by the availability simulation.
+const AVAILABILITY_HORIZONS: [i64; 2] = [120, 730];
+
+/// Simulate giving `developer_id` a new `hours`-long task starting no earlier than `from`.
+///
+/// Existing work keeps its EDF allocation from [`get_developer_workload`]; the new task
+/// only takes the hours left over each workday, so it never delays anything already planned.
+/// Days with no booked work contribute their full effective capacity.
+pub fn estimate_earliest_finish(
+    conn: &Connection,
+    developer_id: i64,
+    hours: f64,
+    from: NaiveDate,
+) -> Result<AvailabilityEstimate, String> {
+    if hours <= 0.0 {
+        return Err("工时必须大于 0".to_string());
+    }
+    let developer = developer_repo::get_by_id(conn, developer_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Developer not found".to_string())?;
+    let profile = capacity_service::load_profile(conn, &developer, settings_service::default_hours_per_day(conn))?;
+    let region_calendar = holiday_service::calendar_for_developer(conn, &developer);
+
+    let mut estimate = AvailabilityEstimate {
+        developer_id,
+        developer_name: developer.name.clone(),
+        hours,
+        earliest_start: None,
+        earliest_finish: None,
+        workdays_used: 0,
+    };
+
+    for horizon in AVAILABILITY_HORIZONS {
+        let end = from + chrono::Duration::days(horizon);
+        holiday_service::ensure_region_holidays_cached(conn, &region_calendar.region, &from, &end);
+        let calendar = holiday_service::work_calendar_for_developer(conn, &developer);
+        let booked: HashMap<String, f64> = get_developer_workload(
+            conn,
+            developer_id,
+            &from.format("%Y-%m-%d").to_string(),
+            &end.format("%Y-%m-%d").to_string(),
+            false,
+        )?
+        .into_iter()
+        .map(|w| (w.date, w.allocated_hours))
+        .collect();
+
+        let mut remaining = hours;
+        let mut start: Option<NaiveDate> = None;
+        let mut used = 0i64;
+        let mut day = from;
+        while day <= end {
+            if calendar.is_workday(&day) {
+                let key = day.format("%Y-%m-%d").to_string();
+                let free = (profile.hours_on(&day) - booked.get(&key).copied().unwrap_or(0.0)).max(0.0);
+                if free > 1e-6 {
+                    start.get_or_insert(day);
+                    used += 1;
+                    remaining -= free.min(remaining);
+                    if remaining <= 1e-6 {
+                        estimate.earliest_start = start.map(|d| d.format("%Y-%m-%d").to_string());
+                        estimate.earliest_finish = Some(key);
+                        estimate.workdays_used = used;
+                        return Ok(estimate);
+                    }
+                }
+            }
+            day += chrono::Duration::days(1);
+        }
+    }
+
+    Ok(estimate)
+}
+
+/// Run [`estimate_earliest_finish`] for every active developer (optionally one team's)
+/// and return them soonest finish first; developers who can't finish come last.
+pub fn find_soonest_available(
+    conn: &Connection,
+    hours: f64,
+    from: NaiveDate,
+    team_id: Option<i64>,
+) -> Result<Vec<AvailabilityEstimate>, String> {
+    let developers = team_service::active_developers(conn, team_id)?;
+
+    let mut estimates = Vec::new();
+    for dev in developers {
+        estimates.push(estimate_earliest_finish(conn, dev.id, hours, from)?);
+    }
+    // ISO dates sort lexically; `None` (cannot finish) goes last.
+    estimates.sort_by(|a, b| {
+        let key = |e: &AvailabilityEstimate| (e.earliest_finish.is_none(), e.earliest_finish.clone(), e.earliest_start.clone());
+        key(a).cmp(&key(b)).then(a.developer_id.cmp(&b.developer_id))
+    });
+    Ok(estimates)
+}
+
 fn task_type_color(task_type: Option<&str>) -> &'static str {
     match task_type {
         Some("需求澄清") => "#1890ff",
@@ -250,3 +758,212 @@ fn task_type_color(task_type: Option<&str>) -> &'static str {
         _ => "#1890ff",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+    use crate::models::sprint::CreateMilestoneDto;
+    use crate::models::task::{DailyAllocation, UpdateTaskDto};
+    use crate::services::holiday_service::DeveloperCalendars;
+    use crate::services::{milestone_service, task_service};
+    use rusqlite::params;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2026-01-01', 1, 0, '元旦', 2026)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2027-01-01', 1, 0, '元旦', 2027)",
+            [],
+        )
+        .unwrap();
+        for name in ["李四", "王五"] {
+            conn.execute("INSERT INTO developers (name) VALUES (?1)", params![name]).unwrap();
+        }
+        conn
+    }
+
+    fn ymd(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn new_task_only_uses_hours_left_after_existing_allocation() {
+        let conn = setup_conn();
+        // 12h over Mon-Tue front-loads as 8h + 4h, leaving 4h on Tuesday.
+        conn.execute(
+            "INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('existing', 1, '2026-11-02', '2026-11-03', 12)",
+            [],
+        )
+        .unwrap();
+
+        let est = estimate_earliest_finish(&conn, 1, 24.0, ymd("2026-11-02")).unwrap();
+        assert_eq!(est.earliest_start.as_deref(), Some("2026-11-03"));
+        // 4h Tue + 8h Wed + 8h Thu + 4h Fri
+        assert_eq!(est.earliest_finish.as_deref(), Some("2026-11-06"));
+        assert_eq!(est.workdays_used, 4);
+    }
+
+    #[test]
+    fn soonest_available_prefers_the_idle_developer() {
+        let conn = setup_conn();
+        conn.execute(
+            "INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('busy', 1, '2026-11-02', '2026-11-06', 40)",
+            [],
+        )
+        .unwrap();
+
+        let ranked = find_soonest_available(&conn, 16.0, ymd("2026-11-02"), None).unwrap();
+        assert_eq!(ranked[0].developer_name, "王五");
+        assert_eq!(ranked[0].earliest_finish.as_deref(), Some("2026-11-03"));
+        assert_eq!(ranked[1].earliest_finish.as_deref(), Some("2026-11-10"));
+    }
+
+    #[test]
+    fn workload_honours_pinned_days() {
+        let conn = setup_conn();
+        conn.execute(
+            "INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('pinned', 1, '2026-11-02', '2026-11-04', 16)",
+            [],
+        )
+        .unwrap();
+        let pin = |date: &str, hours: f64| DailyAllocation { task_id: 1, date: date.to_string(), hours };
+        assert!(task_service::set_daily_allocations(&conn, 1, &[pin("2026-11-05", 1.0)]).is_err());
+        assert!(task_service::set_daily_allocations(&conn, 1, &[pin("2026-11-02", 10.0), pin("2026-11-04", 8.0)]).is_err());
+        task_service::set_daily_allocations(&conn, 1, &[pin("2026-11-02", 2.0), pin("2026-11-04", 6.0)]).unwrap();
+
+        let days = get_developer_workload(&conn, 1, "2026-11-02", "2026-11-04", false).unwrap();
+        let hours: Vec<(f64, bool)> = days.iter().map(|d| (d.allocated_hours, d.tasks[0].pinned)).collect();
+        // Only the 8h left over go through EDF, all on the unpinned Tuesday.
+        assert_eq!(hours, vec![(2.0, true), (8.0, false), (6.0, true)]);
+
+        let calendars = DeveloperCalendars::load(&conn).unwrap();
+        let tasks = task_repo::get_all(&conn, &Default::default()).unwrap();
+        let pinned = assignment_service::load_pinned_hours(&conn).unwrap();
+        let load = assignment_service::build_existing_daily_load(&calendars, &tasks, &HashSet::new(), &pinned);
+        assert_eq!(load.get(&(1, ymd("2026-11-03"))), Some(&8.0));
+        assert_eq!(load.get(&(1, ymd("2026-11-04"))), Some(&6.0));
+    }
+
+    #[test]
+    fn small_tasks_share_a_day_in_time_slots() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            "INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('review', 1, '2026-11-02', '2026-11-02', 2);
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours, planned_start_time) \
+             VALUES ('standup', 1, '2026-11-02', '2026-11-02', 1, '09:00');
+             INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('fix', 1, '2026-11-02', '2026-11-03', 4);",
+        )
+        .unwrap();
+
+        let days = get_developer_workload(&conn, 1, "2026-11-02", "2026-11-02", false).unwrap();
+        let slots: Vec<(&str, &str, &str)> = days[0]
+            .tasks
+            .iter()
+            .map(|t| (t.task_name.as_str(), t.start_time.as_deref().unwrap(), t.end_time.as_deref().unwrap()))
+            .collect();
+        assert_eq!(slots, vec![
+            ("review", "10:00", "12:00"),
+            ("standup", "09:00", "10:00"),
+            ("fix", "13:00", "17:00"),
+        ]);
+
+        let events = get_calendar_events(&conn, "2026-11-02", "2026-11-03", None, Some(&["task".to_string()])).unwrap();
+        let standup = events.iter().find(|e| e.title.starts_with("standup")).unwrap();
+        assert_eq!((standup.start.as_str(), standup.end.as_deref()), ("2026-11-02T09:00:00", Some("2026-11-02T10:00:00")));
+        conn.execute(
+            "INSERT INTO tasks (name, planned_start, planned_end, planned_hours, planned_end_time) \
+             VALUES ('demo', '2026-11-03', '2026-11-03', 3, '17:00')",
+            [],
+        )
+        .unwrap();
+        let events = get_calendar_events(&conn, "2026-11-02", "2026-11-03", None, Some(&["task".to_string()])).unwrap();
+        let demo = events.iter().find(|e| e.title.starts_with("demo")).unwrap();
+        assert_eq!((demo.start.as_str(), demo.end.as_deref()), ("2026-11-03T14:00:00", Some("2026-11-03T17:00:00")));
+        let review = events.iter().find(|e| e.title.starts_with("review")).unwrap();
+        assert_eq!((review.start.as_str(), review.end.as_deref()), ("2026-11-02", Some("2026-11-03")));
+
+        let bad: UpdateTaskDto = serde_json::from_value(serde_json::json!({ "id": 2, "planned_end_time": "08:30" })).unwrap();
+        assert!(task_service::update_task(&conn, &bad).is_err());
+    }
+
+    #[test]
+    fn calendar_events_include_planning_context() {
+        let conn = setup_conn();
+        for (date, is_holiday, name) in [
+            ("2026-10-01", 1, "国庆节"),
+            ("2026-10-02", 1, "国庆节"),
+            ("2026-10-03", 1, "国庆节"),
+            ("2026-10-10", 0, "国庆节"),
+        ] {
+            conn.execute(
+                "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES (?1, ?2, ?3, ?4, 2026)",
+                params![date, is_holiday, 1 - is_holiday, name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO sprints (name, start_date, end_date) VALUES ('S1', '2026-10-05', '2026-10-16')",
+            [],
+        )
+        .unwrap();
+        milestone_service::create_milestone(&conn, &CreateMilestoneDto {
+            name: "提测".to_string(),
+            date: "2026-10-16".to_string(),
+            sprint_id: Some(1),
+            note: None,
+        })
+        .unwrap();
+        conn.execute(
+            "INSERT INTO developer_capacity_rules (developer_id, mode, value, start_date, end_date, note) \
+             VALUES (1, 'set', 0, '2026-10-12', '2026-10-13', '年假'), \
+                    (2, 'ratio', 0.5, '2026-10-12', '2026-10-13', NULL)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tasks (name, owner_id, planned_start, planned_end, planned_hours) \
+             VALUES ('task', 1, '2026-10-14', '2026-10-15', 8)",
+            [],
+        )
+        .unwrap();
+
+        let events = get_calendar_events(&conn, "2026-10-01", "2026-10-31", None, None).unwrap();
+        let of_type = |t: &str| -> Vec<&CalendarEvent> {
+            events.iter().filter(|e| e.ext_props.as_ref().unwrap().event_type == t).collect()
+        };
+
+        // National Day is one merged background event; the makeup Saturday is separate.
+        let holidays = of_type("holiday");
+        assert_eq!(holidays.len(), 1);
+        assert_eq!((holidays[0].start.as_str(), holidays[0].end.as_deref()), ("2026-10-01", Some("2026-10-04")));
+        assert_eq!(of_type("makeup")[0].start, "2026-10-10");
+
+        assert_eq!(of_type("sprint")[0].end.as_deref(), Some("2026-10-17"));
+        assert_eq!(of_type("milestone")[0].ext_props.as_ref().unwrap().sprint_name.as_deref(), Some("S1"));
+
+        // Only the zero-capacity rule is leave; it sits on the developer's row.
+        let absences = of_type("absence");
+        assert_eq!(absences.len(), 1);
+        assert_eq!(absences[0].title, "李四：年假");
+        assert_eq!(absences[0].resource_id.as_deref(), Some("1"));
+        assert_eq!(absences[0].editable, Some(false));
+        assert_eq!(of_type("task").len(), 1);
+
+        let only: Vec<String> = vec!["milestone".to_string()];
+        let filtered = get_calendar_events(&conn, "2026-10-01", "2026-10-31", None, Some(&only)).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert!(get_calendar_events(&conn, "2026-10-01", "2026-10-31", None, Some(&["foo".to_string()])).is_err());
+    }
+}
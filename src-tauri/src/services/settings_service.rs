@@ -97,15 +97,17 @@ pub fn save_excel_template_config(
         "excel_template",
     )
     .map_err(|e| e.to_string())?;
-    if let Some(header_row) = config.header_row {
-        settings_repo::set_setting(
+    // No header row means it is detected on import, so a cleared value is removed.
+    match config.header_row {
+        Some(header_row) => settings_repo::set_setting(
             conn,
             "excel_template.header_row",
             &header_row.to_string(),
             "excel_template",
-        )
-        .map_err(|e| e.to_string())?;
+        ),
+        None => settings_repo::delete_setting(conn, "excel_template.header_row"),
     }
+    .map_err(|e| e.to_string())?;
     if let Some(ref sheet_name) = config.default_sheet_name {
         settings_repo::set_setting(
             conn,
@@ -133,6 +135,16 @@ pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, Strin
     settings_repo::get_setting(conn, key).map_err(|e| e.to_string())
 }
 
+/// The configured `work_hours.hours_per_day`, or 8h when unset.
+pub fn default_hours_per_day(conn: &Connection) -> f64 {
+    get_setting(conn, "work_hours.hours_per_day")
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| *v > 0.0)
+        .unwrap_or(8.0)
+}
+
 pub fn set_setting(
     conn: &Connection,
     key: &str,
@@ -1,7 +1,10 @@
+use std::collections::{HashMap, HashSet};
 use rusqlite::Connection;
-use crate::db::task_repo;
-use crate::models::task::{Task, CreateTaskDto, UpdateTaskDto, TaskFilter};
+use crate::db::{sprint_repo, task_repo};
+use crate::models::task::{Task, CreateTaskDto, UpdateTaskDto, TaskFilter, TaskDependency, DailyAllocation};
 use crate::models::batch::BatchResult;
+use crate::services::date_service::{self, parse_ymd, YearHint};
+use crate::services::time_slot_service;
 
 pub fn list_tasks(conn: &Connection, filter: &TaskFilter) -> Result<Vec<Task>, String> {
     task_repo::get_all(conn, filter).map_err(|e| e.to_string())
@@ -11,11 +14,51 @@ pub fn get_task(conn: &Connection, id: i64) -> Result<Option<Task>, String> {
     task_repo::get_by_id(conn, id).map_err(|e| e.to_string())
 }
 
+/// Start/end times must be "HH:MM"; on a single-day task the end must follow the start.
+fn validate_times(
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+    planned_start: Option<&str>,
+    planned_end: Option<&str>,
+) -> Result<(), String> {
+    let parse = |value: Option<&str>| match value.filter(|v| !v.is_empty()) {
+        Some(v) => time_slot_service::parse_hm(v)
+            .map(Some)
+            .ok_or_else(|| format!("时间格式无效: {}，应为 HH:MM", v)),
+        None => Ok(None),
+    };
+    let (start, end) = (parse(start_time)?, parse(end_time)?);
+    if let (Some(s), Some(e)) = (start, end) {
+        if planned_start.is_some() && planned_start == planned_end && e <= s {
+            return Err("结束时间必须晚于开始时间".to_string());
+        }
+    }
+    Ok(())
+}
+
 pub fn create_task(conn: &Connection, dto: &CreateTaskDto) -> Result<i64, String> {
+    validate_times(
+        dto.planned_start_time.as_deref(),
+        dto.planned_end_time.as_deref(),
+        dto.planned_start.as_deref(),
+        dto.planned_end.as_deref(),
+    )?;
     task_repo::create(conn, dto).map_err(|e| e.to_string())
 }
 
 pub fn update_task(conn: &Connection, dto: &UpdateTaskDto) -> Result<(), String> {
+    if dto.planned_start_time.is_some() || dto.planned_end_time.is_some() {
+        let current = task_repo::get_by_id(conn, dto.id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Task not found".to_string())?;
+        let pick = |new: &Option<String>, old: &Option<String>| new.clone().or_else(|| old.clone());
+        validate_times(
+            pick(&dto.planned_start_time, &current.planned_start_time).as_deref(),
+            pick(&dto.planned_end_time, &current.planned_end_time).as_deref(),
+            pick(&dto.planned_start, &current.planned_start).as_deref(),
+            pick(&dto.planned_end, &current.planned_end).as_deref(),
+        )?;
+    }
     task_repo::update(conn, dto).map_err(|e| e.to_string())
 }
 
@@ -63,10 +106,25 @@ pub fn batch_delete_tasks(conn: &Connection, ids: &[i64]) -> Result<usize, Strin
     Ok(count)
 }
 
+/// Create tasks in one transaction. Dates are normalized to `YYYY-MM-DD` first, a date
+/// without a year taking it from the task's sprint; a value that is not a date fails
+/// the whole batch.
 pub fn batch_create_tasks(conn: &Connection, tasks: &[CreateTaskDto]) -> Result<Vec<i64>, String> {
+    let mut normalized = Vec::with_capacity(tasks.len());
+    for dto in tasks {
+        let mut dto = dto.clone();
+        let sprint = match dto.sprint_id {
+            Some(id) => sprint_repo::get_sprint_by_id(conn, id).map_err(|e| e.to_string())?,
+            None => None,
+        };
+        let hint = YearHint::for_sprint(sprint.as_ref());
+        date_service::require_task_dates(&dto.name, &mut dto.planned_start, &mut dto.planned_end, &hint)?;
+        normalized.push(dto);
+    }
+
     conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
     let mut ids = Vec::new();
-    for dto in tasks {
+    for dto in &normalized {
         match task_repo::create(conn, dto) {
             Ok(id) => ids.push(id),
             Err(e) => {
@@ -78,3 +136,86 @@ pub fn batch_create_tasks(conn: &Connection, tasks: &[CreateTaskDto]) -> Result<
     conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
     Ok(ids)
 }
+
+pub fn list_dependencies(conn: &Connection, task_id: i64) -> Result<Vec<TaskDependency>, String> {
+    task_repo::get_dependencies_for_task(conn, task_id).map_err(|e| e.to_string())
+}
+
+/// Make `successor_id` wait for `predecessor_id`. Self-links and cycles are rejected.
+pub fn add_dependency(conn: &Connection, predecessor_id: i64, successor_id: i64) -> Result<(), String> {
+    if predecessor_id == successor_id {
+        return Err("任务不能依赖自身".to_string());
+    }
+    for id in [predecessor_id, successor_id] {
+        if task_repo::get_by_id(conn, id).map_err(|e| e.to_string())?.is_none() {
+            return Err(format!("任务不存在: {}", id));
+        }
+    }
+
+    let mut successors: HashMap<i64, Vec<i64>> = HashMap::new();
+    for dep in task_repo::get_all_dependencies(conn).map_err(|e| e.to_string())? {
+        successors.entry(dep.predecessor_id).or_default().push(dep.successor_id);
+    }
+    // A path successor -> ... -> predecessor would close a loop.
+    let mut seen = HashSet::new();
+    let mut stack = vec![successor_id];
+    while let Some(id) = stack.pop() {
+        if id == predecessor_id {
+            return Err("添加该依赖会形成循环依赖".to_string());
+        }
+        if seen.insert(id) {
+            stack.extend(successors.get(&id).into_iter().flatten().copied());
+        }
+    }
+
+    task_repo::add_dependency(conn, predecessor_id, successor_id).map_err(|e| e.to_string())
+}
+
+pub fn remove_dependency(conn: &Connection, predecessor_id: i64, successor_id: i64) -> Result<(), String> {
+    task_repo::remove_dependency(conn, predecessor_id, successor_id).map_err(|e| e.to_string())
+}
+
+pub fn list_daily_allocations(conn: &Connection, task_id: i64) -> Result<Vec<DailyAllocation>, String> {
+    task_repo::get_daily_allocations(conn, task_id).map_err(|e| e.to_string())
+}
+
+/// Replace the pinned days of a task. Days must fall inside the task's planned range and
+/// together stay within its planned hours; an empty list removes all pins.
+pub fn set_daily_allocations(conn: &Connection, task_id: i64, allocations: &[DailyAllocation]) -> Result<(), String> {
+    let task = task_repo::get_by_id(conn, task_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Task not found".to_string())?;
+    let range = (task.planned_start.as_deref().and_then(parse_ymd), task.planned_end.as_deref().and_then(parse_ymd));
+
+    let mut seen = HashSet::new();
+    let mut total = 0.0;
+    let mut rows = Vec::new();
+    for a in allocations {
+        let date = parse_ymd(&a.date).ok_or_else(|| format!("日期格式无效: {}", a.date))?;
+        if !seen.insert(date) {
+            return Err(format!("日期重复: {}", a.date));
+        }
+        if a.hours < 0.0 {
+            return Err("固定工时不能为负数".to_string());
+        }
+        if let (Some(start), Some(end)) = range {
+            if date < start || date > end {
+                return Err(format!("固定工时日期 {} 不在任务计划区间内", a.date));
+            }
+        }
+        total += a.hours;
+        rows.push(DailyAllocation { task_id, date: date.format("%Y-%m-%d").to_string(), hours: a.hours });
+    }
+    if let Some(planned) = task.planned_hours {
+        if total > planned + 1e-6 {
+            return Err(format!("固定工时合计 {}h 超过计划工时 {}h", total, planned));
+        }
+    }
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    if let Err(e) = task_repo::set_daily_allocations(conn, task_id, &rows) {
+        conn.execute_batch("ROLLBACK").ok();
+        return Err(e.to_string());
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())
+}
@@ -0,0 +1,248 @@
+use rusqlite::Connection;
+use crate::db::{developer_repo, sprint_repo, team_repo};
+use crate::models::calendar::DeveloperWorkload;
+use crate::models::developer::Developer;
+use crate::models::sprint::SprintCapacitySummary;
+use crate::models::team::{Team, CreateTeamDto, UpdateTeamDto, TeamCapacitySummary, MemberUtilization};
+use crate::services::{capacity_service, schedule_service};
+
+pub fn list_teams(conn: &Connection) -> Result<Vec<Team>, String> {
+    team_repo::get_all(conn).map_err(|e| e.to_string())
+}
+
+pub fn create_team(conn: &Connection, dto: &CreateTeamDto) -> Result<i64, String> {
+    if dto.name.trim().is_empty() {
+        return Err("团队名称不能为空".to_string());
+    }
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match team_repo::create(conn, dto) {
+        Ok(id) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            Err(format!("创建团队失败: {}", e))
+        }
+    }
+}
+
+pub fn update_team(conn: &Connection, dto: &UpdateTeamDto) -> Result<(), String> {
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match team_repo::update(conn, dto) {
+        Ok(()) => conn.execute_batch("COMMIT").map_err(|e| e.to_string()),
+        Err(e) => {
+            conn.execute_batch("ROLLBACK").ok();
+            Err(format!("更新团队失败: {}", e))
+        }
+    }
+}
+
+pub fn delete_team(conn: &Connection, id: i64) -> Result<(), String> {
+    team_repo::delete(conn, id).map_err(|e| e.to_string())
+}
+
+/// Active developers in a team, ordered by name.
+pub fn active_members(conn: &Connection, team_id: i64) -> Result<Vec<Developer>, String> {
+    let member_ids = team_repo::get_member_ids(conn, team_id).map_err(|e| e.to_string())?;
+    Ok(developer_repo::get_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|d| d.is_active && member_ids.contains(&d.id))
+        .collect())
+}
+
+/// Active developers, or the active members of one team.
+pub fn active_developers(conn: &Connection, team_id: Option<i64>) -> Result<Vec<Developer>, String> {
+    match team_id {
+        Some(id) => active_members(conn, id),
+        None => Ok(developer_repo::get_all(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|d| d.is_active)
+            .collect()),
+    }
+}
+
+/// Daily workload rows for every active member of the team.
+pub fn get_team_workload(
+    conn: &Connection,
+    team_id: i64,
+    start_date: &str,
+    end_date: &str,
+    include_overtime: bool,
+) -> Result<Vec<DeveloperWorkload>, String> {
+    let mut rows = Vec::new();
+    for dev in active_members(conn, team_id)? {
+        rows.extend(schedule_service::get_developer_workload(
+            conn, dev.id, start_date, end_date, include_overtime,
+        )?);
+    }
+    Ok(rows)
+}
+
+/// Effective capacity (after capacity rules and holidays) against EDF-allocated hours,
+/// per member and in total.
+pub fn get_team_capacity(
+    conn: &Connection,
+    team_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<TeamCapacitySummary, String> {
+    let team = team_repo::get_by_id(conn, team_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Team not found".to_string())?;
+
+    let mut members = Vec::new();
+    for dev in active_members(conn, team_id)? {
+        members.push(member_utilization(conn, &dev, start_date, end_date)?);
+    }
+
+    let capacity_hours: f64 = members.iter().map(|m| m.capacity_hours).sum();
+    let allocated_hours: f64 = members.iter().map(|m| m.allocated_hours).sum();
+    Ok(TeamCapacitySummary {
+        team_id: team.id,
+        team_name: team.name,
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        capacity_hours,
+        allocated_hours,
+        utilization: capacity_service::utilization_ratio(allocated_hours, capacity_hours),
+        members,
+    })
+}
+
+/// Effective capacity (after capacity rules and the member's holidays) against
+/// EDF-allocated hours for one developer over a date range.
+pub fn member_utilization(
+    conn: &Connection,
+    developer: &Developer,
+    start_date: &str,
+    end_date: &str,
+) -> Result<MemberUtilization, String> {
+    let days = capacity_service::get_effective_capacity(conn, developer.id, start_date, end_date)?;
+    let capacity_hours: f64 = days.iter().map(|d| d.hours).sum();
+    let allocated_hours: f64 = schedule_service::get_developer_workload(conn, developer.id, start_date, end_date, false)?
+        .iter()
+        .map(|w| w.allocated_hours)
+        .sum();
+    Ok(MemberUtilization {
+        developer_id: developer.id,
+        developer_name: developer.name.clone(),
+        workdays: days.iter().filter(|d| d.is_workday).count() as i64,
+        capacity_hours,
+        allocated_hours,
+        utilization: capacity_service::utilization_ratio(allocated_hours, capacity_hours),
+    })
+}
+
+/// Capacity of active developers (optionally one team's) over a sprint's date range.
+pub fn get_sprint_capacity(
+    conn: &Connection,
+    sprint_id: i64,
+    team_id: Option<i64>,
+) -> Result<SprintCapacitySummary, String> {
+    let sprint = sprint_repo::get_sprint_by_id(conn, sprint_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "迭代不存在".to_string())?;
+    let (Some(start_date), Some(end_date)) = (sprint.start_date.clone(), sprint.end_date.clone()) else {
+        return Err("迭代未设置开始或结束日期".to_string());
+    };
+
+    let developers = active_developers(conn, team_id)?;
+    let mut members = Vec::new();
+    for dev in &developers {
+        members.push(member_utilization(conn, dev, &start_date, &end_date)?);
+    }
+
+    let capacity_hours: f64 = members.iter().map(|m| m.capacity_hours).sum();
+    let allocated_hours: f64 = members.iter().map(|m| m.allocated_hours).sum();
+    Ok(SprintCapacitySummary {
+        sprint_id: sprint.id,
+        sprint_name: sprint.name,
+        start_date,
+        end_date,
+        capacity_hours,
+        allocated_hours,
+        utilization: capacity_service::utilization_ratio(allocated_hours, capacity_hours),
+        members,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{init, task_repo};
+    use crate::models::task::TaskFilter;
+    use rusqlite::params;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn.execute(
+            "INSERT INTO holiday_cache (date, is_holiday, is_workday, name, year) VALUES ('2026-01-01', 1, 0, '元旦', 2026)",
+            [],
+        )
+        .unwrap();
+        for name in ["FE-1", "BE-1", "QA-1"] {
+            conn.execute("INSERT INTO developers (name) VALUES (?1)", params![name]).unwrap();
+        }
+        // 2026-11-02 .. 2026-11-06 is Mon..Fri
+        conn.execute_batch(
+            "INSERT INTO tasks (id, name, owner_id, planned_start, planned_end, planned_hours)
+                 VALUES (1, 'fe', 1, '2026-11-02', '2026-11-03', 12);
+             INSERT INTO tasks (id, name, owner_id, planned_start, planned_end, planned_hours)
+                 VALUES (2, 'be', 2, '2026-11-02', '2026-11-06', 20);
+             INSERT INTO tasks (id, name, owner_id) VALUES (3, 'qa, paired with fe', 3);
+             INSERT INTO task_co_owners (task_id, developer_id) VALUES (3, 1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn team_filter_matches_owners_and_co_owners() {
+        let conn = setup_conn();
+        let fe = create_team(
+            &conn,
+            &CreateTeamDto { name: "Frontend".to_string(), description: None, color: None, member_ids: Some(vec![1]) },
+        )
+        .unwrap();
+
+        let filter = TaskFilter { team_id: Some(fe), ..Default::default() };
+        let mut ids: Vec<i64> = task_repo::get_all(&conn, &filter).unwrap().iter().map(|t| t.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn team_capacity_sums_member_capacity_and_allocation() {
+        let conn = setup_conn();
+        let team = create_team(
+            &conn,
+            &CreateTeamDto { name: "Dev".to_string(), description: None, color: None, member_ids: Some(vec![1, 2]) },
+        )
+        .unwrap();
+
+        let summary = get_team_capacity(&conn, team, "2026-11-02", "2026-11-06").unwrap();
+        assert_eq!(summary.members.len(), 2);
+        assert_eq!(summary.capacity_hours, 80.0);
+        assert_eq!(summary.allocated_hours, 32.0);
+        assert!((summary.utilization - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn team_changes_are_all_or_nothing() {
+        let conn = setup_conn();
+        let dto = |member_ids| CreateTeamDto { name: "QA".to_string(), description: None, color: None, member_ids: Some(member_ids) };
+        assert!(create_team(&conn, &dto(vec![3, 99])).is_err());
+        assert!(list_teams(&conn).unwrap().is_empty());
+
+        let team = create_team(&conn, &dto(vec![3])).unwrap();
+        delete_team(&conn, team).unwrap();
+        let members: i64 = conn.query_row("SELECT COUNT(*) FROM team_members", [], |r| r.get(0)).unwrap();
+        assert_eq!(members, 0);
+    }
+}
@@ -0,0 +1,188 @@
+use rusqlite::Connection;
+use crate::services::settings_service;
+
+/// Working-hours window of a day, in minutes since midnight. Time between
+/// `break_start` and `break_end` is never booked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkWindow {
+    pub day_start: u32,
+    pub day_end: u32,
+    pub break_start: u32,
+    pub break_end: u32,
+}
+
+impl Default for WorkWindow {
+    fn default() -> Self {
+        WorkWindow { day_start: 9 * 60, day_end: 18 * 60, break_start: 12 * 60, break_end: 13 * 60 }
+    }
+}
+
+/// Parse "HH:MM" into minutes since midnight.
+pub fn parse_hm(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    if h.is_empty() || h.len() > 2 || m.len() != 2 {
+        return None;
+    }
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    if h > 23 || m > 59 {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+/// Format minutes since midnight as "HH:MM"; times past midnight are capped at 23:59.
+pub fn format_hm(minutes: u32) -> String {
+    let minutes = minutes.min(23 * 60 + 59);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// The `work_hours.day_start/day_end/break_start/break_end` settings, falling back to
+/// 09:00–18:00 with a 12:00–13:00 break. An inconsistent window falls back as a whole.
+pub fn load_work_window(conn: &Connection) -> WorkWindow {
+    let default = WorkWindow::default();
+    let read = |key: &str, fallback: u32| {
+        settings_service::get_setting(conn, key)
+            .ok()
+            .flatten()
+            .and_then(|v| parse_hm(&v))
+            .unwrap_or(fallback)
+    };
+    let window = WorkWindow {
+        day_start: read("work_hours.day_start", default.day_start),
+        day_end: read("work_hours.day_end", default.day_end),
+        break_start: read("work_hours.break_start", default.break_start),
+        break_end: read("work_hours.break_end", default.break_end),
+    };
+    let valid = window.day_start < window.day_end
+        && window.break_start <= window.break_end
+        && (window.break_start == window.break_end
+            || (window.break_start >= window.day_start && window.break_end <= window.day_end));
+    if valid { window } else { default }
+}
+
+/// One task's share of a day to place: a fixed start and/or end time, and its hours.
+#[derive(Debug, Clone, Copy)]
+pub struct SlotRequest {
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+    pub hours: f64,
+}
+
+impl WorkWindow {
+    /// End of `minutes` of work starting at `from`, skipping the break.
+    fn advance(&self, from: u32, minutes: u32) -> u32 {
+        let mut at = from;
+        if at >= self.break_start && at < self.break_end {
+            at = self.break_end;
+        }
+        let mut left = minutes;
+        if at < self.break_start {
+            let before_break = self.break_start - at;
+            if left <= before_break {
+                return at + left;
+            }
+            left -= before_break;
+            at = self.break_end;
+        }
+        at + left
+    }
+
+    /// Start of `minutes` of work ending at `to`, skipping the break.
+    fn retreat(&self, to: u32, minutes: u32) -> u32 {
+        let mut at = to;
+        if at > self.break_start && at <= self.break_end {
+            at = self.break_start;
+        }
+        let mut left = minutes;
+        if at > self.break_end {
+            let after_break = at - self.break_end;
+            if left <= after_break {
+                return at - left;
+            }
+            left -= after_break;
+            at = self.break_start;
+        }
+        at.saturating_sub(left)
+    }
+}
+
+/// Lay a day's tasks out in time, returning `(start, end)` per request in input order.
+///
+/// Requests with a fixed time are placed first: a start time runs forward, an end time
+/// alone runs backward, both are taken as given. The others are packed back to back in
+/// input order from the start of the day into the gaps left, skipping the break; work
+/// that does not fit runs past the end of the day.
+pub fn pack_day(window: &WorkWindow, requests: &[SlotRequest]) -> Vec<(u32, u32)> {
+    let minutes = |hours: f64| (hours.max(0.0) * 60.0).round() as u32;
+    let mut placed: Vec<Option<(u32, u32)>> = requests
+        .iter()
+        .map(|r| match (r.start, r.end) {
+            (Some(s), Some(e)) if e > s => Some((s, e)),
+            (Some(s), _) => Some((s, window.advance(s, minutes(r.hours)))),
+            (None, Some(e)) => Some((window.retreat(e, minutes(r.hours)), e)),
+            (None, None) => None,
+        })
+        .collect();
+    let mut busy: Vec<(u32, u32)> = placed.iter().flatten().copied().collect();
+
+    let mut cursor = window.day_start;
+    for (i, request) in requests.iter().enumerate() {
+        if placed[i].is_some() {
+            continue;
+        }
+        let length = minutes(request.hours);
+        let (start, end) = loop {
+            let start = if cursor >= window.break_start && cursor < window.break_end { window.break_end } else { cursor };
+            let end = window.advance(start, length);
+            match busy.iter().filter(|(s, e)| *s < end.max(start + 1) && *e > start).map(|(_, e)| *e).max() {
+                Some(blocked_until) => cursor = blocked_until,
+                None => break (start, end),
+            }
+        };
+        placed[i] = Some((start, end));
+        busy.push((start, end));
+        cursor = end;
+    }
+    placed.into_iter().map(|p| p.unwrap_or((window.day_start, window.day_start))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(start: Option<&str>, end: Option<&str>, hours: f64) -> SlotRequest {
+        SlotRequest { start: start.and_then(parse_hm), end: end.and_then(parse_hm), hours }
+    }
+
+    fn formatted(slots: Vec<(u32, u32)>) -> Vec<(String, String)> {
+        slots.into_iter().map(|(s, e)| (format_hm(s), format_hm(e))).collect()
+    }
+
+    #[test]
+    fn small_tasks_pack_around_fixed_slots_and_the_break() {
+        let window = WorkWindow::default();
+        let slots = pack_day(&window, &[
+            slot(None, None, 2.0),
+            slot(Some("10:00"), None, 1.0),
+            slot(None, None, 1.5),
+            slot(None, Some("18:00"), 2.0),
+            slot(None, None, 3.0),
+        ]);
+        assert_eq!(formatted(slots), vec![
+            ("11:00".to_string(), "14:00".to_string()),
+            ("10:00".to_string(), "11:00".to_string()),
+            ("14:00".to_string(), "15:30".to_string()),
+            ("16:00".to_string(), "18:00".to_string()),
+            ("18:00".to_string(), "21:00".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn parse_hm_rejects_malformed_times() {
+        assert_eq!(parse_hm("09:30"), Some(570));
+        assert_eq!(parse_hm("9:05"), Some(545));
+        assert_eq!(parse_hm("24:00"), None);
+        assert_eq!(parse_hm("09:7"), None);
+        assert_eq!(parse_hm("nine"), None);
+    }
+}
@@ -0,0 +1,273 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strsim::jaro_winkler;
+use crate::db::settings_repo;
+use crate::models::enums::{Priority, TaskStatus, TaskType};
+
+/// Import fields whose values must be one of a fixed set.
+pub const ENUM_FIELDS: [&str; 3] = ["task_type", "priority", "status"];
+
+/// Similarity above which an unknown value is taken as the closest known one.
+const FUZZY_THRESHOLD: f64 = 0.88;
+/// Similarity above which the closest known value is offered as a suggestion.
+const SUGGESTION_THRESHOLD: f64 = 0.6;
+
+/// The values a field accepts.
+pub fn canonical_values(field: &str) -> Vec<&'static str> {
+    match field {
+        "task_type" => TaskType::all_values(),
+        "priority" => Priority::all_values(),
+        "status" => TaskStatus::all_values(),
+        _ => Vec::new(),
+    }
+}
+
+fn field_label(field: &str) -> &'static str {
+    match field {
+        "task_type" => "任务类型",
+        "priority" => "优先级",
+        "status" => "状态",
+        _ => "字段",
+    }
+}
+
+/// Spellings common in hand-made plans, matched case-insensitively.
+fn builtin_synonyms(field: &str) -> &'static [(&'static str, &'static str)] {
+    match field {
+        "task_type" => &[
+            ("需求", "需求澄清"), ("需求分析", "需求澄清"), ("预研", "技术预研"), ("调研", "技术预研"),
+            ("原型", "产品设计"), ("ue", "UE设计"), ("ux", "UE设计"), ("交互设计", "UE设计"),
+            ("架构", "架构设计"), ("概要设计", "架构设计"), ("开发", "代码开发"), ("编码", "代码开发"),
+            ("dev", "代码开发"), ("代码评审", "代码检查"), ("评审", "代码检查"), ("review", "代码检查"),
+            ("demo", "演示"), ("用例", "用例设计"), ("测试", "测试执行"), ("test", "测试执行"),
+            ("bug", "JIRA BUG"), ("缺陷", "JIRA BUG"), ("jira", "JIRA BUG"),
+        ],
+        "priority" => &[
+            // Plans label the codes "P0-紧急", "P1-高", "P2-中", so the bare words follow them.
+            ("紧急", "P0"), ("最高", "P0"), ("urgent", "P0"),
+            ("高", "P1"), ("high", "P1"),
+            ("中", "P2"), ("普通", "P2"), ("medium", "P2"), ("normal", "P2"), ("低", "P2"), ("low", "P2"),
+        ],
+        "status" => &[
+            ("未开始", "待开始"), ("新建", "待开始"), ("todo", "待开始"), ("to do", "待开始"),
+            ("open", "待开始"), ("new", "待开始"), ("开发中", "进行中"), ("处理中", "进行中"),
+            ("doing", "进行中"), ("in progress", "进行中"), ("wip", "进行中"),
+            ("完成", "已完成"), ("done", "已完成"), ("closed", "已完成"), ("resolved", "已完成"),
+            ("暂停", "暂停中"), ("挂起", "暂停中"), ("搁置", "暂停中"), ("on hold", "暂停中"),
+            ("取消", "已取消"), ("cancelled", "已取消"), ("canceled", "已取消"),
+        ],
+        _ => &[],
+    }
+}
+
+/// A value after normalization. `method` is how it was recognised: "exact",
+/// "dictionary" (a user mapping), "synonym", "fuzzy" or "unmapped" (left as given).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedValue {
+    pub value: String,
+    pub method: String,
+}
+
+/// A value no mapping recognised, for the user to map once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmappedValue {
+    pub field: String,
+    pub value: String,
+    pub count: usize,
+    pub suggestion: Option<String>,
+}
+
+/// Candidate spellings of a cell: as given, without bracketed notes or percentages
+/// ("进行中(50%)"), and each part of a code-and-label value ("P1-高").
+fn variants(raw: &str) -> Vec<String> {
+    let mut cleaned = String::new();
+    let mut depth = 0;
+    for c in raw.chars() {
+        match c {
+            '(' | '（' | '[' | '【' => depth += 1,
+            ')' | '）' | ']' | '】' => depth = (depth - 1).max(0),
+            _ if depth == 0 => cleaned.push(c),
+            _ => {}
+        }
+    }
+    let cleaned = cleaned.trim_end_matches(|c: char| c.is_ascii_digit() || c == '%' || c == '.' || c.is_whitespace());
+
+    let mut out = vec![raw.trim().to_lowercase()];
+    for candidate in std::iter::once(cleaned).chain(cleaned.split(['-', '－', '_', ':', '：', '/', ' '])) {
+        let candidate = candidate.trim().to_lowercase();
+        if !candidate.is_empty() && !out.contains(&candidate) {
+            out.push(candidate);
+        }
+    }
+    out
+}
+
+/// User dictionaries and built-in synonyms that turn spreadsheet spellings of
+/// task type, priority and status into the values the app uses.
+pub struct ValueNormalizer {
+    /// field -> lowercase spelling -> canonical value
+    dictionaries: HashMap<String, HashMap<String, String>>,
+}
+
+impl ValueNormalizer {
+    pub fn load(conn: &Connection) -> Result<Self, String> {
+        let dictionaries = get_value_mappings(conn)?
+            .into_iter()
+            .map(|(field, map)| (field, map.into_iter().map(|(k, v)| (k.trim().to_lowercase(), v)).collect()))
+            .collect();
+        Ok(ValueNormalizer { dictionaries })
+    }
+
+    /// Map a cell to a canonical value: a user mapping of the value as given wins, then
+    /// the value itself, a synonym, or either of those for a cleaned-up spelling, and
+    /// finally a close fuzzy match. Anything else is returned unchanged as "unmapped".
+    pub fn normalize(&self, field: &str, raw: &str) -> NormalizedValue {
+        let canonical = canonical_values(field);
+        let dictionary = self.dictionaries.get(field);
+        let found = |value: &str, method: &str| NormalizedValue { value: value.to_string(), method: method.to_string() };
+
+        for candidate in variants(raw) {
+            if let Some(value) = dictionary.and_then(|d| d.get(&candidate)) {
+                return found(value, "dictionary");
+            }
+            if let Some(value) = canonical.iter().find(|c| c.to_lowercase() == candidate) {
+                return found(value, "exact");
+            }
+            if let Some((_, value)) = builtin_synonyms(field).iter().find(|(s, _)| *s == candidate) {
+                return found(value, "synonym");
+            }
+        }
+        match self.closest(field, raw) {
+            Some((value, score)) if score >= FUZZY_THRESHOLD => found(&value, "fuzzy"),
+            _ => found(raw.trim(), "unmapped"),
+        }
+    }
+
+    /// The closest known value for a value `normalize` leaves unmapped, if any is close.
+    pub fn suggest(&self, field: &str, raw: &str) -> Option<String> {
+        self.closest(field, raw)
+            .filter(|(_, score)| *score >= SUGGESTION_THRESHOLD)
+            .map(|(value, _)| value)
+    }
+
+    /// Best jaro-winkler match among the canonical values, synonyms and user spellings.
+    fn closest(&self, field: &str, raw: &str) -> Option<(String, f64)> {
+        let mut spellings: Vec<(String, String)> = canonical_values(field)
+            .into_iter()
+            .map(|c| (c.to_lowercase(), c.to_string()))
+            .chain(builtin_synonyms(field).iter().map(|(s, c)| (s.to_string(), c.to_string())))
+            .collect();
+        if let Some(dictionary) = self.dictionaries.get(field) {
+            spellings.extend(dictionary.iter().map(|(s, c)| (s.clone(), c.clone())));
+        }
+        let candidates = variants(raw);
+        spellings
+            .into_iter()
+            .map(|(spelling, value)| {
+                let score = candidates.iter().map(|c| jaro_winkler(c, &spelling)).fold(0.0, f64::max);
+                (value, score)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+fn setting_key(field: &str) -> String {
+    format!("value_mapping.{}", field)
+}
+
+/// The user's mappings per enum field: spelling in the spreadsheet -> canonical value.
+pub fn get_value_mappings(conn: &Connection) -> Result<HashMap<String, HashMap<String, String>>, String> {
+    let settings = settings_repo::get_settings_by_category(conn, "value_mapping")
+        .map_err(|e| e.to_string())?;
+    let mut mappings = HashMap::new();
+    for field in ENUM_FIELDS {
+        let map: HashMap<String, String> = match settings.get(&setting_key(field)) {
+            Some(s) if !s.is_empty() => serde_json::from_str(s)
+                .map_err(|e| format!("Failed to parse {} mappings: {}", field, e))?,
+            _ => HashMap::new(),
+        };
+        mappings.insert(field.to_string(), map);
+    }
+    Ok(mappings)
+}
+
+fn validate_mappings(field: &str, mappings: &HashMap<String, String>) -> Result<(), String> {
+    let canonical = canonical_values(field);
+    if canonical.is_empty() {
+        return Err(format!("字段 {} 不支持值映射", field));
+    }
+    for (raw, value) in mappings {
+        if raw.trim().is_empty() {
+            return Err("映射的原始值不能为空".to_string());
+        }
+        if !canonical.contains(&value.as_str()) {
+            return Err(format!("「{}」不是有效的{}", value, field_label(field)));
+        }
+    }
+    Ok(())
+}
+
+/// Replace a field's mappings, as edited in settings.
+pub fn save_value_mappings(conn: &Connection, field: &str, mappings: &HashMap<String, String>) -> Result<(), String> {
+    validate_mappings(field, mappings)?;
+    let json = serde_json::to_string(mappings).map_err(|e| e.to_string())?;
+    settings_repo::set_setting(conn, &setting_key(field), &json, "value_mapping").map_err(|e| e.to_string())
+}
+
+/// Add mappings chosen during an import to a field's dictionary, keeping the others.
+pub fn remember_value_mappings(conn: &Connection, field: &str, mappings: &HashMap<String, String>) -> Result<(), String> {
+    validate_mappings(field, mappings)?;
+    let mut current = get_value_mappings(conn)?.remove(field).unwrap_or_default();
+    current.extend(mappings.iter().map(|(k, v)| (k.trim().to_string(), v.clone())));
+    save_value_mappings(conn, field, &current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        init::create_tables(&conn).expect("create tables");
+        init::run_migrations(&conn).expect("run migrations");
+        conn
+    }
+
+    #[test]
+    fn spreadsheet_spellings_map_to_canonical_values() {
+        let conn = setup_conn();
+        let normalizer = ValueNormalizer::load(&conn).unwrap();
+        let normalize = |field: &str, raw: &str| {
+            let n = normalizer.normalize(field, raw);
+            (n.value, n.method)
+        };
+        assert_eq!(normalize("task_type", "开发"), ("代码开发".into(), "synonym".into()));
+        assert_eq!(normalize("priority", "高"), ("P1".into(), "synonym".into()));
+        assert_eq!(normalize("priority", "中"), ("P2".into(), "synonym".into()));
+        assert_eq!(normalize("priority", "P1-高"), ("P1".into(), "exact".into()));
+        assert_eq!(normalize("priority", "p2"), ("P2".into(), "exact".into()));
+        assert_eq!(normalize("status", "完成"), ("已完成".into(), "synonym".into()));
+        assert_eq!(normalize("status", "Done"), ("已完成".into(), "synonym".into()));
+        assert_eq!(normalize("status", "进行中(50%)"), ("进行中".into(), "exact".into()));
+        assert_eq!(normalize("status", "进行中 80%"), ("进行中".into(), "exact".into()));
+        assert_eq!(normalize("status", "已完成了"), ("已完成".into(), "fuzzy".into()));
+        assert_eq!(normalize("status", "待评估"), ("待评估".into(), "unmapped".into()));
+    }
+
+    #[test]
+    fn remembered_mappings_win_and_are_validated() {
+        let conn = setup_conn();
+        let mapping = |raw: &str, value: &str| HashMap::from([(raw.to_string(), value.to_string())]);
+        remember_value_mappings(&conn, "status", &mapping("待评估", "待开始")).unwrap();
+        remember_value_mappings(&conn, "status", &mapping("Done", "暂停中")).unwrap();
+        assert!(remember_value_mappings(&conn, "status", &mapping("x", "P0")).unwrap_err().contains("状态"));
+        assert!(remember_value_mappings(&conn, "owner", &mapping("x", "y")).is_err());
+
+        let normalizer = ValueNormalizer::load(&conn).unwrap();
+        assert_eq!(normalizer.normalize("status", "待评估").value, "待开始");
+        assert_eq!(normalizer.normalize("status", "done").method, "dictionary");
+        assert_eq!(get_value_mappings(&conn).unwrap()["status"].len(), 2);
+    }
+}
@@ -0,0 +1,298 @@
+#!/usr/bin/env python3
+"""Regenerate the workbook fixtures used by the Excel reader tests.
+
+Every tasks.* file holds the same two sheets: a visible "任务" sheet with a header row
+and two tasks, and a hidden "说明" sheet. plan.xlsx is laid out like a hand-made plan: a
+title banner, a two-row header and a module name merged down its tasks. Only the
+standard library is used, so the files are written directly in each format's on-disk
+layout (OOXML, BIFF8 in a compound file, XLSB records, OpenDocument).
+
+    python3 generate_fixtures.py
+"""
+import os
+import struct
+import zipfile
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+SHEETS = [
+    ("任务", False, [["编号", "名称", "负责人", "计划工时"], ["T-1", "登录", "张三", 2], ["T-2", "导出", "李四", 1.5]]),
+    ("说明", True, [["仅供参考"]]),
+]
+
+PLAN_SHEETS = [
+    ("计划", False, [
+        ["2026 Q1 迭代计划"],
+        [],
+        ["所属模块", "任务名称", "负责人", "计划"],
+        ["", "", "", "开始", "结束"],
+        ["账号", "登录", "张三", "2026-03-02", "2026-03-03"],
+        ["", "注册", "李四", "2026-03-04", "2026-03-05"],
+        ["报表", "导出", "张三", "2026-03-06", "2026-03-06"],
+    ]),
+]
+
+# Merged regions of plan.xlsx; only the top-left cell of each holds a value.
+PLAN_MERGES = ["A1:E1", "A3:A4", "B3:B4", "C3:C4", "D3:E3", "A5:A6"]
+
+
+def write_zip(path, entries, stored_first=None):
+    # Fixed timestamps keep the output byte-identical between runs.
+    stamp = (2026, 1, 1, 0, 0, 0)
+    with zipfile.ZipFile(path, "w") as z:
+        if stored_first:
+            z.writestr(zipfile.ZipInfo(stored_first[0], stamp), stored_first[1], zipfile.ZIP_STORED)
+        for name, data in entries:
+            z.writestr(zipfile.ZipInfo(name, stamp), data, zipfile.ZIP_DEFLATED)
+
+
+def col_letter(c):
+    return "ABCDEFGHIJKLMNOPQRSTUVWXYZ"[c]
+
+
+# --- xlsx -----------------------------------------------------------------
+
+HIDDEN_STATE = ' state="hidden"'
+
+
+def xlsx(path, sheets=SHEETS, merges=()):
+    sheets_xml = "".join(
+        f'<sheet name="{name}" sheetId="{i + 1}"{HIDDEN_STATE if hidden else ""} r:id="rId{i + 1}"/>'
+        for i, (name, hidden, _) in enumerate(sheets)
+    )
+    entries = [
+        ("[Content_Types].xml",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">'
+         '<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>'
+         '<Default Extension="xml" ContentType="application/xml"/>'
+         '<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>'
+         + "".join(
+             f'<Override PartName="/xl/worksheets/sheet{i + 1}.xml" '
+             'ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>'
+             for i in range(len(sheets)))
+         + '</Types>'),
+        ("_rels/.rels",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+         '<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>'
+         '</Relationships>'),
+        ("xl/workbook.xml",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" '
+         'xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">'
+         f'<sheets>{sheets_xml}</sheets></workbook>'),
+        ("xl/_rels/workbook.xml.rels",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+         + "".join(
+             f'<Relationship Id="rId{i + 1}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" '
+             f'Target="worksheets/sheet{i + 1}.xml"/>'
+             for i in range(len(sheets)))
+         + '</Relationships>'),
+    ]
+    # Merged regions apply to the first sheet.
+    merge_xml = "".join(f'<mergeCell ref="{m}"/>' for m in merges)
+    for i, (_, _, rows) in enumerate(sheets):
+        body = ""
+        for r, row in enumerate(rows):
+            cells = ""
+            for c, v in enumerate(row):
+                ref = f"{col_letter(c)}{r + 1}"
+                if v == "":
+                    continue
+                if isinstance(v, str):
+                    cells += f'<c r="{ref}" t="inlineStr"><is><t>{v}</t></is></c>'
+                else:
+                    cells += f'<c r="{ref}"><v>{v}</v></c>'
+            body += f'<row r="{r + 1}">{cells}</row>'
+        entries.append((f"xl/worksheets/sheet{i + 1}.xml",
+                        '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+                        '<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">'
+                        f'<sheetData>{body}</sheetData>'
+                        + (f'<mergeCells count="{len(merges)}">{merge_xml}</mergeCells>' if i == 0 and merges else '')
+                        + '</worksheet>'))
+    write_zip(path, entries)
+
+
+# --- xls (BIFF8 in a compound file) -----------------------------------------
+
+def biff(typ, data=b""):
+    return struct.pack("<HH", typ, len(data)) + data
+
+
+def biff_bof(dt):
+    return biff(0x0809, struct.pack("<HHHHII", 0x0600, dt, 0x0DBB, 0x07CC, 0, 0x06))
+
+
+def short_unicode(s):
+    return struct.pack("<BB", len(s), 1) + s.encode("utf-16-le")
+
+
+def xls(path):
+    globals_prefix = biff_bof(0x0005) + biff(0x0042, struct.pack("<H", 1200))
+    bound_len = sum(4 + 6 + len(short_unicode(name)) for name, _, _ in SHEETS)
+    offset = len(globals_prefix) + bound_len + 4  # + EOF
+    sheet_streams = []
+    bound = b""
+    for name, hidden, rows in SHEETS:
+        cells = b""
+        for r, row in enumerate(rows):
+            for c, v in enumerate(row):
+                if isinstance(v, str):
+                    cells += biff(0x0204, struct.pack("<HHHHB", r, c, 0, len(v), 1) + v.encode("utf-16-le"))
+                else:
+                    cells += biff(0x0203, struct.pack("<HHHd", r, c, 0, float(v)))
+        width = max(len(row) for row in rows)
+        stream = biff_bof(0x0010) + biff(0x0200, struct.pack("<IIHHH", 0, len(rows), 0, width, 0)) + cells + biff(0x000A)
+        bound += biff(0x0085, struct.pack("<IBB", offset, 1 if hidden else 0, 0) + short_unicode(name))
+        sheet_streams.append(stream)
+        offset += len(stream)
+    workbook = globals_prefix + bound + biff(0x000A) + b"".join(sheet_streams)
+    # Keep the stream out of the mini stream (< 4096 bytes) to need only regular sectors.
+    workbook += b"\0" * (max(4096, -(-len(workbook) // 512) * 512) - len(workbook))
+
+    sector = 512
+    n_stream = len(workbook) // sector
+    endofchain, freesect, fatsect, nostream = 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFD, 0xFFFFFFFF
+    fat = [fatsect, endofchain] + [2 + i + 1 for i in range(n_stream - 1)] + [endofchain]
+    fat += [freesect] * (sector // 4 - len(fat))
+
+    header = bytearray(512)
+    header[0:8] = bytes.fromhex("D0CF11E0A1B11AE1")
+    struct.pack_into("<HHHHH", header, 24, 0x003E, 0x0003, 0xFFFE, 9, 6)
+    struct.pack_into("<IIIIIIIII", header, 40, 0, 1, 1, 0, 4096, endofchain, 0, endofchain, 0)
+    difat = [0] + [freesect] * 108
+    struct.pack_into("<109I", header, 76, *difat)
+
+    def entry(name, typ, child, start, size):
+        e = bytearray(128)
+        encoded = (name + "\0").encode("utf-16-le")
+        e[0:len(encoded)] = encoded
+        struct.pack_into("<HBB", e, 64, len(encoded), typ, 1)
+        struct.pack_into("<III", e, 68, nostream, nostream, child)
+        struct.pack_into("<II", e, 116, start, size)
+        return bytes(e)
+
+    directory = entry("Root Entry", 5, 1, endofchain, 0) + entry("Workbook", 2, nostream, 2, len(workbook))
+    directory += bytes(128) * 2
+    with open(path, "wb") as f:
+        f.write(bytes(header) + struct.pack(f"<{sector // 4}I", *fat) + directory + workbook)
+
+
+# --- xlsb -------------------------------------------------------------------
+
+def record(typ, data=b""):
+    out = bytes([typ]) if typ < 0x80 else bytes([(typ & 0x7F) | 0x80, typ >> 7])
+    n = len(data)
+    size = bytearray()
+    while True:
+        b = n & 0x7F
+        n >>= 7
+        size.append(b | (0x80 if n else 0))
+        if not n:
+            break
+    return out + bytes(size) + data
+
+
+def wide(s):
+    return struct.pack("<I", len(s)) + s.encode("utf-16-le")
+
+
+def xlsb(path):
+    workbook = record(0x0083)  # BrtBeginBook
+    workbook += record(0x008F)  # BrtBeginBundleShs
+    for i, (name, hidden, _) in enumerate(SHEETS):
+        workbook += record(0x009C, struct.pack("<II", 1 if hidden else 0, i + 1) + wide(f"rId{i + 1}") + wide(name))
+    workbook += record(0x0090)  # BrtEndBundleShs
+    workbook += record(0x009D, bytes(26))  # BrtCalcProp
+    workbook += record(0x0084)  # BrtEndBook
+
+    entries = [
+        ("[Content_Types].xml",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">'
+         '<Default Extension="bin" ContentType="application/vnd.ms-excel.sheet.binary.macroEnabled.main"/>'
+         '<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>'
+         '<Default Extension="xml" ContentType="application/xml"/>'
+         + "".join(
+             f'<Override PartName="/xl/worksheets/sheet{i + 1}.bin" ContentType="application/vnd.ms-excel.worksheet"/>'
+             for i in range(len(SHEETS)))
+         + '</Types>'),
+        ("_rels/.rels",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+         '<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.bin"/>'
+         '</Relationships>'),
+        ("xl/workbook.bin", workbook),
+        ("xl/_rels/workbook.bin.rels",
+         '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+         '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+         + "".join(
+             f'<Relationship Id="rId{i + 1}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" '
+             f'Target="worksheets/sheet{i + 1}.bin"/>'
+             for i in range(len(SHEETS)))
+         + '</Relationships>'),
+    ]
+    for i, (_, _, rows) in enumerate(SHEETS):
+        width = max(len(row) for row in rows)
+        sheet = record(0x0081)  # BrtBeginSheet
+        sheet += record(0x0094, struct.pack("<IIII", 0, len(rows) - 1, 0, width - 1))  # BrtWsDim
+        sheet += record(0x0091)  # BrtBeginSheetData
+        for r, row in enumerate(rows):
+            sheet += record(0x0000, struct.pack("<IIHHB", r, 0, 0x0F0, 0, 0) + bytes(8))  # BrtRowHdr
+            for c, v in enumerate(row):
+                if isinstance(v, str):
+                    sheet += record(0x0006, struct.pack("<II", c, 0) + wide(v))  # BrtCellSt
+                else:
+                    sheet += record(0x0005, struct.pack("<IId", c, 0, float(v)))  # BrtCellReal
+        sheet += record(0x0092)  # BrtEndSheetData
+        sheet += record(0x0082)  # BrtEndSheet
+        entries.append((f"xl/worksheets/sheet{i + 1}.bin", sheet))
+    write_zip(path, entries)
+
+
+# --- ods --------------------------------------------------------------------
+
+def ods(path):
+    tables = ""
+    for name, hidden, rows in SHEETS:
+        body = ""
+        for row in rows:
+            cells = ""
+            for v in row:
+                if isinstance(v, str):
+                    cells += f'<table:table-cell office:value-type="string"><text:p>{v}</text:p></table:table-cell>'
+                else:
+                    cells += f'<table:table-cell office:value-type="float" office:value="{v}"><text:p>{v}</text:p></table:table-cell>'
+            body += f"<table:table-row>{cells}</table:table-row>"
+        style = "ta2" if hidden else "ta1"
+        tables += f'<table:table table:name="{name}" table:style-name="{style}">{body}</table:table>'
+    content = (
+        '<?xml version="1.0" encoding="UTF-8"?>'
+        '<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" '
+        'xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" '
+        'xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" '
+        'xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" office:version="1.2">'
+        '<office:automatic-styles>'
+        '<style:style style:name="ta1" style:family="table"><style:table-properties table:display="true"/></style:style>'
+        '<style:style style:name="ta2" style:family="table"><style:table-properties table:display="false"/></style:style>'
+        '</office:automatic-styles>'
+        f'<office:body><office:spreadsheet>{tables}</office:spreadsheet></office:body>'
+        '</office:document-content>'
+    )
+    manifest = (
+        '<?xml version="1.0" encoding="UTF-8"?>'
+        '<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">'
+        '<manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>'
+        '<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>'
+        '</manifest:manifest>'
+    )
+    write_zip(path, [("content.xml", content), ("META-INF/manifest.xml", manifest)],
+              stored_first=("mimetype", "application/vnd.oasis.opendocument.spreadsheet"))
+
+
+if __name__ == "__main__":
+    for ext, writer in [("xlsx", xlsx), ("xls", xls), ("xlsb", xlsb), ("ods", ods)]:
+        writer(os.path.join(HERE, f"tasks.{ext}"))
+    xlsx(os.path.join(HERE, "plan.xlsx"), PLAN_SHEETS, PLAN_MERGES)
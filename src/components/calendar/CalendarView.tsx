@@ -4,7 +4,7 @@ import dayGridPlugin from '@fullcalendar/daygrid';
 import timeGridPlugin from '@fullcalendar/timegrid';
 import interactionPlugin from '@fullcalendar/interaction';
 import resourceTimelinePlugin from '@fullcalendar/resource-timeline';
-import { Select, Typography, Space, Tooltip, Segmented, Tag, Button, Badge, message } from 'antd';
+import { Select, Typography, Space, Tooltip, Segmented, Tag, Button, Badge, Modal, message } from 'antd';
 import { FullscreenOutlined, FullscreenExitOutlined, SaveOutlined, UndoOutlined } from '@ant-design/icons';
 import { calendarApi, taskApi, batchApi } from '../../lib/api';
 import { useDeveloperStore } from '../../stores/developerStore';
@@ -340,6 +340,36 @@ export const CalendarView: React.FC = () => {
         end: dto.planned_end,
         ownerId: dto.owner_id,
       });
+    } else if (!info.newResource) {
+      // 同一负责人内拖动：按工作日顺延，并提示将被连带顺延的任务
+      try {
+        const newStart = dto.planned_start!;
+        const preview = await taskApi.move(taskId, newStart, { ripple: true });
+        if (preview.length > 1) {
+          const confirmed = await new Promise<boolean>(resolve => Modal.confirm({
+            title: `移动该任务将顺延 ${preview.length - 1} 个任务`,
+            content: (
+              <ul style={{ paddingLeft: 16, margin: 0 }}>
+                {preview.map(m => (
+                  <li key={m.task_id}>{m.task_name}: {m.old_start ?? '-'} ~ {m.old_end ?? '-'} → {m.new_start} ~ {m.new_end}</li>
+                ))}
+              </ul>
+            ),
+            okText: '确认移动',
+            cancelText: '取消',
+            onOk: () => resolve(true),
+            onCancel: () => resolve(false),
+          }));
+          if (!confirmed) { info.revert(); return; }
+        }
+        await taskApi.move(taskId, newStart, { ripple: true, apply: true });
+        message.success('任务已更新');
+        reloadEvents();
+        if (ownerId) showWorkloadPopover(ownerId, ownerName);
+      } catch (e) {
+        info.revert();
+        message.error('更新失败: ' + String(e));
+      }
     } else {
       try {
         await taskApi.update(dto);
@@ -525,10 +555,25 @@ export const CalendarView: React.FC = () => {
 
   // ── Transform events ──
   const displayEvents = useMemo(() => {
+    // 迭代、里程碑、节假日、请假等背景信息不参与着色与过滤，原样保留（请假仍按人员过滤）
+    const isContext = (evt: CalendarEvent) => {
+      const type = ((evt.extendedProps || evt.ext_props || {}) as CalendarEventExtProps).event_type;
+      return type != null && type !== 'task';
+    };
+    const markers = events.filter(evt => {
+      if (!isContext(evt)) return false;
+      const props = (evt.extendedProps || evt.ext_props || {}) as CalendarEventExtProps;
+      if (props.event_type === 'absence' && selectedDevs.length > 0) {
+        return props.owner_id != null && selectedDevs.includes(props.owner_id);
+      }
+      return true;
+    });
+
     // 先排除被取消排期的事件（批量模式下乐观移除）
     let result = events.filter(evt => {
+      if (isContext(evt)) return false;
       const props = (evt.extendedProps || evt.ext_props || {}) as CalendarEventExtProps;
-      const mut = pendingMutations.get(props.task_id);
+      const mut = props.task_id != null ? pendingMutations.get(props.task_id) : undefined;
       return !mut?.unschedule;
     });
 
@@ -582,7 +627,7 @@ export const CalendarView: React.FC = () => {
       });
     }
 
-    return result;
+    return [...result, ...markers];
   }, [events, dimension, developers, sprints, hiddenLabels, selectedDevs, selectedSprints, pendingMutations]);
 
   // ── Transform resources ──
@@ -636,6 +681,7 @@ export const CalendarView: React.FC = () => {
   const getEventTooltip = (evt: any) => {
     const props = evt.extendedProps || {};
     const parts = [evt.title];
+    if (props.event_type === 'milestone' && props.sprint_name) parts.push(`迭代: ${props.sprint_name}`);
     if (props.owner_name) parts.push(`负责人: ${props.owner_name}`);
     if (props.task_type) parts.push(`类型: ${props.task_type}`);
     if (props.status) parts.push(`状态: ${props.status}`);
@@ -137,13 +137,21 @@ export const ExportDialog: React.FC<ExportDialogProps> = ({
       const defaultPath = buildDefaultExportFileName(selectedSprintName, exportCount);
       const filePath = await save({
         defaultPath,
-        filters: [{ name: 'Excel', extensions: ['xlsx'] }],
+        filters: [
+          { name: 'Excel', extensions: ['xlsx'] },
+          { name: 'CSV', extensions: ['csv'] },
+          { name: 'TSV', extensions: ['tsv'] },
+        ],
       });
 
       if (!filePath) return;
 
       setExporting(true);
-      await excelApi.export(filePath, filter);
+      if (/\.(csv|tsv)$/i.test(filePath)) {
+        await excelApi.exportCsv(filePath, filter);
+      } else {
+        await excelApi.export(filePath, filter);
+      }
       onClose();
       Modal.confirm({
         title: '导出成功',
@@ -1,12 +1,21 @@
-import React, { useState } from 'react';
-import { Steps, Button, Space, Upload, Table, Select, Alert, Progress, Typography, Card, Tag, Radio, Spin, message, Result } from 'antd';
+import React, { useEffect, useState } from 'react';
+import { Steps, Button, Space, Upload, Table, Select, Alert, Progress, Typography, Card, Tag, Radio, Spin, message, Result, Popconfirm } from 'antd';
 import { UploadOutlined, FileExcelOutlined, CheckCircleOutlined, WarningOutlined } from '@ant-design/icons';
 import { open } from '@tauri-apps/plugin-dialog';
-import { excelApi } from '../../lib/api';
-import type { SheetScore, ColumnMatch, ImportResult, ImportConflict } from '../../lib/types';
+import { excelApi, settingsApi } from '../../lib/api';
+import type { SheetScore, ColumnMatch, ImportResult, ImportConflict, ImportDryRun, ImportRowPreview, ImportHistory, ImportRollback, UnmappedValue } from '../../lib/types';
+import { TASK_TYPES, PRIORITIES, TASK_STATUSES } from '../../lib/types';
 
 const { Title, Text } = Typography;
 
+const ENUM_VALUES: Record<UnmappedValue['field'], string[]> = {
+  task_type: TASK_TYPES,
+  priority: PRIORITIES,
+  status: TASK_STATUSES,
+};
+
+const unmappedKey = (u: UnmappedValue) => `${u.field}:${u.value}`;
+
 export const ImportWizard: React.FC = () => {
   const [current, setCurrent] = useState(0);
   const [filePath, setFilePath] = useState('');
@@ -23,6 +32,12 @@ export const ImportWizard: React.FC = () => {
   const [conflicts, setConflicts] = useState<ImportConflict[]>([]);
   const [conflictMode, setConflictMode] = useState<string>('create_new');
   const [detectingConflicts, setDetectingConflicts] = useState(false);
+  const [dryRun, setDryRun] = useState<ImportDryRun | null>(null);
+  const [dryRunning, setDryRunning] = useState(false);
+  const [dryRunVersion, setDryRunVersion] = useState(0);
+  const [valueChoices, setValueChoices] = useState<Record<string, string>>({});
+  const [savingMappings, setSavingMappings] = useState(false);
+  const [history, setHistory] = useState<ImportHistory[]>([]);
 
   const TASK_FIELDS = [
     { value: 'task_type', label: '任务类型' },
@@ -37,13 +52,99 @@ export const ImportWizard: React.FC = () => {
     { value: 'planned_hours', label: '计划工时' },
     { value: 'status', label: '状态' },
   ];
+  const FIELD_LABELS: Record<string, string> = {
+    ...Object.fromEntries(TASK_FIELDS.map(f => [f.value, f.label])),
+    parent_number: '父级编号',
+    parent_name: '父级名称',
+  };
+  const ACTION_TAGS: Record<string, { color: string; label: string }> = {
+    create: { color: 'green', label: '新建' },
+    update: { color: 'blue', label: '更新' },
+    skip: { color: 'default', label: '跳过' },
+  };
+  const ISSUE_COLORS: Record<string, string> = { error: 'red', warning: 'orange', info: 'default' };
+
+  const loadHistory = () => {
+    excelApi.getHistory().then(setHistory).catch(() => setHistory([]));
+  };
+
+  useEffect(() => {
+    if (current === 0) loadHistory();
+  }, [current]);
+
+  const describeRollback = (r: ImportRollback) => {
+    const parts = [
+      r.tasks_deleted > 0 ? `删除 ${r.tasks_deleted} 条任务` : null,
+      r.tasks_restored > 0 ? `还原 ${r.tasks_restored} 条任务` : null,
+      r.developers_deleted > 0 ? `删除 ${r.developers_deleted} 名人员` : null,
+      r.sprints_deleted > 0 ? `删除 ${r.sprints_deleted} 个迭代` : null,
+    ].filter(Boolean);
+    const kept = r.kept.length > 0 ? `；仍被使用而保留: ${r.kept.join(', ')}` : '';
+    const edited = r.edited.length > 0 ? `；导入后已手动修改而保留: ${r.edited.join(', ')}` : '';
+    return (parts.join(', ') || '无数据变更') + kept + edited;
+  };
+
+  const handleRollback = async (importId: number) => {
+    try {
+      const result = await excelApi.rollbackImport(importId);
+      message.success(`已撤销导入: ${describeRollback(result)}`);
+      loadHistory();
+      return true;
+    } catch (e) {
+      message.error('撤销失败: ' + String(e));
+      return false;
+    }
+  };
+
+  // Conflict step: re-run the dry run whenever the conflict mode changes
+  useEffect(() => {
+    if (current !== 4) return;
+    let cancelled = false;
+    setDryRunning(true);
+    excelApi.dryRun(filePath, selectedSheet, columnMapping, conflictMode)
+      .then(result => {
+        if (cancelled) return;
+        setDryRun(result);
+        setValueChoices(Object.fromEntries(result.unmapped_values
+          .filter(u => u.suggestion)
+          .map(u => [unmappedKey(u), u.suggestion as string])));
+      })
+      .catch(e => { if (!cancelled) message.error('预演导入失败: ' + String(e)); })
+      .finally(() => { if (!cancelled) setDryRunning(false); });
+    return () => { cancelled = true; };
+  }, [current, conflictMode, dryRunVersion]);
+
+  // Remember the chosen mappings for unrecognised values, then preview again with them
+  const handleSaveValueMappings = async () => {
+    if (!dryRun) return;
+    const byField: Record<string, Record<string, string>> = {};
+    for (const u of dryRun.unmapped_values) {
+      const choice = valueChoices[unmappedKey(u)];
+      if (choice) (byField[u.field] ??= {})[u.value] = choice;
+    }
+    setSavingMappings(true);
+    try {
+      for (const [field, mappings] of Object.entries(byField)) {
+        await settingsApi.rememberValueMappings(field, mappings);
+      }
+      message.success('已保存取值映射，之后的导入会自动识别');
+      setDryRunVersion(v => v + 1);
+    } catch (e) {
+      message.error('保存取值映射失败: ' + String(e));
+    } finally {
+      setSavingMappings(false);
+    }
+  };
 
   // Step 1: Select file
   const handleSelectFile = async () => {
     try {
       const selected = await open({
         multiple: false,
-        filters: [{ name: 'Excel', extensions: ['xlsx', 'xls'] }],
+        filters: [
+          { name: 'Excel / ODS', extensions: ['xlsx', 'xlsm', 'xls', 'xlsb', 'ods'] },
+          { name: 'CSV / TSV', extensions: ['csv', 'tsv', 'txt'] },
+        ],
       });
       if (selected) {
         const path = selected as string;
@@ -100,6 +201,7 @@ export const ImportWizard: React.FC = () => {
     setDetectingConflicts(true);
     setConflicts([]);
     setConflictMode('create_new');
+    setDryRun(null);
     try {
       const detected = await excelApi.detectConflicts(filePath, selectedSheet, columnMapping);
       setConflicts(detected);
@@ -124,7 +226,7 @@ export const ImportWizard: React.FC = () => {
       if (result.rows_skipped > 0) parts.push(`跳过 ${result.rows_skipped} 条`);
       message.success(`导入完成: ${parts.join(', ')}`);
     } catch (e) {
-      message.error('导入失败: ' + String(e));
+      message.error('导入失败，未写入任何数据: ' + String(e));
     } finally {
       setImporting(false);
     }
@@ -211,6 +313,45 @@ export const ImportWizard: React.FC = () => {
           </Button>
         </Card>
       )}
+      {current === 0 && history.length > 0 && (
+        <Card size="small" title="导入记录" style={{ marginTop: 16 }}>
+          <Table
+            dataSource={history}
+            rowKey="id"
+            size="small"
+            pagination={{ pageSize: 5, size: 'small' }}
+            columns={[
+              { title: '时间', dataIndex: 'import_date', width: 170 },
+              {
+                title: '来源',
+                dataIndex: 'file_name',
+                ellipsis: true,
+                render: (v: string, record: ImportHistory) => record.sheet_name ? `${v} / ${record.sheet_name}` : v,
+              },
+              {
+                title: '新增 / 更新',
+                width: 110,
+                render: (_: unknown, record: ImportHistory) => `${record.rows_imported} / ${record.rows_updated}`,
+              },
+              {
+                title: '操作',
+                width: 100,
+                render: (_: unknown, record: ImportHistory) => record.rolled_back_at ? (
+                  <Tag>已撤销</Tag>
+                ) : (
+                  <Popconfirm
+                    title="撤销这次导入？"
+                    description="将删除其新建的任务并还原被更新任务的原值"
+                    onConfirm={() => handleRollback(record.id)}
+                  >
+                    <Button size="small" danger>撤销</Button>
+                  </Popconfirm>
+                ),
+              },
+            ]}
+          />
+        </Card>
+      )}
 
       {/* Step 1: Sheet Selection */}
       {current === 1 && (
@@ -403,6 +544,104 @@ export const ImportWizard: React.FC = () => {
               </Card>
             </div>
           )}
+          <Card size="small" title="导入预演" loading={dryRunning && !dryRun} style={{ marginBottom: 16 }}>
+            {dryRun && (
+              <>
+                <Alert
+                  type={dryRun.rows.some(r => r.issues.some(i => i.level !== 'info')) ? 'warning' : 'info'}
+                  message={`将新建 ${dryRun.rows_to_create} 条，更新 ${dryRun.rows_to_update} 条，跳过 ${dryRun.rows_to_skip} 条`}
+                  description={[
+                    dryRun.developers_to_create.length > 0 ? `新建人员: ${dryRun.developers_to_create.join(', ')}` : null,
+                    dryRun.sprints_to_create.length > 0 ? `新建迭代: ${dryRun.sprints_to_create.join(', ')}` : null,
+                  ].filter(Boolean).join('；') || undefined}
+                  style={{ marginBottom: 12 }}
+                />
+                {dryRun.unmapped_values.length > 0 && (
+                  <Card
+                    size="small"
+                    type="inner"
+                    title="未识别的取值"
+                    extra={
+                      <Button
+                        size="small"
+                        type="primary"
+                        loading={savingMappings}
+                        disabled={!dryRun.unmapped_values.some(u => valueChoices[unmappedKey(u)])}
+                        onClick={handleSaveValueMappings}
+                      >
+                        保存映射
+                      </Button>
+                    }
+                    style={{ marginBottom: 12 }}
+                  >
+                    <Text type="secondary">以下取值将原样保存。选择对应的标准值后保存，之后的导入会自动识别。</Text>
+                    <Table
+                      dataSource={dryRun.unmapped_values}
+                      rowKey={unmappedKey}
+                      pagination={false}
+                      size="small"
+                      style={{ marginTop: 8 }}
+                      columns={[
+                        { title: '字段', dataIndex: 'field', width: 100, render: (v: string) => FIELD_LABELS[v] ?? v },
+                        { title: '取值', dataIndex: 'value' },
+                        { title: '行数', dataIndex: 'count', width: 70 },
+                        {
+                          title: '对应为',
+                          width: 180,
+                          render: (_: unknown, record: UnmappedValue) => (
+                            <Select
+                              size="small"
+                              allowClear
+                              placeholder="保持原样"
+                              style={{ width: '100%' }}
+                              value={valueChoices[unmappedKey(record)]}
+                              options={ENUM_VALUES[record.field].map(v => ({ value: v, label: v }))}
+                              onChange={(v?: string) => setValueChoices(prev => ({ ...prev, [unmappedKey(record)]: v ?? '' }))}
+                            />
+                          ),
+                        },
+                      ]}
+                    />
+                  </Card>
+                )}
+                <Table
+                  dataSource={dryRun.rows}
+                  rowKey="row_index"
+                  pagination={false}
+                  size="small"
+                  scroll={{ y: 300 }}
+                  columns={[
+                    { title: '行号', dataIndex: 'row_index', width: 70, render: (v: number) => v + 1 },
+                    { title: '名称', dataIndex: 'name', width: 160, ellipsis: true, render: (v: string) => v || '-' },
+                    {
+                      title: '动作',
+                      dataIndex: 'action',
+                      width: 80,
+                      render: (v: string) => <Tag color={ACTION_TAGS[v]?.color}>{ACTION_TAGS[v]?.label ?? v}</Tag>,
+                    },
+                    {
+                      title: '字段变更',
+                      render: (_: unknown, record: ImportRowPreview) => record.changes.map(c => (
+                        <div key={c.field}>
+                          <Text type="secondary">{FIELD_LABELS[c.field] ?? c.field}: </Text>
+                          {record.action === 'update' && c.old_value != null && <Text delete>{c.old_value}</Text>}
+                          {record.action === 'update' && c.old_value != null && ' → '}
+                          <Text>{c.new_value}</Text>
+                        </div>
+                      )),
+                    },
+                    {
+                      title: '问题',
+                      width: 240,
+                      render: (_: unknown, record: ImportRowPreview) => record.issues.map((i, idx) => (
+                        <Tag key={idx} color={ISSUE_COLORS[i.level]} style={{ whiteSpace: 'normal', marginBottom: 4 }}>{i.message}</Tag>
+                      )),
+                    },
+                  ]}
+                />
+              </>
+            )}
+          </Card>
           <div style={{ textAlign: 'right', padding: '12px 0', borderTop: '1px solid #f0f0f0' }}>
             <Space>
               <Button onClick={() => setCurrent(3)}>上一步</Button>
@@ -451,9 +690,20 @@ export const ImportWizard: React.FC = () => {
                 )}
               </div>
               <Space>
-                <Button onClick={() => { setCurrent(0); setImportResult(null); setConflicts([]); setConflictMode('create_new'); }}>
+                <Button onClick={() => { setCurrent(0); setImportResult(null); setConflicts([]); setConflictMode('create_new'); setDryRun(null); }}>
                   再次导入
                 </Button>
+                <Popconfirm
+                  title="撤销本次导入？"
+                  description="将删除本次新建的任务并还原被更新任务的原值"
+                  onConfirm={async () => {
+                    if (await handleRollback(importResult.import_id)) {
+                      setCurrent(0); setImportResult(null); setConflicts([]); setConflictMode('create_new'); setDryRun(null);
+                    }
+                  }}
+                >
+                  <Button danger>撤销本次导入</Button>
+                </Popconfirm>
                 <Button type="primary" onClick={() => window.location.hash = '#/tasks'}>
                   查看任务列表
                 </Button>
@@ -1,9 +1,9 @@
 import React, { useEffect, useState } from 'react';
-import { Tabs, Form, Input, InputNumber, Select, Button, Table, Space, message, Popconfirm, Segmented, DatePicker, Tag } from 'antd';
+import { Tabs, Form, Input, InputNumber, Select, Button, Table, Space, message, Popconfirm, Segmented, DatePicker, TimePicker, Tag } from 'antd';
 import { PlusOutlined, DeleteOutlined, ApiOutlined, ArrowUpOutlined, ArrowDownOutlined } from '@ant-design/icons';
 import { useSettingsStore } from '../../stores/settingsStore';
-import { llmApi } from '../../lib/api';
-import type { LlmConfig, ExcelTemplateConfig, TemplateColumn, WorkHoursConfig, OvertimeConfig } from '../../lib/types';
+import { llmApi, settingsApi } from '../../lib/api';
+import type { LlmConfig, ExcelTemplateConfig, TemplateColumn, WorkHoursConfig, OvertimeConfig, ValueMappings } from '../../lib/types';
 import dayjs from 'dayjs';
 
 const TASK_FIELDS = [
@@ -160,12 +160,12 @@ const ExcelTemplateTab: React.FC = () => {
           ? excelTemplateConfig.export_columns
           : DEFAULT_EXPORT_COLUMNS
       );
-      setHeaderRow(excelTemplateConfig.header_row ?? 0);
+      setHeaderRow(excelTemplateConfig.header_row ?? undefined);
       setSheetName(excelTemplateConfig.default_sheet_name ?? '任务清单');
     } else {
       setColumns(DEFAULT_TEMPLATE_COLUMNS);
       setExportColumns(DEFAULT_EXPORT_COLUMNS);
-      setHeaderRow(0);
+      setHeaderRow(undefined);
       setSheetName('任务清单');
     }
   }, [excelTemplateConfig]);
@@ -250,7 +250,7 @@ const ExcelTemplateTab: React.FC = () => {
   const resetToDefault = () => {
     setColumns(DEFAULT_TEMPLATE_COLUMNS);
     setExportColumns(DEFAULT_EXPORT_COLUMNS);
-    setHeaderRow(0);
+    setHeaderRow(undefined);
     setSheetName('任务清单');
     message.info('已恢复为默认模板');
   };
@@ -320,7 +320,7 @@ const ExcelTemplateTab: React.FC = () => {
       </p>
       <Space style={{ marginBottom: 16 }}>
         <span>Header 行号:</span>
-        <InputNumber value={headerRow} onChange={v => setHeaderRow(v ?? undefined)} min={0} size="small" />
+        <InputNumber value={headerRow} onChange={v => setHeaderRow(v ?? undefined)} min={0} size="small" placeholder="自动识别" style={{ width: 100 }} />
         <span style={{ marginLeft: 16 }}>默认 Sheet:</span>
         <Input value={sheetName} onChange={e => setSheetName(e.target.value)} placeholder="Sheet 名称" size="small" style={{ width: 150 }} />
       </Space>
@@ -354,6 +354,70 @@ const ExcelTemplateTab: React.FC = () => {
         <Button type="primary" onClick={handleSave} loading={loading}>保存</Button>
         <Button onClick={resetToDefault}>恢复默认</Button>
       </Space>
+      <ValueMappingSection />
+    </div>
+  );
+};
+
+const VALUE_MAPPING_FIELDS: Record<string, string> = {
+  task_type: '任务类型',
+  priority: '优先级',
+  status: '状态',
+};
+
+/** Values remembered during imports, e.g. "高" -> "P0"; deleting one makes imports ask again. */
+const ValueMappingSection: React.FC = () => {
+  const [mappings, setMappings] = useState<ValueMappings>({});
+
+  const load = () => {
+    settingsApi.getValueMappings()
+      .then(setMappings)
+      .catch(e => message.error('加载取值映射失败: ' + String(e)));
+  };
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const handleDelete = async (field: string, raw: string) => {
+    const { [raw]: _, ...rest } = mappings[field] ?? {};
+    try {
+      await settingsApi.saveValueMappings(field, rest);
+      load();
+    } catch (e) {
+      message.error('删除失败: ' + String(e));
+    }
+  };
+
+  const rows = Object.entries(mappings).flatMap(([field, map]) =>
+    Object.entries(map).map(([raw, value]) => ({ key: `${field}:${raw}`, field, raw, value })));
+
+  return (
+    <div style={{ marginTop: 24 }}>
+      <div style={{ marginBottom: 8, fontWeight: 500 }}>导入取值映射</div>
+      <p style={{ color: '#888', marginBottom: 8 }}>
+        导入时将表格中的取值识别为标准值。常见写法（如"高"、"完成"、"Done"）已内置，此处为导入时保存的映射。
+      </p>
+      <Table
+        dataSource={rows}
+        pagination={false}
+        size="small"
+        locale={{ emptyText: '暂无映射' }}
+        columns={[
+          { title: '字段', dataIndex: 'field', width: 100, render: (v: string) => VALUE_MAPPING_FIELDS[v] ?? v },
+          { title: '表格中的取值', dataIndex: 'raw' },
+          { title: '识别为', dataIndex: 'value', render: (v: string) => <Tag>{v}</Tag> },
+          {
+            title: '操作',
+            width: 60,
+            render: (_: unknown, record: { field: string; raw: string }) => (
+              <Popconfirm title="确认删除?" onConfirm={() => handleDelete(record.field, record.raw)}>
+                <Button type="text" size="small" danger icon={<DeleteOutlined />} />
+              </Popconfirm>
+            ),
+          },
+        ]}
+      />
     </div>
   );
 };
@@ -448,6 +512,37 @@ const GeneralSettingsTab: React.FC = () => {
           用于"天"与"小时"之间的换算 (默认 8 小时 = 1 天)
         </div>
       </div>
+      <div style={{ marginBottom: 20 }}>
+        <div style={{ marginBottom: 8, fontWeight: 500 }}>每日工作时段</div>
+        <Space direction="vertical">
+          <TimePicker.RangePicker
+            format="HH:mm"
+            minuteStep={15}
+            allowClear={false}
+            value={[dayjs(localConfig.day_start, 'HH:mm'), dayjs(localConfig.day_end, 'HH:mm')]}
+            onChange={(v) => v && v[0] && v[1] && setLocalConfig({
+              ...localConfig,
+              day_start: v[0].format('HH:mm'),
+              day_end: v[1].format('HH:mm'),
+            })}
+          />
+          <TimePicker.RangePicker
+            format="HH:mm"
+            minuteStep={15}
+            allowClear={false}
+            placeholder={['午休开始', '午休结束']}
+            value={[dayjs(localConfig.break_start, 'HH:mm'), dayjs(localConfig.break_end, 'HH:mm')]}
+            onChange={(v) => v && v[0] && v[1] && setLocalConfig({
+              ...localConfig,
+              break_start: v[0].format('HH:mm'),
+              break_end: v[1].format('HH:mm'),
+            })}
+          />
+        </Space>
+        <div style={{ marginTop: 4, fontSize: 12, color: '#999' }}>
+          未设置具体时间的任务按顺序排入工作时段，跳过午休
+        </div>
+      </div>
       <div style={{ marginBottom: 20 }}>
         <div style={{ marginBottom: 8, fontWeight: 500 }}>固定加班日</div>
         <Segmented
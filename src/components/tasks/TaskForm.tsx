@@ -1,5 +1,5 @@
 import React, { useEffect, useState, useCallback } from 'react';
-import { Modal, Form, Input, Select, DatePicker, InputNumber, message, Tooltip } from 'antd';
+import { Modal, Form, Input, Select, DatePicker, TimePicker, InputNumber, message, Tooltip } from 'antd';
 import { useTaskStore } from '../../stores/taskStore';
 import { useDeveloperStore } from '../../stores/developerStore';
 import { useSprintStore } from '../../stores/sprintStore';
@@ -103,6 +103,8 @@ export const TaskForm: React.FC<TaskFormProps> = ({ visible, task, onClose }) =>
           ...task,
           planned_start: task.planned_start ? dayjs(task.planned_start) : undefined,
           planned_end: task.planned_end ? dayjs(task.planned_end) : undefined,
+          planned_start_time: task.planned_start_time ? dayjs(task.planned_start_time, 'HH:mm') : undefined,
+          planned_end_time: task.planned_end_time ? dayjs(task.planned_end_time, 'HH:mm') : undefined,
         });
       } else {
         form.resetFields();
@@ -118,6 +120,9 @@ export const TaskForm: React.FC<TaskFormProps> = ({ visible, task, onClose }) =>
         ...values,
         planned_start: values.planned_start?.format('YYYY-MM-DD'),
         planned_end: values.planned_end?.format('YYYY-MM-DD'),
+        // An empty string clears a previously set time on update
+        planned_start_time: values.planned_start_time?.format('HH:mm') ?? (task ? '' : undefined),
+        planned_end_time: values.planned_end_time?.format('HH:mm') ?? (task ? '' : undefined),
       };
 
       if (task) {
@@ -192,6 +197,12 @@ export const TaskForm: React.FC<TaskFormProps> = ({ visible, task, onClose }) =>
           <Form.Item name="planned_end" label="计划结束日期">
             <DatePicker style={{ width: '100%' }} disabledDate={disabledDate} cellRender={dateCellRender} />
           </Form.Item>
+          <Form.Item name="planned_start_time" label="开始时间" tooltip="留空表示全天，按工作时段自动排布">
+            <TimePicker format="HH:mm" minuteStep={15} style={{ width: '100%' }} />
+          </Form.Item>
+          <Form.Item name="planned_end_time" label="结束时间">
+            <TimePicker format="HH:mm" minuteStep={15} style={{ width: '100%' }} />
+          </Form.Item>
         </div>
       </Form>
     </Modal>
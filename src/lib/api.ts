@@ -1,10 +1,18 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
-  Task, CreateTaskDto, UpdateTaskDto, TaskFilter,
-  Developer, CreateDeveloperDto, UpdateDeveloperDto,
-  Sprint, Project, CreateSprintDto, UpdateSprintDto, DeleteSprintResult,
-  CalendarEvent, CalendarResource, DeveloperWorkload,
-  ExcelFileInfo, SheetScore, ColumnMatch, ImportResult, ImportHistory, ImportConflict,
+  Task, CreateTaskDto, UpdateTaskDto, TaskFilter, TaskDependency, TaskMove, MoveMode, DailyAllocation,
+  Developer, CreateDeveloperDto, UpdateDeveloperDto, AssignmentScore,
+  OffboardDeveloperDto, OffboardReport,
+  CapacityRule, CreateCapacityRuleDto, DailyCapacity, OvertimeConfig,
+  Team, CreateTeamDto, UpdateTeamDto, TeamCapacitySummary,
+  Sprint, Project, CreateSprintDto, UpdateSprintDto, DeleteSprintResult, SprintCapacitySummary,
+  Milestone, CreateMilestoneDto, UpdateMilestoneDto, Baseline, BaselineVariance,
+  Scenario, CreateScenarioDto, ScenarioDeveloper, CreateScenarioDeveloperDto, ScenarioTask, ScenarioTaskEdit,
+  ScenarioComparison,
+  CalendarEvent, CalendarEventType, CalendarResource, DeveloperWorkload, AvailabilityEstimate, ScheduleViolation, LevelingPlan,
+  HolidaySource, HolidayYearSummary, RegionCalendar, CreateRegionCalendarDto, UpdateRegionCalendarDto,
+  CalendarOverride, CreateCalendarOverrideDto, UpdateCalendarOverrideDto,
+  ExcelFileInfo, SheetScore, ColumnMatch, ImportResult, ImportHistory, ImportConflict, ImportDryRun, ImportRollback, ValueMappings,
   LlmConfig, ExcelTemplateConfig, BatchResult,
   ChatMessage, LlmChatResponse, ChatAction, TaskGroup, ScheduleSuggestion,
   StandupDocument, SaveStandupDocumentRequest,
@@ -18,6 +26,23 @@ export const taskApi = {
   update: (dto: UpdateTaskDto) => invoke<void>('update_task', { dto }),
   delete: (id: number) => invoke<void>('delete_task', { id }),
   count: () => invoke<number>('count_tasks'),
+  suggestOwner: (taskId: number, limit?: number) =>
+    invoke<AssignmentScore[]>('suggest_task_owner', { taskId, limit }),
+  /** 未传 apply 时仅预览，返回将被修改的任务 */
+  move: (taskId: number, newStart: string, opts: { mode?: MoveMode; ripple?: boolean; apply?: boolean } = {}) =>
+    invoke<TaskMove[]>('move_task', {
+      taskId, newStart, mode: opts.mode ?? null, ripple: opts.ripple ?? null, apply: opts.apply ?? null,
+    }),
+  listDependencies: (taskId: number) => invoke<TaskDependency[]>('list_task_dependencies', { taskId }),
+  addDependency: (predecessorId: number, successorId: number) =>
+    invoke<void>('add_task_dependency', { predecessorId, successorId }),
+  removeDependency: (predecessorId: number, successorId: number) =>
+    invoke<void>('remove_task_dependency', { predecessorId, successorId }),
+  listDailyAllocations: (taskId: number) =>
+    invoke<DailyAllocation[]>('list_task_daily_allocations', { taskId }),
+  /** 传空数组取消固定，恢复自动分配 */
+  setDailyAllocations: (taskId: number, allocations: DailyAllocation[]) =>
+    invoke<void>('set_task_daily_allocations', { taskId, allocations }),
 };
 
 // Developer API
@@ -27,6 +52,17 @@ export const developerApi = {
   create: (dto: CreateDeveloperDto) => invoke<number>('create_developer', { dto }),
   update: (dto: UpdateDeveloperDto) => invoke<void>('update_developer', { dto }),
   delete: (id: number) => invoke<void>('delete_developer', { id }),
+  offboard: (dto: OffboardDeveloperDto) => invoke<OffboardReport>('offboard_developer', { dto }),
+  listCapacityRules: (developerId: number) =>
+    invoke<CapacityRule[]>('list_capacity_rules', { developerId }),
+  createCapacityRule: (dto: CreateCapacityRuleDto) => invoke<number>('create_capacity_rule', { dto }),
+  deleteCapacityRule: (id: number) => invoke<void>('delete_capacity_rule', { id }),
+  getEffectiveCapacity: (developerId: number, startDate: string, endDate: string) =>
+    invoke<DailyCapacity[]>('get_effective_capacity', { developerId, startDate, endDate }),
+  getOvertime: (developerId: number) =>
+    invoke<OvertimeConfig | null>('get_developer_overtime', { developerId }),
+  setOvertime: (developerId: number, config: OvertimeConfig | null) =>
+    invoke<void>('set_developer_overtime', { developerId, config }),
 };
 
 // Sprint API
@@ -35,6 +71,13 @@ export const sprintApi = {
   create: (dto: CreateSprintDto) => invoke<number>('create_sprint', { dto }),
   update: (dto: UpdateSprintDto) => invoke<Sprint>('update_sprint', { dto }),
   delete: (id: number) => invoke<DeleteSprintResult>('delete_sprint', { id }),
+  getCapacity: (sprintId: number, teamId?: number) =>
+    invoke<SprintCapacitySummary>('get_sprint_capacity', { sprintId, teamId: teamId ?? null }),
+  listMilestones: (startDate?: string, endDate?: string) =>
+    invoke<Milestone[]>('list_milestones', { startDate: startDate ?? null, endDate: endDate ?? null }),
+  createMilestone: (dto: CreateMilestoneDto) => invoke<number>('create_milestone', { dto }),
+  updateMilestone: (dto: UpdateMilestoneDto) => invoke<void>('update_milestone', { dto }),
+  deleteMilestone: (id: number) => invoke<void>('delete_milestone', { id }),
 };
 
 // Project API
@@ -44,18 +87,93 @@ export const projectApi = {
     invoke<number>('create_project', { dto }),
 };
 
+// Baseline API
+export const baselineApi = {
+  list: (sprintId?: number) => invoke<Baseline[]>('list_baselines', { sprintId: sprintId ?? null }),
+  save: (name: string, sprintId?: number) =>
+    invoke<Baseline>('save_baseline', { name, sprintId: sprintId ?? null }),
+  delete: (id: number) => invoke<void>('delete_baseline', { id }),
+  getVariance: (baselineId: number) => invoke<BaselineVariance>('get_baseline_variance', { baselineId }),
+};
+
+// Scenario API
+export const scenarioApi = {
+  list: () => invoke<Scenario[]>('list_scenarios'),
+  create: (dto: CreateScenarioDto) => invoke<number>('create_scenario', { dto }),
+  delete: (id: number) => invoke<void>('delete_scenario', { id }),
+  listTasks: (scenarioId: number) => invoke<ScenarioTask[]>('list_scenario_tasks', { scenarioId }),
+  updateTask: (edit: ScenarioTaskEdit) => invoke<ScenarioTask>('update_scenario_task', { edit }),
+  resetTask: (scenarioId: number, taskId: number) =>
+    invoke<void>('reset_scenario_task', { scenarioId, taskId }),
+  moveTask: (scenarioId: number, taskId: number, newStart: string, mode?: MoveMode, ripple?: boolean) =>
+    invoke<TaskMove[]>('move_scenario_task', {
+      scenarioId, taskId, newStart, mode: mode ?? null, ripple: ripple ?? null,
+    }),
+  listDevelopers: (scenarioId: number) =>
+    invoke<ScenarioDeveloper[]>('list_scenario_developers', { scenarioId }),
+  addDeveloper: (dto: CreateScenarioDeveloperDto) => invoke<number>('add_scenario_developer', { dto }),
+  removeDeveloper: (scenarioId: number, developerId: number) =>
+    invoke<void>('remove_scenario_developer', { scenarioId, developerId }),
+  /** leftId / rightId 为空表示当前计划 */
+  compare: (startDate: string, endDate: string, leftId?: number, rightId?: number) =>
+    invoke<ScenarioComparison>('compare_scenarios', {
+      leftId: leftId ?? null, rightId: rightId ?? null, startDate, endDate,
+    }),
+  apply: (id: number) => invoke<number>('apply_scenario', { id }),
+};
+
 // Calendar API
+// Team API
+export const teamApi = {
+  list: () => invoke<Team[]>('list_teams'),
+  create: (dto: CreateTeamDto) => invoke<number>('create_team', { dto }),
+  update: (dto: UpdateTeamDto) => invoke<void>('update_team', { dto }),
+  delete: (id: number) => invoke<void>('delete_team', { id }),
+  getWorkload: (teamId: number, startDate: string, endDate: string, includeOvertime?: boolean) =>
+    invoke<DeveloperWorkload[]>('get_team_workload', {
+      teamId, startDate, endDate, includeOvertime: includeOvertime ?? false,
+    }),
+  getCapacity: (teamId: number, startDate: string, endDate: string) =>
+    invoke<TeamCapacitySummary>('get_team_capacity', { teamId, startDate, endDate }),
+};
+
 export const calendarApi = {
-  getEvents: (startDate: string, endDate: string, developerId?: number) =>
+  getEvents: (startDate: string, endDate: string, developerId?: number, eventTypes?: CalendarEventType[]) =>
     invoke<CalendarEvent[]>('get_calendar_events', {
-      startDate, endDate, developerId: developerId ?? null,
+      startDate, endDate, developerId: developerId ?? null, eventTypes: eventTypes ?? null,
     }),
-  getResources: () => invoke<CalendarResource[]>('get_calendar_resources'),
+  getResources: (teamId?: number) =>
+    invoke<CalendarResource[]>('get_calendar_resources', { teamId: teamId ?? null }),
   getWorkload: (developerId: number, startDate: string, endDate: string, includeOvertime?: boolean) =>
     invoke<DeveloperWorkload[]>('get_developer_workload', {
       developerId, startDate, endDate, includeOvertime: includeOvertime ?? false,
     }),
-  syncHolidays: (year: number) => invoke<number>('sync_holidays', { year }),
+  scanViolations: (startDate: string, endDate: string, teamId?: number) =>
+    invoke<ScheduleViolation[]>('scan_schedule_violations', { startDate, endDate, teamId: teamId ?? null }),
+  levelResources: (startDate: string, endDate: string, teamId?: number) =>
+    invoke<LevelingPlan>('level_resources', { startDate, endDate, teamId: teamId ?? null }),
+  estimateAvailability: (developerId: number, hours: number, fromDate?: string) =>
+    invoke<AvailabilityEstimate>('estimate_availability', { developerId, hours, fromDate: fromDate ?? null }),
+  findSoonestAvailable: (hours: number, fromDate?: string, teamId?: number) =>
+    invoke<AvailabilityEstimate[]>('find_soonest_available', {
+      hours, fromDate: fromDate ?? null, teamId: teamId ?? null,
+    }),
+  syncHolidays: (year: number, source?: HolidaySource, region?: string) =>
+    invoke<number>('sync_holidays', { year, source: source ?? null, region: region ?? null }),
+  importHolidaysFromFile: (path: string, region?: string) =>
+    invoke<HolidayYearSummary[]>('import_holidays_from_file', { path, region: region ?? null }),
+  listCachedHolidayYears: () => invoke<HolidayYearSummary[]>('list_cached_holiday_years'),
+  listRegionCalendars: () => invoke<RegionCalendar[]>('list_region_calendars'),
+  createRegionCalendar: (dto: CreateRegionCalendarDto) => invoke<number>('create_region_calendar', { dto }),
+  updateRegionCalendar: (dto: UpdateRegionCalendarDto) => invoke<void>('update_region_calendar', { dto }),
+  deleteRegionCalendar: (id: number) => invoke<void>('delete_region_calendar', { id }),
+  listOverrides: (startDate?: string, endDate?: string) =>
+    invoke<CalendarOverride[]>('list_calendar_overrides', {
+      startDate: startDate ?? null, endDate: endDate ?? null,
+    }),
+  createOverride: (dto: CreateCalendarOverrideDto) => invoke<number>('create_calendar_override', { dto }),
+  updateOverride: (dto: UpdateCalendarOverrideDto) => invoke<void>('update_calendar_override', { dto }),
+  deleteOverride: (id: number) => invoke<void>('delete_calendar_override', { id }),
 };
 
 // Excel API
@@ -68,11 +186,24 @@ export const excelApi = {
     invoke<[string[], string[][]]>('preview_excel_import', { filePath, sheetName, limit }),
   import: (filePath: string, sheetName: string, columnMapping: Record<string, string>, conflictMode?: string) =>
     invoke<ImportResult>('import_excel', { filePath, sheetName, columnMapping, conflictMode: conflictMode || 'create_new' }),
+  dryRun: (filePath: string, sheetName: string, columnMapping: Record<string, string>, conflictMode?: string) =>
+    invoke<ImportDryRun>('dry_run_excel_import', { filePath, sheetName, columnMapping, conflictMode: conflictMode || 'create_new' }),
   export: (filePath: string, filter: TaskFilter = {}) =>
     invoke<string>('export_excel', { filePath, filter }),
+  exportCsv: (filePath: string, filter: TaskFilter = {}) =>
+    invoke<string>('export_csv', { filePath, filter }),
+  previewPasted: (text: string) =>
+    invoke<[string[], string[][]]>('preview_pasted_table', { text }),
+  matchPastedColumns: (text: string) =>
+    invoke<ColumnMatch[]>('match_pasted_columns', { text }),
+  importPasted: (text: string, columnMapping: Record<string, string>, conflictMode?: string) =>
+    invoke<ImportResult>('import_pasted_table', { text, columnMapping, conflictMode: conflictMode || 'create_new' }),
+  dryRunPasted: (text: string, columnMapping: Record<string, string>, conflictMode?: string) =>
+    invoke<ImportDryRun>('dry_run_pasted_import', { text, columnMapping, conflictMode: conflictMode || 'create_new' }),
   revealInFolder: (filePath: string) =>
     invoke<void>('reveal_in_folder', { filePath }),
   getHistory: () => invoke<ImportHistory[]>('get_import_history'),
+  rollbackImport: (importId: number) => invoke<ImportRollback>('rollback_import', { importId }),
   detectConflicts: (filePath: string, sheetName: string, columnMapping: Record<string, string>) =>
     invoke<ImportConflict[]>('detect_excel_conflicts', { filePath, sheetName, columnMapping }),
 };
@@ -87,6 +218,11 @@ export const settingsApi = {
   getSetting: (key: string) => invoke<string | null>('get_setting', { key }),
   saveSetting: (key: string, value: string, category: string) =>
     invoke<void>('save_setting', { key, value, category }),
+  getValueMappings: () => invoke<ValueMappings>('get_value_mappings'),
+  saveValueMappings: (field: string, mappings: Record<string, string>) =>
+    invoke<void>('save_value_mappings', { field, mappings }),
+  rememberValueMappings: (field: string, mappings: Record<string, string>) =>
+    invoke<void>('remember_value_mappings', { field, mappings }),
 };
 
 // Batch API
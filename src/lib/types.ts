@@ -12,12 +12,16 @@ export interface Task {
   priority?: string;
   planned_start?: string;
   planned_end?: string;
+  /** 开始日/结束日的具体时间 (HH:MM)，为空表示全天 */
+  planned_start_time?: string;
+  planned_end_time?: string;
   planned_hours?: number;
   parent_task_id?: number;
   parent_number?: string;
   parent_name?: string;
   status?: string;
   co_owners?: CoOwner[];
+  required_skills: SkillLevel[];
 }
 
 export interface CoOwner {
@@ -35,12 +39,15 @@ export interface CreateTaskDto {
   priority?: string;
   planned_start?: string;
   planned_end?: string;
+  planned_start_time?: string;
+  planned_end_time?: string;
   planned_hours?: number;
   parent_task_id?: number;
   parent_number?: string;
   parent_name?: string;
   status?: string;
   co_owner_ids?: number[];
+  required_skills?: SkillLevel[];
 }
 
 export interface UpdateTaskDto {
@@ -54,12 +61,15 @@ export interface UpdateTaskDto {
   priority?: string;
   planned_start?: string;
   planned_end?: string;
+  planned_start_time?: string;
+  planned_end_time?: string;
   planned_hours?: number;
   parent_task_id?: number;
   parent_number?: string;
   parent_name?: string;
   status?: string;
   co_owner_ids?: number[];
+  required_skills?: SkillLevel[];
 }
 
 export interface TaskFilter {
@@ -75,6 +85,36 @@ export interface TaskFilter {
   end_date?: string;
   /** 按ID列表精确过滤 by AI.Coding */
   task_ids?: number[];
+  /** 负责人或协作人属于该团队 */
+  team_id?: number;
+}
+
+/** successor 须在 predecessor 结束后开始 */
+export interface TaskDependency {
+  predecessor_id: number;
+  successor_id: number;
+}
+
+/** 任务某一天手动固定的工时，其余工时自动分配 */
+export interface DailyAllocation {
+  task_id: number;
+  date: string;
+  hours: number;
+}
+
+/** workdays 保持工作日天数；hours 按负责人每日可用工时排完计划工时 */
+export type MoveMode = 'workdays' | 'hours';
+
+export interface TaskMove {
+  task_id: number;
+  task_name: string;
+  owner_id?: number;
+  old_start?: string;
+  old_end?: string;
+  new_start: string;
+  new_end: string;
+  /** moved 为拖动的任务，dependency / same_owner 为被连带顺延的任务；资源平衡另有 extended（延长）/ shifted（顺延） */
+  reason: 'moved' | 'dependency' | 'same_owner' | 'extended' | 'shifted';
 }
 
 // Developer types
@@ -83,17 +123,111 @@ export interface Developer {
   name: string;
   roles: string[];
   skills: string[];
+  skill_levels: SkillLevel[];
   max_hours_per_day: number;
   avatar_color: string;
   is_active: boolean;
+  /** 工作日历；为空时使用默认日历 */
+  calendar_id?: number;
+}
+
+// Team types
+export interface Team {
+  id: number;
+  name: string;
+  description?: string;
+  color?: string;
+  member_ids: number[];
+}
+
+export interface CreateTeamDto {
+  name: string;
+  description?: string;
+  color?: string;
+  member_ids?: number[];
+}
+
+export interface UpdateTeamDto {
+  id: number;
+  name?: string;
+  description?: string;
+  color?: string;
+  member_ids?: number[];
+}
+
+export interface MemberUtilization {
+  developer_id: number;
+  developer_name: string;
+  /** 按成员自己的日历计算的工作日数 */
+  workdays: number;
+  capacity_hours: number;
+  allocated_hours: number;
+  utilization: number;
+}
+
+export interface TeamCapacitySummary {
+  team_id: number;
+  team_name: string;
+  start_date: string;
+  end_date: string;
+  capacity_hours: number;
+  allocated_hours: number;
+  utilization: number;
+  members: MemberUtilization[];
+}
+
+/** 技能熟练度 1-5；用于任务时表示所需最低等级 */
+export interface SkillLevel {
+  name: string;
+  level: number;
+}
+
+export interface TaskReassignment {
+  task_id: number;
+  developer_id: number;
+}
+
+export interface OffboardDeveloperDto {
+  developer_id: number;
+  assignments?: TaskReassignment[];
+  /** 未单独指定的任务交给此成员；为空时按分派评分自动选择 */
+  default_assignee_id?: number;
+}
+
+export interface ReassignedTask {
+  task_id: number;
+  task_name: string;
+  role: 'owner' | 'co_owner';
+  to_developer_id?: number;
+  to_developer_name?: string;
+  reason: string;
+}
+
+export interface OffboardReport {
+  developer_id: number;
+  developer_name: string;
+  moved: ReassignedTask[];
+  retained_history_tasks: number;
+}
+
+export interface AssignmentScore {
+  developer_id: number;
+  developer_name: string;
+  score: number;
+  skill_fit: number;
+  load_ratio: number;
+  missing_skills: string[];
+  reasons: string[];
 }
 
 export interface CreateDeveloperDto {
   name: string;
   roles?: string[];
   skills?: string[];
+  skill_levels?: SkillLevel[];
   max_hours_per_day?: number;
   avatar_color?: string;
+  calendar_id?: number;
 }
 
 export interface UpdateDeveloperDto {
@@ -101,9 +235,45 @@ export interface UpdateDeveloperDto {
   name?: string;
   roles?: string[];
   skills?: string[];
+  skill_levels?: SkillLevel[];
   max_hours_per_day?: number;
   avatar_color?: string;
   is_active?: boolean;
+  calendar_id?: number;
+  /** 清除人员日历，改用默认日历 */
+  clear_calendar?: boolean;
+}
+
+/** 容量规则：set 覆盖为固定小时，ratio 按比例折算，reduce 扣减小时 */
+export type CapacityRuleMode = 'set' | 'ratio' | 'reduce';
+
+export interface CapacityRule {
+  id: number;
+  developer_id: number;
+  mode: CapacityRuleMode;
+  value: number;
+  start_date?: string;
+  end_date?: string;
+  /** 0=周一 .. 6=周日，为空表示每天 */
+  weekday?: number;
+  note?: string;
+}
+
+export interface CreateCapacityRuleDto {
+  developer_id: number;
+  mode: CapacityRuleMode;
+  value: number;
+  start_date?: string;
+  end_date?: string;
+  weekday?: number;
+  note?: string;
+}
+
+export interface DailyCapacity {
+  date: string;
+  is_workday: boolean;
+  base_hours: number;
+  hours: number;
 }
 
 // Sprint/Project types
@@ -148,7 +318,180 @@ export interface DeleteSprintResult {
   unlinked_tasks: number;
 }
 
+/** 迭代容量：每位成员的工作日按其所属日历计算 */
+export interface SprintCapacitySummary {
+  sprint_id: number;
+  sprint_name: string;
+  start_date: string;
+  end_date: string;
+  capacity_hours: number;
+  allocated_hours: number;
+  utilization: number;
+  members: MemberUtilization[];
+}
+
+/** 里程碑：日历上的单日节点，可关联迭代 */
+export interface Milestone {
+  id: number;
+  name: string;
+  date: string;
+  sprint_id?: number;
+  sprint_name?: string;
+  note?: string;
+}
+
+export interface CreateMilestoneDto {
+  name: string;
+  date: string;
+  sprint_id?: number;
+  note?: string;
+}
+
+export interface UpdateMilestoneDto {
+  id: number;
+  name?: string;
+  date?: string;
+  sprint_id?: number;
+  note?: string;
+}
+
+/** 排期基线：保存时刻的任务计划快照，可限定某个迭代 */
+export interface Baseline {
+  id: number;
+  name: string;
+  sprint_id?: number;
+  sprint_name?: string;
+  created_at: string;
+  task_count: number;
+}
+
+export type VarianceChange = 'added' | 'removed' | 'changed' | 'unchanged';
+
+/** 单个任务相对基线的变化；slip 为日历天数，正数表示推迟 */
+export interface TaskVariance {
+  task_id: number;
+  task_name: string;
+  owner_name?: string;
+  change: VarianceChange;
+  baseline_start?: string;
+  baseline_end?: string;
+  current_start?: string;
+  current_end?: string;
+  start_slip_days?: number;
+  end_slip_days?: number;
+  baseline_hours?: number;
+  current_hours?: number;
+  hours_delta: number;
+}
+
+export interface BaselineVariance {
+  baseline: Baseline;
+  tasks: TaskVariance[];
+  added_count: number;
+  removed_count: number;
+  changed_count: number;
+  slipped_count: number;
+  max_slip_days: number;
+  baseline_hours: number;
+  current_hours: number;
+  hours_growth: number;
+}
+
+/** 假设方案：只保存方案内修改过的任务与方案专属成员，其余沿用当前计划 */
+export interface Scenario {
+  id: number;
+  name: string;
+  description?: string;
+  created_at: string;
+  /** 已应用到当前计划的时间；应用后方案只读 */
+  applied_at?: string;
+  task_count: number;
+}
+
+export interface CreateScenarioDto {
+  name: string;
+  description?: string;
+}
+
+/** 仅存在于方案中的成员（如拟引入的外包），应用方案时创建为正式成员 */
+export interface ScenarioDeveloper {
+  id: number;
+  scenario_id: number;
+  name: string;
+  max_hours_per_day: number;
+}
+
+export interface CreateScenarioDeveloperDto {
+  scenario_id: number;
+  name: string;
+  max_hours_per_day?: number;
+}
+
+export interface ScenarioTask {
+  scenario_id: number;
+  task_id: number;
+  task_name: string;
+  owner_id?: number;
+  scenario_developer_id?: number;
+  planned_start?: string;
+  planned_end?: string;
+  planned_hours?: number;
+  /** 方案首次修改该任务时的实际值，应用时只写入与之不同的字段 */
+  base_owner_id?: number;
+  base_start?: string;
+  base_end?: string;
+  base_hours?: number;
+}
+
+/** 方案内任务修改；未填字段保持不变，scenario_developer_id 表示改派给方案成员 */
+export interface ScenarioTaskEdit {
+  scenario_id: number;
+  task_id: number;
+  owner_id?: number;
+  scenario_developer_id?: number;
+  planned_start?: string;
+  planned_end?: string;
+  planned_hours?: number;
+}
+
+/** 方案在日期范围内的概况；scenario_id 为空表示当前计划 */
+export interface ScenarioSummary {
+  scenario_id?: number;
+  name: string;
+  finish_date?: string;
+  capacity_hours: number;
+  allocated_hours: number;
+  overload_days: number;
+  overload_hours: number;
+  idle_days: number;
+  idle_hours: number;
+}
+
+export interface ScenarioTaskDiff {
+  task_id: number;
+  task_name: string;
+  left_owner_name?: string;
+  right_owner_name?: string;
+  left_start?: string;
+  right_start?: string;
+  left_end?: string;
+  right_end?: string;
+  /** 右侧比左侧晚结束的日历天数 */
+  end_delta_days?: number;
+}
+
+export interface ScenarioComparison {
+  start_date: string;
+  end_date: string;
+  left: ScenarioSummary;
+  right: ScenarioSummary;
+  tasks: ScenarioTaskDiff[];
+}
+
 // Calendar types
+/** task 为任务，其余为排期背景信息：日历调整、迭代、里程碑、节假日、补班、请假 */
+export type CalendarEventType = 'task' | 'override' | 'sprint' | 'milestone' | 'holiday' | 'makeup' | 'absence';
+
 export interface CalendarEvent {
   id: string;
   title: string;
@@ -157,12 +500,17 @@ export interface CalendarEvent {
   resourceId?: string;
   resource_id?: string;
   color?: string;
+  /** 'background' 用于日历调整等整日标记 */
+  display?: string;
+  /** 非任务事件为 false，不可拖拽 */
+  editable?: boolean;
   extendedProps?: CalendarEventExtProps;
   ext_props?: CalendarEventExtProps;
 }
 
 export interface CalendarEventExtProps {
-  task_id: number;
+  event_type?: CalendarEventType;
+  task_id?: number;
   task_type?: string;
   priority?: string;
   status?: string;
@@ -194,6 +542,103 @@ export interface WorkloadTask {
   task_id: number;
   task_name: string;
   daily_hours: number;
+  /** 手动固定的当日工时，不参与自动分配 */
+  pinned?: boolean;
+  /** 当日工时在工作时段内的排布 (HH:MM) */
+  start_time?: string;
+  end_time?: string;
+}
+
+export type ScheduleViolationKind =
+  | 'overload' | 'non_workday' | 'insufficient_capacity' | 'invalid_range' | 'outside_sprint' | 'dependency';
+
+/** 排期检查发现的问题；overload 针对成员某天，其余针对单个任务 */
+export interface ScheduleViolation {
+  kind: ScheduleViolationKind;
+  severity: 'error' | 'warning';
+  task_id?: number;
+  task_name?: string;
+  developer_id?: number;
+  developer_name?: string;
+  date?: string;
+  message: string;
+  suggestion: string;
+}
+
+/** 资源平衡预览：updates 可直接交给 batchApi 保存，remaining 为无法消除的超载 */
+export interface LevelingPlan {
+  changes: TaskMove[];
+  updates: UpdateTaskDto[];
+  remaining: ScheduleViolation[];
+}
+
+export type HolidaySource = 'auto' | 'timor' | 'bundled' | 'nager';
+
+/** 节假日缓存按地区/年份/来源汇总；source 为 timor / bundled / nager / file:<文件名> / unknown */
+export interface HolidayYearSummary {
+  region: string;
+  year: number;
+  source: string;
+  entries: number;
+  holidays: number;
+  makeup_workdays: number;
+}
+
+/** 工作日历：节假日地区（CN 为中国大陆调休安排，其它为 ISO 国家代码）+ 周末定义，0=周一 … 6=周日 */
+export interface RegionCalendar {
+  id: number;
+  name: string;
+  region: string;
+  weekend_days: number[];
+  is_default: boolean;
+}
+
+export interface CreateRegionCalendarDto {
+  name: string;
+  region: string;
+  weekend_days?: number[];
+  is_default?: boolean;
+}
+
+export interface UpdateRegionCalendarDto {
+  id: number;
+  name?: string;
+  region?: string;
+  weekend_days?: number[];
+  is_default?: boolean;
+}
+
+/** 手动调整的休息日/工作日，优先于同步的节假日数据；calendar_id 为空表示对所有日历生效 */
+export interface CalendarOverride {
+  id: number;
+  calendar_id?: number;
+  date: string;
+  is_workday: boolean;
+  name?: string;
+}
+
+export interface CreateCalendarOverrideDto {
+  calendar_id?: number;
+  date: string;
+  is_workday: boolean;
+  name?: string;
+}
+
+export interface UpdateCalendarOverrideDto {
+  id: number;
+  date?: string;
+  is_workday?: boolean;
+  name?: string;
+}
+
+/** 在现有排期基础上插入 N 小时新任务的最早开始/完成估算 */
+export interface AvailabilityEstimate {
+  developer_id: number;
+  developer_name: string;
+  hours: number;
+  earliest_start?: string;
+  earliest_finish?: string;
+  workdays_used: number;
 }
 
 // Excel types
@@ -209,6 +654,8 @@ export interface SheetInfo {
   headers: string[];
   sample_rows: string[][];
   is_hidden: boolean;
+  header_row: number;
+  header_height: number;
 }
 
 export interface SheetScore {
@@ -227,6 +674,7 @@ export interface ColumnMatch {
 }
 
 export interface ImportResult {
+  import_id: number;
   rows_imported: number;
   rows_updated: number;
   rows_skipped: number;
@@ -245,6 +693,47 @@ export interface ImportConflict {
   match_type: string;
 }
 
+export interface FieldChange {
+  field: string;
+  old_value?: string | null;
+  new_value: string;
+}
+
+export interface ImportIssue {
+  field?: string | null;
+  level: 'error' | 'warning' | 'info';
+  message: string;
+}
+
+export interface ImportRowPreview {
+  row_index: number;
+  name: string;
+  action: 'create' | 'update' | 'skip';
+  existing_task_id?: number | null;
+  changes: FieldChange[];
+  issues: ImportIssue[];
+}
+
+export interface ImportDryRun {
+  rows: ImportRowPreview[];
+  rows_to_create: number;
+  rows_to_update: number;
+  rows_to_skip: number;
+  developers_to_create: string[];
+  sprints_to_create: string[];
+  unmapped_values: UnmappedValue[];
+}
+
+export interface UnmappedValue {
+  field: 'task_type' | 'priority' | 'status';
+  value: string;
+  count: number;
+  suggestion?: string;
+}
+
+/** Per field: spelling in the spreadsheet -> canonical value. */
+export type ValueMappings = Record<string, Record<string, string>>;
+
 export interface ImportHistory {
   id: number;
   file_name: string;
@@ -253,6 +742,18 @@ export interface ImportHistory {
   sheet_name?: string;
   column_mapping?: string;
   rows_imported: number;
+  rows_updated: number;
+  rolled_back_at?: string | null;
+}
+
+export interface ImportRollback {
+  import_id: number;
+  tasks_deleted: number;
+  tasks_restored: number;
+  developers_deleted: number;
+  sprints_deleted: number;
+  kept: string[];
+  edited: string[];
 }
 
 // Enum values
@@ -300,9 +801,15 @@ export const PRIORITY_COLORS: Record<string, string> = {
 export interface WorkHoursConfig {
   display_unit: 'day' | 'hour';
   hours_per_day: number;
+  /** 每日工作时段与午休 (HH:MM) */
+  day_start: string;
+  day_end: string;
+  break_start: string;
+  break_end: string;
 }
 
 // Overtime config
+/** 加班设置：全局保存在 schedule.overtime_days；成员可单独设置，设置后替代全局规则 */
 export interface OvertimeConfig {
   weekend: 'none' | 'saturday' | 'sunday' | 'both';
   custom_dates: string[];
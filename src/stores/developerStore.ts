@@ -1,6 +1,8 @@
 import { create } from 'zustand';
 import { developerApi } from '../lib/api';
-import type { Developer, CreateDeveloperDto, UpdateDeveloperDto } from '../lib/types';
+import type {
+  Developer, CreateDeveloperDto, UpdateDeveloperDto, OffboardDeveloperDto, OffboardReport,
+} from '../lib/types';
 
 interface DeveloperState {
   developers: Developer[];
@@ -15,6 +17,7 @@ interface DeveloperState {
   createDeveloper: (dto: CreateDeveloperDto) => Promise<number>;
   updateDeveloper: (dto: UpdateDeveloperDto) => Promise<void>;
   deleteDeveloper: (id: number) => Promise<void>;
+  offboardDeveloper: (dto: OffboardDeveloperDto) => Promise<OffboardReport>;
 }
 
 export const useDeveloperStore = create<DeveloperState>((set, get) => ({
@@ -54,4 +57,10 @@ export const useDeveloperStore = create<DeveloperState>((set, get) => ({
     set({ selectedDeveloper: null });
     await get().fetchDevelopers();
   },
+
+  offboardDeveloper: async (dto) => {
+    const report = await developerApi.offboard(dto);
+    await get().fetchDevelopers();
+    return report;
+  },
 }));
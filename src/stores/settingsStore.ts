@@ -22,6 +22,10 @@ interface SettingsState {
 const DEFAULT_WORK_HOURS: WorkHoursConfig = {
   display_unit: 'day',
   hours_per_day: 8,
+  day_start: '09:00',
+  day_end: '18:00',
+  break_start: '12:00',
+  break_end: '13:00',
 };
 
 const DEFAULT_OVERTIME: OvertimeConfig = {
@@ -81,10 +85,18 @@ export const useSettingsStore = create<SettingsState>((set) => ({
     try {
       const unitStr = await settingsApi.getSetting('work_hours.display_unit');
       const hpdStr = await settingsApi.getSetting('work_hours.hours_per_day');
+      const dayStart = await settingsApi.getSetting('work_hours.day_start');
+      const dayEnd = await settingsApi.getSetting('work_hours.day_end');
+      const breakStart = await settingsApi.getSetting('work_hours.break_start');
+      const breakEnd = await settingsApi.getSetting('work_hours.break_end');
       set({
         workHoursConfig: {
           display_unit: (unitStr === 'hour' ? 'hour' : 'day') as 'day' | 'hour',
           hours_per_day: hpdStr ? parseFloat(hpdStr) : 8,
+          day_start: dayStart || DEFAULT_WORK_HOURS.day_start,
+          day_end: dayEnd || DEFAULT_WORK_HOURS.day_end,
+          break_start: breakStart || DEFAULT_WORK_HOURS.break_start,
+          break_end: breakEnd || DEFAULT_WORK_HOURS.break_end,
         },
       });
     } catch {
@@ -96,6 +108,10 @@ export const useSettingsStore = create<SettingsState>((set) => ({
     try {
       await settingsApi.saveSetting('work_hours.display_unit', config.display_unit, 'work_hours');
       await settingsApi.saveSetting('work_hours.hours_per_day', String(config.hours_per_day), 'work_hours');
+      await settingsApi.saveSetting('work_hours.day_start', config.day_start, 'work_hours');
+      await settingsApi.saveSetting('work_hours.day_end', config.day_end, 'work_hours');
+      await settingsApi.saveSetting('work_hours.break_start', config.break_start, 'work_hours');
+      await settingsApi.saveSetting('work_hours.break_end', config.break_end, 'work_hours');
       set({ workHoursConfig: config });
     } catch (e: any) {
       set({ error: e.toString() });